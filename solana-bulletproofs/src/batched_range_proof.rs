@@ -0,0 +1,182 @@
+//! Batched range proofs whose bit lengths sum to a fixed total.
+//!
+//! `BatchedRangeProofU64`, `BatchedRangeProofU128` and `BatchedRangeProofU256` mirror the batched
+//! range proofs of the zk-token SDK: each proves that several commitments open to values in
+//! `[0, 2^(n_j))` for bit lengths `n_j` that sum to 64, 128 or 256. They are thin wrappers around
+//! the batched `RangeProof`, with a fixed total and a fixed serialized size. Their encoding is the
+//! encoding of `RangeProof` with a type tag of their own.
+
+use {
+    crate::{
+        encoding::{ProofBytes, ProofType, HEADER_SIZE},
+        generators::{BulletproofGens, PedersenGens},
+        range_proof::{RangeProof, RangeProofView},
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::ristretto::RistrettoPoint,
+};
+#[cfg(not(target_os = "solana"))]
+use {
+    rand_core::{CryptoRng, OsRng, RngCore},
+    solana_ristretto::scalar::Scalar,
+};
+
+macro_rules! define_batched_range_proof {
+    (
+        $(#[$meta:meta])* $name:ident, $view:ident, $proof_type:ident, $bit_length:literal,
+        $size:literal
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct $name(RangeProof);
+
+        impl $name {
+            /// The sum of the bit lengths of the proven values.
+            pub const BIT_LENGTH: usize = $bit_length;
+
+            /// The size of the serialized proof in bytes.
+            pub const SIZE: usize = $size;
+
+            /// Create a proof that every value `values[j]` is in `[0, 2^bit_lengths[j])` with
+            /// randomness from the operating system.
+            ///
+            /// Returns the proof together with the commitments to the values.
+            #[cfg(not(target_os = "solana"))]
+            pub fn new(
+                bp_gens: &BulletproofGens,
+                pc_gens: &PedersenGens,
+                transcript: &mut Transcript,
+                values: &[u64],
+                blindings: &[Scalar],
+                bit_lengths: &[usize],
+            ) -> Result<(Self, Vec<RistrettoPoint>), String> {
+                Self::new_with_rng(
+                    bp_gens,
+                    pc_gens,
+                    transcript,
+                    values,
+                    blindings,
+                    bit_lengths,
+                    &mut OsRng,
+                )
+            }
+
+            /// Create a proof that every value `values[j]` is in `[0, 2^bit_lengths[j])` with
+            /// randomness from `rng`.
+            #[cfg(not(target_os = "solana"))]
+            pub fn new_with_rng<R: RngCore + CryptoRng>(
+                bp_gens: &BulletproofGens,
+                pc_gens: &PedersenGens,
+                transcript: &mut Transcript,
+                values: &[u64],
+                blindings: &[Scalar],
+                bit_lengths: &[usize],
+                rng: &mut R,
+            ) -> Result<(Self, Vec<RistrettoPoint>), String> {
+                check_total(bit_lengths.iter().copied(), Self::BIT_LENGTH)?;
+                let (proof, value_commitments) = RangeProof::prove_batched_with_rng(
+                    bp_gens,
+                    pc_gens,
+                    transcript,
+                    values,
+                    blindings,
+                    bit_lengths,
+                    rng,
+                )?;
+                Ok((Self(proof), value_commitments))
+            }
+
+            /// Verify the proof for the `(commitment, bit_length)` pairs in `commitments`.
+            pub fn verify(
+                &self,
+                bp_gens: &BulletproofGens,
+                pc_gens: &PedersenGens,
+                transcript: &mut Transcript,
+                commitments: &[(RistrettoPoint, usize)],
+            ) -> Result<(), String> {
+                check_total(commitments.iter().map(|(_, n)| *n), Self::BIT_LENGTH)?;
+                self.0.verify_batched(bp_gens, pc_gens, transcript, commitments)
+            }
+
+            /// Serialize the proof in the format of `RangeProof::to_bytes`, with the type tag
+            /// of this proof.
+            pub fn to_bytes(&self) -> [u8; $size] {
+                let mut buf = [0; $size];
+                buf.copy_from_slice(&self.0.to_bytes_with_type(ProofType::$proof_type));
+                buf
+            }
+
+            /// Deserialize a proof that was serialized with `to_bytes` by any version of the
+            /// encoding.
+            pub fn from_bytes(slice: &[u8]) -> Result<Self, String> {
+                $view::from_bytes(slice).map(|view| view.to_proof())
+            }
+        }
+
+        #[doc = concat!("A validated `", stringify!($name), "` that borrows its encoding.")]
+        ///
+        /// `from_bytes` validates the encoding like the owned proof, but does not copy the body:
+        /// the verifier reads every point and scalar from the borrowed bytes.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct $view<'a>(RangeProofView<'a>);
+
+        impl<'a> $view<'a> {
+            /// Validate a proof that was serialized with `to_bytes` by any version of the
+            /// encoding, and borrow it.
+            pub fn from_bytes(slice: &'a [u8]) -> Result<Self, String> {
+                let body = ProofBytes::body_of(slice, ProofType::$proof_type)?;
+                if body.len() != $name::SIZE - HEADER_SIZE {
+                    return Err("Batched range proof has an invalid length".to_string());
+                }
+                RangeProofView::from_body(body).map(Self)
+            }
+
+            #[doc = concat!("Verify the proof as `", stringify!($name), "::verify` does.")]
+            pub fn verify(
+                &self,
+                bp_gens: &BulletproofGens,
+                pc_gens: &PedersenGens,
+                transcript: &mut Transcript,
+                commitments: &[(RistrettoPoint, usize)],
+            ) -> Result<(), String> {
+                check_total(commitments.iter().map(|(_, n)| *n), $name::BIT_LENGTH)?;
+                self.0.verify_batched(bp_gens, pc_gens, transcript, commitments)
+            }
+
+            /// Copy the proof into an owned proof.
+            pub fn to_proof(&self) -> $name {
+                $name(self.0.to_proof())
+            }
+        }
+
+        impl From<$name> for RangeProof {
+            fn from(proof: $name) -> Self {
+                proof.0
+            }
+        }
+    };
+}
+
+define_batched_range_proof!(
+    /// A range proof for values whose bit lengths sum to 64.
+    BatchedRangeProofU64, BatchedRangeProofU64View, BatchedRangeU64, 64, 674
+);
+define_batched_range_proof!(
+    /// A range proof for values whose bit lengths sum to 128.
+    BatchedRangeProofU128, BatchedRangeProofU128View, BatchedRangeU128, 128, 738
+);
+define_batched_range_proof!(
+    /// A range proof for values whose bit lengths sum to 256.
+    BatchedRangeProofU256, BatchedRangeProofU256View, BatchedRangeU256, 256, 802
+);
+
+/// Check that the bit lengths sum to `total`.
+fn check_total(bit_lengths: impl Iterator<Item = usize>, total: usize) -> Result<(), String> {
+    if bit_lengths.sum::<usize>() != total {
+        return Err(format!(
+            "Batched range proof bit lengths must sum to {}",
+            total
+        ));
+    }
+    Ok(())
+}
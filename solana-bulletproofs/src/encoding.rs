@@ -0,0 +1,132 @@
+//! Versioned binary encoding of proofs.
+//!
+//! Every proof serializes as `version || type || body`, where `version` is the one-byte
+//! `ENCODING_VERSION`, `type` is the one-byte `ProofType` tag, and `body` is a sequence of 32-byte
+//! points and scalars. Deserialization requires the exact length of the proof type, valid
+//! Ristretto encodings for all points and canonical encodings for all scalars.
+//!
+//! The body of every version is frozen, so proofs serialized by an older version of the crate stay
+//! readable: `from_bytes` accepts every version up to `ENCODING_VERSION`. The header is always
+//! required.
+//!
+//! `ProofBytes::parse` reads the header and borrows the body, so that a program can dispatch on the
+//! type of a proof in its instruction data before it deserializes the proof.
+//!
+//! Parsing is zero-copy with the views `InnerProductProofView`, `RangeProofView`, `R1CSProofView`
+//! and the views of the batched range proofs. Their `from_bytes` validates every point and scalar
+//! of the body in place, and their verifiers read the elements from the borrowed bytes. The
+//! `from_bytes` of an owned proof validates the same way and copies the body into the proof.
+
+use solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar};
+
+/// The current version of the encoding.
+pub const ENCODING_VERSION: u8 = 1;
+
+/// The size of the header `version || type` in bytes.
+pub const HEADER_SIZE: usize = 2;
+
+/// The type tag of a serialized proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ProofType {
+    /// An `InnerProductProof`
+    InnerProduct = 1,
+    /// A `RangeProof`
+    Range = 2,
+    /// A `BatchedRangeProofU64`
+    BatchedRangeU64 = 3,
+    /// A `BatchedRangeProofU128`
+    BatchedRangeU128 = 4,
+    /// A `BatchedRangeProofU256`
+    BatchedRangeU256 = 5,
+    /// An `R1CSProof`
+    R1CS = 6,
+    /// An `R1CSProof` with randomized constraints
+    R1CSRandomized = 7,
+}
+
+impl TryFrom<u8> for ProofType {
+    type Error = String;
+
+    fn try_from(tag: u8) -> Result<Self, Self::Error> {
+        match tag {
+            1 => Ok(ProofType::InnerProduct),
+            2 => Ok(ProofType::Range),
+            3 => Ok(ProofType::BatchedRangeU64),
+            4 => Ok(ProofType::BatchedRangeU128),
+            5 => Ok(ProofType::BatchedRangeU256),
+            6 => Ok(ProofType::R1CS),
+            7 => Ok(ProofType::R1CSRandomized),
+            _ => Err("Proof encoding has an unknown proof type".to_string()),
+        }
+    }
+}
+
+/// A serialized proof whose header has been read, borrowing its body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofBytes<'a> {
+    /// The version of the encoding
+    pub version: u8,
+    /// The type of the proof
+    pub proof_type: ProofType,
+    /// The body of the proof, which has not been validated yet
+    pub body: &'a [u8],
+}
+
+impl<'a> ProofBytes<'a> {
+    /// Read the header of a serialized proof without copying the body.
+    ///
+    /// The version must be supported and the body must consist of 32-byte elements.
+    pub fn parse(slice: &'a [u8]) -> Result<Self, String> {
+        if slice.len() < HEADER_SIZE {
+            return Err("Proof encoding is missing its header".to_string());
+        }
+        let (header, body) = slice.split_at(HEADER_SIZE);
+        let version = header[0];
+        if version == 0 || version > ENCODING_VERSION {
+            return Err("Proof encoding has an unsupported version".to_string());
+        }
+        let proof_type = ProofType::try_from(header[1])?;
+        if body.len() / 32 * 32 != body.len() {
+            return Err("Proof encoding has an invalid length".to_string());
+        }
+
+        Ok(ProofBytes {
+            version,
+            proof_type,
+            body,
+        })
+    }
+
+    /// Read the body of a serialized proof of type `proof_type`.
+    pub(crate) fn body_of(slice: &'a [u8], proof_type: ProofType) -> Result<&'a [u8], String> {
+        Self::body_of_any(slice, &[proof_type]).map(|(body, _)| body)
+    }
+
+    /// Read the body of a serialized proof of one of the types `proof_types`, and its type.
+    pub(crate) fn body_of_any(
+        slice: &'a [u8],
+        proof_types: &[ProofType],
+    ) -> Result<(&'a [u8], ProofType), String> {
+        let proof = Self::parse(slice)?;
+        if !proof_types.contains(&proof.proof_type) {
+            return Err("Proof encoding has an unexpected proof type".to_string());
+        }
+        Ok((proof.body, proof.proof_type))
+    }
+}
+
+/// The header of a proof of type `proof_type` in the current version.
+pub(crate) fn header(proof_type: ProofType) -> [u8; HEADER_SIZE] {
+    [ENCODING_VERSION, proof_type as u8]
+}
+
+/// The element `index` of a body as a point, which the caller has validated.
+pub(crate) fn point_at(body: &[u8], index: usize) -> RistrettoPoint {
+    RistrettoPoint::from_bytes(&body[32 * index..32 * (index + 1)]).unwrap()
+}
+
+/// The element `index` of a body as a scalar, which the caller has validated.
+pub(crate) fn scalar_at(body: &[u8], index: usize) -> Scalar {
+    Scalar::from_bytes(&body[32 * index..32 * (index + 1)]).unwrap()
+}
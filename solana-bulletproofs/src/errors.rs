@@ -0,0 +1,66 @@
+//! Errors of the multi-party computation of aggregated range proofs.
+//!
+//! The rest of the crate reports errors as `String`s. The multi-party computation has its own
+//! error type, so that the dealer can blame the parties that sent malformed messages.
+
+use std::fmt;
+
+/// An error during the multi-party computation of an aggregated range proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MPCError {
+    /// The number of parties is not a power of two, or the generators do not support it.
+    InvalidAggregation,
+    /// The bit size is not supported, or the generators do not support it.
+    InvalidBitsize,
+    /// The value of a party does not fit into the bit size.
+    ValueOutOfRange,
+    /// The dealer received a wrong number of bit commitments.
+    WrongNumBitCommitments,
+    /// The dealer received a wrong number of polynomial commitments.
+    WrongNumPolyCommitments,
+    /// The dealer received a wrong number of proof shares.
+    WrongNumProofShares,
+    /// A challenge of the dealer is zero, which would reveal the blinding of a party.
+    MaliciousDealer,
+    /// The proof shares of the parties with the given positions are malformed.
+    MalformedProofShares {
+        /// The positions of the parties whose shares are malformed
+        bad_shares: Vec<usize>,
+    },
+    /// An error of the underlying proof arithmetic or serialization.
+    ProofError(String),
+}
+
+impl fmt::Display for MPCError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MPCError::InvalidAggregation => write!(f, "Invalid number of parties"),
+            MPCError::InvalidBitsize => write!(f, "Invalid bit size"),
+            MPCError::ValueOutOfRange => write!(f, "Value is out of range"),
+            MPCError::WrongNumBitCommitments => write!(f, "Wrong number of bit commitments"),
+            MPCError::WrongNumPolyCommitments => {
+                write!(f, "Wrong number of polynomial commitments")
+            }
+            MPCError::WrongNumProofShares => write!(f, "Wrong number of proof shares"),
+            MPCError::MaliciousDealer => write!(f, "Dealer sent a zero challenge"),
+            MPCError::MalformedProofShares { bad_shares } => {
+                write!(f, "Malformed proof shares from parties {:?}", bad_shares)
+            }
+            MPCError::ProofError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for MPCError {}
+
+impl From<String> for MPCError {
+    fn from(err: String) -> Self {
+        MPCError::ProofError(err)
+    }
+}
+
+impl From<MPCError> for String {
+    fn from(err: MPCError) -> Self {
+        err.to_string()
+    }
+}
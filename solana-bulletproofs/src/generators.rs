@@ -0,0 +1,260 @@
+//! Generators for Pedersen commitments and vector commitments.
+//!
+//! Generators that are derived from a label are computed with `hash_to_point`, which only uses
+//! SHA-256 and Ristretto point validation. Both are available as syscalls on-chain, so the
+//! derived generators are the same on-chain and off-chain.
+
+#![allow(non_snake_case)]
+
+use {
+    solana_program::hash::hashv,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// The domain separator of `hash_to_point`.
+const HASH_TO_POINT_DOMAIN: &[u8] = b"solana-bulletproofs hash-to-point";
+
+/// The compressed Ristretto basepoint.
+const RISTRETTO_BASEPOINT_BYTES: [u8; 32] = [
+    226, 242, 174, 10, 106, 188, 78, 113, 168, 132, 169, 97, 197, 0, 81, 95, 88, 227, 11, 106, 165,
+    130, 221, 141, 182, 166, 89, 69, 224, 141, 45, 118,
+];
+
+/// The compressed blinding base of dalek-bulletproofs, which is the Ristretto basepoint hashed to
+/// a point with `RistrettoPoint::hash_from_bytes::<Sha3_512>`.
+const DALEK_BLINDING_BASE_BYTES: [u8; 32] = [
+    140, 146, 64, 180, 86, 169, 230, 220, 101, 195, 119, 161, 4, 141, 116, 95, 148, 160, 140, 219,
+    127, 68, 203, 205, 123, 70, 243, 64, 72, 135, 17, 52,
+];
+
+/// Map a sequence of byte strings to a Ristretto point with unknown discrete logarithm.
+///
+/// The point is found by try-and-increment. For `counter = 0, 1, ...`, the candidate is
+///
+/// ```text
+/// H("solana-bulletproofs hash-to-point" || LE32(len(input_0)) || input_0 || ... || LE32(counter))
+/// ```
+///
+/// with the lowest bit of its first byte and the highest bit of its last byte cleared, where `H`
+/// is SHA-256. The first candidate that is a valid Ristretto encoding is returned. Every valid
+/// encoding has both bits cleared, so clearing them raises the chance that a candidate is valid.
+pub(crate) fn hash_to_point(inputs: &[&[u8]]) -> RistrettoPoint {
+    let lengths = inputs
+        .iter()
+        .map(|input| (input.len() as u32).to_le_bytes())
+        .collect::<Vec<_>>();
+
+    let mut counter = 0_u32;
+    loop {
+        let counter_bytes = counter.to_le_bytes();
+        let mut hash_inputs = vec![HASH_TO_POINT_DOMAIN];
+        for (length, input) in lengths.iter().zip(inputs) {
+            hash_inputs.push(length);
+            hash_inputs.push(input);
+        }
+        hash_inputs.push(&counter_bytes);
+
+        let mut candidate = hashv(&hash_inputs).to_bytes();
+        candidate[0] &= 0xfe;
+        candidate[31] &= 0x7f;
+
+        let point = RistrettoPoint::from_bytes(&candidate).unwrap();
+        if point.is_valid() {
+            return point;
+        }
+        counter = counter
+            .checked_add(1)
+            .expect("hash-to-point did not find a valid point");
+    }
+}
+
+/// The generators `B` and `B_blinding` of Pedersen commitments `value * B + blinding * B_blinding`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PedersenGens {
+    /// The base for the committed value.
+    B: RistrettoPoint,
+    /// The base for the blinding factor.
+    B_blinding: RistrettoPoint,
+}
+
+impl PedersenGens {
+    /// Create Pedersen generators from custom bases.
+    ///
+    /// The discrete logarithm of `B_blinding` with respect to `B` must be unknown. Both bases must
+    /// be valid Ristretto points, since every commitment and verification multiplies them.
+    pub fn new(B: RistrettoPoint, B_blinding: RistrettoPoint) -> Result<Self, String> {
+        if !B.is_valid() || !B_blinding.is_valid() {
+            return Err("Pedersen generators are not valid points".to_string());
+        }
+        Ok(Self { B, B_blinding })
+    }
+
+    /// Derive Pedersen generators from a label with `hash_to_point`.
+    ///
+    /// Distinct labels give independent generators.
+    pub fn from_label(label: &[u8]) -> Self {
+        Self {
+            B: hash_to_point(&[b"PedersenGens B", label]),
+            B_blinding: hash_to_point(&[b"PedersenGens B_blinding", label]),
+        }
+    }
+
+    /// The base for the committed value.
+    pub fn B(&self) -> RistrettoPoint {
+        self.B
+    }
+
+    /// The base for the blinding factor.
+    pub fn B_blinding(&self) -> RistrettoPoint {
+        self.B_blinding
+    }
+
+    /// Create a Pedersen commitment `value * B + blinding * B_blinding`.
+    pub fn commit(&self, value: Scalar, blinding: Scalar) -> Result<RistrettoPoint, String> {
+        RistrettoPoint::multiscalar_multiply(&[value, blinding], &[self.B, self.B_blinding])
+    }
+}
+
+impl Default for PedersenGens {
+    /// The generators of dalek-bulletproofs: the Ristretto basepoint and the basepoint hashed to a
+    /// point with SHA3-512.
+    fn default() -> Self {
+        Self {
+            B: RistrettoPoint::from_bytes(&RISTRETTO_BASEPOINT_BYTES).unwrap(),
+            B_blinding: RistrettoPoint::from_bytes(&DALEK_BLINDING_BASE_BYTES).unwrap(),
+        }
+    }
+}
+
+/// A chain of independent generators for one party, derived from a label with `hash_to_point`.
+///
+/// The `i`-th generator of party `j` for the label `label` is
+/// `hash_to_point(["GeneratorsChain", label, LE32(j), LE32(i)])`. Every generator only depends on
+/// its own index, so a chain can be extended without recomputing earlier generators.
+struct GeneratorsChain<'a> {
+    label: &'a [u8],
+    party: u32,
+    index: u32,
+}
+
+impl<'a> GeneratorsChain<'a> {
+    /// Create the chain of party `party` that starts at the generator with index `index`.
+    fn new(label: &'a [u8], party: usize, index: usize) -> Self {
+        Self {
+            label,
+            party: party as u32,
+            index: index as u32,
+        }
+    }
+}
+
+impl Iterator for GeneratorsChain<'_> {
+    type Item = RistrettoPoint;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = hash_to_point(&[
+            b"GeneratorsChain",
+            self.label,
+            &self.party.to_le_bytes(),
+            &self.index.to_le_bytes(),
+        ]);
+        self.index = self.index.checked_add(1)?;
+        Some(point)
+    }
+}
+
+/// The generator vectors `G` and `H` of range proofs and inner product arguments.
+///
+/// Every party of an aggregated proof uses its own `gens_capacity` generators of `G` and `H`,
+/// which are derived with the chains labeled `G` and `H`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulletproofGens {
+    /// The number of generators per party.
+    pub gens_capacity: usize,
+    /// The maximum number of parties that can produce an aggregated proof.
+    pub party_capacity: usize,
+    /// The `G` generators of every party.
+    G_vec: Vec<Vec<RistrettoPoint>>,
+    /// The `H` generators of every party.
+    H_vec: Vec<Vec<RistrettoPoint>>,
+}
+
+impl BulletproofGens {
+    /// Create `gens_capacity` generators of `G` and `H` for each of `party_capacity` parties.
+    pub fn new(gens_capacity: usize, party_capacity: usize) -> Self {
+        let mut gens = Self {
+            gens_capacity: 0,
+            party_capacity,
+            G_vec: vec![Vec::new(); party_capacity],
+            H_vec: vec![Vec::new(); party_capacity],
+        };
+        gens.increase_capacity(gens_capacity);
+        gens
+    }
+
+    /// Return the generators of party `j`.
+    pub fn share(&self, j: usize) -> BulletproofGensShare<'_> {
+        assert!(
+            j < self.party_capacity,
+            "party index exceeds party capacity"
+        );
+        BulletproofGensShare {
+            gens: self,
+            share: j,
+        }
+    }
+
+    /// Increase the number of generators per party to `new_capacity`.
+    ///
+    /// Only the new generators are derived. Nothing happens if the capacity is already at least
+    /// `new_capacity`.
+    pub fn increase_capacity(&mut self, new_capacity: usize) {
+        if self.gens_capacity >= new_capacity {
+            return;
+        }
+
+        let additional = new_capacity - self.gens_capacity;
+        for (party, G) in self.G_vec.iter_mut().enumerate() {
+            G.extend(GeneratorsChain::new(b"G", party, self.gens_capacity).take(additional));
+        }
+        for (party, H) in self.H_vec.iter_mut().enumerate() {
+            H.extend(GeneratorsChain::new(b"H", party, self.gens_capacity).take(additional));
+        }
+        self.gens_capacity = new_capacity;
+    }
+
+    /// The first `n` generators of `G` of each of the first `m` parties, party by party.
+    pub fn G(&self, n: usize, m: usize) -> impl Iterator<Item = &RistrettoPoint> {
+        assert!(n <= self.gens_capacity, "n exceeds generator capacity");
+        assert!(m <= self.party_capacity, "m exceeds party capacity");
+        self.G_vec[..m].iter().flat_map(move |G| G[..n].iter())
+    }
+
+    /// The first `n` generators of `H` of each of the first `m` parties, party by party.
+    pub fn H(&self, n: usize, m: usize) -> impl Iterator<Item = &RistrettoPoint> {
+        assert!(n <= self.gens_capacity, "n exceeds generator capacity");
+        assert!(m <= self.party_capacity, "m exceeds party capacity");
+        self.H_vec[..m].iter().flat_map(move |H| H[..n].iter())
+    }
+}
+
+/// The generators of a single party of an aggregated proof.
+#[derive(Clone, Copy, Debug)]
+pub struct BulletproofGensShare<'a> {
+    gens: &'a BulletproofGens,
+    share: usize,
+}
+
+impl<'a> BulletproofGensShare<'a> {
+    /// The first `n` generators of `G` of the party.
+    pub fn G(&self, n: usize) -> impl Iterator<Item = &'a RistrettoPoint> {
+        assert!(n <= self.gens.gens_capacity, "n exceeds generator capacity");
+        self.gens.G_vec[self.share][..n].iter()
+    }
+
+    /// The first `n` generators of `H` of the party.
+    pub fn H(&self, n: usize) -> impl Iterator<Item = &'a RistrettoPoint> {
+        assert!(n <= self.gens.gens_capacity, "n exceeds generator capacity");
+        self.gens.H_vec[self.share][..n].iter()
+    }
+}
@@ -0,0 +1,490 @@
+//! The logarithmic-size inner product argument of the Bulletproofs paper.
+//!
+//! The prover convinces the verifier that it knows vectors `a` and `b` of length `n` such that
+//!
+//! ```text
+//! P = <a * G_factors, G> + <b * H_factors, H> + <a, b> * Q
+//! ```
+//!
+//! where `G_factors` and `H_factors` are public scalars that the verifier folds into its single
+//! multiscalar multiplication instead of rescaling the generators. The range proof uses this to
+//! multiply `H` with powers of `y^-1`.
+//!
+//! The prover only runs off-chain. The verifier runs on all targets: it computes all challenges,
+//! inverts them in one batch, and checks the proof with one multiscalar multiplication of size
+//! `2n + 2 lg(n) + 2`.
+
+#![allow(non_snake_case)]
+
+#[cfg(not(target_os = "solana"))]
+use crate::util;
+use {
+    crate::{
+        encoding::{self, ProofBytes, ProofType, HEADER_SIZE},
+        transcript::{self as schedule, inner_product_transcript},
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+    std::borrow::Borrow,
+};
+
+/// An inner product argument for vectors of length `n`, with `lg(n)` rounds of folding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InnerProductProof {
+    pub(crate) L_vec: Vec<RistrettoPoint>,
+    pub(crate) R_vec: Vec<RistrettoPoint>,
+    pub(crate) a: Scalar,
+    pub(crate) b: Scalar,
+}
+
+impl InnerProductProof {
+    /// Create an inner product argument for `a_vec` and `b_vec` with respect to the generators
+    /// `G_vec` and `H_vec`, scaled by `G_factors` and `H_factors`.
+    ///
+    /// All vectors must have the same length, which must be a power of two.
+    #[cfg(not(target_os = "solana"))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        transcript: &mut Transcript,
+        Q: &RistrettoPoint,
+        G_factors: &[Scalar],
+        H_factors: &[Scalar],
+        G_vec: Vec<RistrettoPoint>,
+        H_vec: Vec<RistrettoPoint>,
+        a_vec: Vec<Scalar>,
+        b_vec: Vec<Scalar>,
+    ) -> Result<Self, String> {
+        let mut n = G_vec.len();
+        if H_vec.len() != n
+            || a_vec.len() != n
+            || b_vec.len() != n
+            || G_factors.len() != n
+            || H_factors.len() != n
+        {
+            return Err("Inner product vectors have different lengths".to_string());
+        }
+        if !n.is_power_of_two() {
+            return Err("Inner product vector length is not a power of two".to_string());
+        }
+
+        // Folding the factors into the generators gives the same proof as folding them round by
+        // round, since the prover is not constrained by the compute budget.
+        let mut G = G_vec
+            .iter()
+            .zip(G_factors)
+            .map(|(G_i, g_i)| G_i.multiply(g_i))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut H = H_vec
+            .iter()
+            .zip(H_factors)
+            .map(|(H_i, h_i)| H_i.multiply(h_i))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut a = a_vec;
+        let mut b = b_vec;
+
+        let lg_n = n.trailing_zeros() as usize;
+        let mut L_vec = Vec::with_capacity(lg_n);
+        let mut R_vec = Vec::with_capacity(lg_n);
+
+        let mut transcript = inner_product_transcript(transcript, n as u64);
+        while n != 1 {
+            n /= 2;
+            let (a_L, a_R) = a.split_at(n);
+            let (b_L, b_R) = b.split_at(n);
+            let (G_L, G_R) = G.split_at(n);
+            let (H_L, H_R) = H.split_at(n);
+
+            let c_L = util::inner_product(a_L, b_R).unwrap();
+            let c_R = util::inner_product(a_R, b_L).unwrap();
+
+            let L_scalars = [a_L, b_R, &[c_L]].concat();
+            let L_points = [G_R, H_L, &[*Q]].concat();
+            let L = RistrettoPoint::multiscalar_multiply(&L_scalars, &L_points)?;
+
+            let R_scalars = [a_R, b_L, &[c_R]].concat();
+            let R_points = [G_L, H_R, &[*Q]].concat();
+            let R = RistrettoPoint::multiscalar_multiply(&R_scalars, &R_points)?;
+
+            let (next, u) = transcript
+                .append::<schedule::L>(&L)
+                .append::<schedule::R>(&R)
+                .challenge_scalar::<schedule::U>();
+            transcript = next;
+            let u_inv = u.invert()?;
+            L_vec.push(L);
+            R_vec.push(R);
+
+            let mut a_next = Vec::with_capacity(n);
+            let mut b_next = Vec::with_capacity(n);
+            let mut G_next = Vec::with_capacity(n);
+            let mut H_next = Vec::with_capacity(n);
+            for i in 0..n {
+                a_next.push(
+                    a_L[i]
+                        .multiply_scalar(u)?
+                        .add_scalar(a_R[i].multiply_scalar(u_inv)?)?,
+                );
+                b_next.push(
+                    b_L[i]
+                        .multiply_scalar(u_inv)?
+                        .add_scalar(b_R[i].multiply_scalar(u)?)?,
+                );
+                G_next.push(RistrettoPoint::multiscalar_multiply(
+                    &[u_inv, u],
+                    &[G_L[i], G_R[i]],
+                )?);
+                H_next.push(RistrettoPoint::multiscalar_multiply(
+                    &[u, u_inv],
+                    &[H_L[i], H_R[i]],
+                )?);
+            }
+            a = a_next;
+            b = b_next;
+            G = G_next;
+            H = H_next;
+        }
+
+        Ok(Self {
+            L_vec,
+            R_vec,
+            a: a[0],
+            b: b[0],
+        })
+    }
+
+    /// Verify the proof for the commitment `P` with respect to the generators `G` and `H`, scaled
+    /// by `G_factors` and `H_factors`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify<IG, IH>(
+        &self,
+        n: usize,
+        transcript: &mut Transcript,
+        G_factors: IG,
+        H_factors: IH,
+        P: &RistrettoPoint,
+        Q: &RistrettoPoint,
+        G: &[RistrettoPoint],
+        H: &[RistrettoPoint],
+    ) -> Result<(), String>
+    where
+        IG: IntoIterator,
+        IG::Item: Borrow<Scalar>,
+        IH: IntoIterator,
+        IH::Item: Borrow<Scalar>,
+    {
+        verify(self, n, transcript, G_factors, H_factors, P, Q, G, H)
+    }
+
+    /// The size of the proof in bytes, `2 + 32 * (2 lg(n) + 2)`.
+    pub fn serialized_size(&self) -> usize {
+        HEADER_SIZE + (self.L_vec.len() * 2 + 2) * 32
+    }
+
+    /// Serialize the proof as `header || L_0 || R_0 || ... || L_(k-1) || R_(k-1) || a || b`,
+    /// where `header` is the header of the `encoding` module.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.serialized_size());
+        buf.extend_from_slice(&encoding::header(ProofType::InnerProduct));
+        self.write_body(&mut buf);
+        buf
+    }
+
+    /// Deserialize a proof that was serialized with `to_bytes` by any version of the encoding.
+    ///
+    /// Every point must be a valid Ristretto encoding and both scalars must be canonical.
+    pub fn from_bytes(slice: &[u8]) -> Result<Self, String> {
+        InnerProductProofView::from_bytes(slice).map(|view| view.to_proof())
+    }
+
+    /// Append the body of the encoding to `buf`.
+    pub(crate) fn write_body(&self, buf: &mut Vec<u8>) {
+        for (L, R) in self.L_vec.iter().zip(&self.R_vec) {
+            buf.extend_from_slice(&L.to_bytes());
+            buf.extend_from_slice(&R.to_bytes());
+        }
+        buf.extend_from_slice(&self.a.to_bytes());
+        buf.extend_from_slice(&self.b.to_bytes());
+    }
+}
+
+/// A validated inner product proof that borrows its encoding.
+///
+/// `from_bytes` validates the encoding like `InnerProductProof::from_bytes`, but does not copy the
+/// body into an owned proof: the verifier reads every point and scalar from the borrowed bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InnerProductProofView<'a> {
+    body: &'a [u8],
+}
+
+impl<'a> InnerProductProofView<'a> {
+    /// Validate a proof that was serialized with `InnerProductProof::to_bytes` by any version of
+    /// the encoding, and borrow it.
+    pub fn from_bytes(slice: &'a [u8]) -> Result<Self, String> {
+        Self::from_body(ProofBytes::body_of(slice, ProofType::InnerProduct)?)
+    }
+
+    /// Validate the body of the encoding, and borrow it.
+    pub(crate) fn from_body(body: &'a [u8]) -> Result<Self, String> {
+        let num_elements = body.len() / 32;
+        if num_elements * 32 != body.len() || num_elements < 2 || num_elements & 1 != 0 {
+            return Err("Inner product proof has an invalid length".to_string());
+        }
+        if (num_elements - 2) / 2 >= 32 {
+            return Err("Inner product proof has too many rounds".to_string());
+        }
+
+        for index in 0..num_elements - 2 {
+            if !encoding::point_at(body, index).is_valid() {
+                return Err("Inner product proof contains an invalid point".to_string());
+            }
+        }
+        for index in num_elements - 2..num_elements {
+            if !encoding::scalar_at(body, index).is_canonical() {
+                return Err("Inner product proof contains a non-canonical scalar".to_string());
+            }
+        }
+
+        Ok(Self { body })
+    }
+
+    /// Verify the proof as `InnerProductProof::verify` does.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify<IG, IH>(
+        &self,
+        n: usize,
+        transcript: &mut Transcript,
+        G_factors: IG,
+        H_factors: IH,
+        P: &RistrettoPoint,
+        Q: &RistrettoPoint,
+        G: &[RistrettoPoint],
+        H: &[RistrettoPoint],
+    ) -> Result<(), String>
+    where
+        IG: IntoIterator,
+        IG::Item: Borrow<Scalar>,
+        IH: IntoIterator,
+        IH::Item: Borrow<Scalar>,
+    {
+        verify(self, n, transcript, G_factors, H_factors, P, Q, G, H)
+    }
+
+    /// Copy the proof into an owned proof.
+    pub fn to_proof(&self) -> InnerProductProof {
+        let lg_n = self.num_rounds();
+        InnerProductProof {
+            L_vec: (0..lg_n).map(|j| self.round(j).0).collect(),
+            R_vec: (0..lg_n).map(|j| self.round(j).1).collect(),
+            a: self.a(),
+            b: self.b(),
+        }
+    }
+}
+
+/// The points and scalars of an inner product proof, which `InnerProductProof` owns and
+/// `InnerProductProofView` reads from its encoding.
+pub(crate) trait InnerProductElements {
+    /// The number of rounds `lg(n)`
+    fn num_rounds(&self) -> usize;
+
+    /// The commitments `L_j` and `R_j` of round `j`
+    fn round(&self, j: usize) -> (RistrettoPoint, RistrettoPoint);
+
+    /// The folded scalar `a`
+    fn a(&self) -> Scalar;
+
+    /// The folded scalar `b`
+    fn b(&self) -> Scalar;
+
+    /// Replay the transcript of the proof and compute the verification scalars.
+    ///
+    /// Returns the squared challenges `u_j^2`, their inverses `u_j^-2`, and the vector `s` with
+    /// `s_i = prod_j u_j^(b(i, j))`, where `b(i, j)` is `1` if bit `lg(n) - 1 - j` of `i` is set
+    /// and `-1` otherwise.
+    ///
+    /// All inverses are computed with a single scalar inversion. The scalars in `extra_inverses`
+    /// are inverted in place in the same batch, so a protocol that runs the argument as its last
+    /// step does not need an inversion of its own.
+    #[allow(clippy::type_complexity)]
+    fn verification_scalars(
+        &self,
+        n: usize,
+        transcript: &mut Transcript,
+        extra_inverses: &mut [Scalar],
+    ) -> Result<(Vec<Scalar>, Vec<Scalar>, Vec<Scalar>), String> {
+        let lg_n = self.num_rounds();
+        if lg_n >= 32 || n != 1 << lg_n {
+            return Err("Inner product proof has an invalid number of rounds".to_string());
+        }
+
+        let mut transcript = inner_product_transcript(transcript, n as u64);
+        let mut challenges = Vec::with_capacity(lg_n);
+        for j in 0..lg_n {
+            let (left, right) = self.round(j);
+            if is_identity(&left) || is_identity(&right) {
+                return Err("Inner product proof contains the identity".to_string());
+            }
+            let (next, u) = transcript
+                .append::<schedule::L>(&left)
+                .append::<schedule::R>(&right)
+                .challenge_scalar::<schedule::U>();
+            transcript = next;
+            challenges.push(u);
+        }
+
+        let mut inverses = [&challenges[..], extra_inverses].concat();
+        Scalar::batch_invert(&mut inverses)?;
+        let (challenges_inv, extra) = inverses.split_at(lg_n);
+        extra_inverses.copy_from_slice(extra);
+
+        let mut allinv = Scalar::one();
+        for u_inv in challenges_inv {
+            allinv = allinv.multiply_scalar(*u_inv)?;
+        }
+
+        let challenges_sq = challenges
+            .iter()
+            .map(|u| u.multiply_scalar(*u))
+            .collect::<Result<Vec<_>, _>>()?;
+        let challenges_inv_sq = challenges_inv
+            .iter()
+            .map(|u_inv| u_inv.multiply_scalar(*u_inv))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // s_0 is the product of all inverses. Setting bit lg_i of i moves from s_(i - 2^lg_i) to
+        // s_i by replacing the factor u^-1 of that round with u, i.e. multiplying with u^2.
+        let mut s = Vec::with_capacity(n);
+        s.push(allinv);
+        for i in 1..n {
+            let lg_i = (usize::BITS - 1 - i.leading_zeros()) as usize;
+            let k = 1 << lg_i;
+            let u_lg_i_sq = challenges_sq[lg_n - 1 - lg_i];
+            s.push(s[i - k].multiply_scalar(u_lg_i_sq)?);
+        }
+
+        Ok((challenges_sq, challenges_inv_sq, s))
+    }
+
+    /// Append the commitments `L_0, ..., L_(k-1)` and then `R_0, ..., R_(k-1)` to `points`, in
+    /// the order of the challenges returned by `verification_scalars`.
+    fn extend_rounds(&self, points: &mut Vec<RistrettoPoint>) {
+        let lg_n = self.num_rounds();
+        points.extend((0..lg_n).map(|j| self.round(j).0));
+        points.extend((0..lg_n).map(|j| self.round(j).1));
+    }
+}
+
+impl InnerProductElements for InnerProductProof {
+    fn num_rounds(&self) -> usize {
+        self.L_vec.len()
+    }
+
+    fn round(&self, j: usize) -> (RistrettoPoint, RistrettoPoint) {
+        (self.L_vec[j], self.R_vec[j])
+    }
+
+    fn a(&self) -> Scalar {
+        self.a
+    }
+
+    fn b(&self) -> Scalar {
+        self.b
+    }
+}
+
+impl InnerProductElements for InnerProductProofView<'_> {
+    fn num_rounds(&self) -> usize {
+        (self.body.len() / 32 - 2) / 2
+    }
+
+    fn round(&self, j: usize) -> (RistrettoPoint, RistrettoPoint) {
+        (
+            encoding::point_at(self.body, 2 * j),
+            encoding::point_at(self.body, 2 * j + 1),
+        )
+    }
+
+    fn a(&self) -> Scalar {
+        encoding::scalar_at(self.body, 2 * self.num_rounds())
+    }
+
+    fn b(&self) -> Scalar {
+        encoding::scalar_at(self.body, 2 * self.num_rounds() + 1)
+    }
+}
+
+/// Verify the proof for the commitment `P` with respect to the generators `G` and `H`, scaled by
+/// `G_factors` and `H_factors`.
+#[allow(clippy::too_many_arguments)]
+fn verify<IG, IH>(
+    proof: &impl InnerProductElements,
+    n: usize,
+    transcript: &mut Transcript,
+    G_factors: IG,
+    H_factors: IH,
+    P: &RistrettoPoint,
+    Q: &RistrettoPoint,
+    G: &[RistrettoPoint],
+    H: &[RistrettoPoint],
+) -> Result<(), String>
+where
+    IG: IntoIterator,
+    IG::Item: Borrow<Scalar>,
+    IH: IntoIterator,
+    IH::Item: Borrow<Scalar>,
+{
+    if G.len() != n || H.len() != n {
+        return Err("Inner product generators have an invalid length".to_string());
+    }
+    let (u_sq, u_inv_sq, s) = proof.verification_scalars(n, transcript, &mut [])?;
+
+    let (a, b) = (proof.a(), proof.b());
+    let ab = a.multiply_scalar(b)?;
+    let mut scalars = Vec::with_capacity(2 * n + 2 * u_sq.len() + 1);
+    scalars.push(ab);
+
+    // a * s_i * g_i for every generator of G
+    let mut G_factors = G_factors.into_iter();
+    for s_i in &s {
+        let g_i = G_factors
+            .next()
+            .ok_or_else(|| "Not enough G factors".to_string())?;
+        scalars.push(a.multiply_scalar(*s_i)?.multiply_scalar(*g_i.borrow())?);
+    }
+
+    // b * s_i^-1 * h_i for every generator of H, where s_i^-1 = s_(n - 1 - i)
+    let mut H_factors = H_factors.into_iter();
+    for s_i_inv in s.iter().rev() {
+        let h_i = H_factors
+            .next()
+            .ok_or_else(|| "Not enough H factors".to_string())?;
+        scalars.push(
+            b.multiply_scalar(*s_i_inv)?
+                .multiply_scalar(*h_i.borrow())?,
+        );
+    }
+
+    for u_j_sq in &u_sq {
+        scalars.push(u_j_sq.negate()?);
+    }
+    for u_j_inv_sq in &u_inv_sq {
+        scalars.push(u_j_inv_sq.negate()?);
+    }
+
+    let mut points = Vec::with_capacity(scalars.len());
+    points.push(*Q);
+    points.extend(G);
+    points.extend(H);
+    proof.extend_rounds(&mut points);
+    let expected_P = RistrettoPoint::multiscalar_multiply(&scalars, &points)?;
+
+    if expected_P == *P {
+        Ok(())
+    } else {
+        Err("Inner product proof verification failed".to_string())
+    }
+}
+
+fn is_identity(point: &RistrettoPoint) -> bool {
+    point.to_bytes() == [0; 32]
+}
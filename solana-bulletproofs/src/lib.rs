@@ -1,2 +1,9 @@
-#[cfg(not(target_os = "solana"))]
+pub mod batched_range_proof;
+pub mod encoding;
+pub mod errors;
+pub mod generators;
+pub mod inner_product_proof;
+pub mod r1cs;
+pub mod range_proof;
+pub mod transcript;
 pub mod util;
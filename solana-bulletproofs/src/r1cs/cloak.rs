@@ -0,0 +1,297 @@
+//! Gadgets for confidential transactions with multiple assets, following Cloak.
+//!
+//! A value is a pair of a quantity `q` and a flavor `f`, which identifies the asset, and is
+//! committed with one Pedersen commitment for each. A `cloak` transaction proves that the
+//! outputs have the same quantity of every flavor as the inputs without revealing which flavors
+//! move where:
+//!
+//! 1. the inputs are shuffled so that equal flavors are adjacent, and `merge` combines each run of
+//!    equal flavors into its total followed by zero quantities,
+//! 2. the outputs are shuffled and merged in the same way, which read backwards is the `split` of
+//!    the totals into the outputs,
+//! 3. the totals of both sides, padded with zero quantities, are shuffled into each other,
+//! 4. the quantities of the outputs are constrained to 64 bits with `range`.
+//!
+//! Both `merge` and `split` consist of `mix` gadgets, each of which proves that two values are
+//! either passed through unchanged or combined because they have the same flavor. The quantities
+//! of the inputs are not range checked, since they are the outputs of earlier transactions.
+//!
+//! The zero quantities that `merge` and `split` produce are assigned the zero flavor, and a
+//! flavor whose total is zero must occur on both sides of the transaction.
+
+#[cfg(not(target_os = "solana"))]
+use super::Prover;
+use {
+    super::{
+        gadgets, ConstraintSystem, LinearCombination, RandomizableConstraintSystem,
+        RandomizedConstraintSystem, Variable, Verifier,
+    },
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// The number of bits of a quantity
+pub const QUANTITY_BIT_LENGTH: usize = 64;
+
+/// The quantity and the flavor of a value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Value {
+    pub q: u64,
+    pub f: Scalar,
+}
+
+/// The commitments to the quantity and the flavor of a value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CommittedValue {
+    pub q: RistrettoPoint,
+    pub f: RistrettoPoint,
+}
+
+/// The variables of a value in a constraint system, with the assignment of the prover
+#[derive(Clone, Copy, Debug)]
+pub struct AllocatedValue {
+    pub q: Variable,
+    pub f: Variable,
+    pub assignment: Option<Value>,
+}
+
+impl Value {
+    /// The zero quantity of the zero flavor.
+    pub fn zero() -> Self {
+        Self {
+            q: 0,
+            f: Scalar::zero(),
+        }
+    }
+
+    /// Commit to the value with the blinding factors `q_blinding` and `f_blinding`.
+    #[cfg(not(target_os = "solana"))]
+    pub fn prover_commit(
+        &self,
+        prover: &mut Prover,
+        q_blinding: Scalar,
+        f_blinding: Scalar,
+    ) -> Result<(CommittedValue, AllocatedValue), String> {
+        let (q_commitment, q) = prover.commit(Scalar::from(self.q), q_blinding)?;
+        let (f_commitment, f) = prover.commit(self.f, f_blinding)?;
+        Ok((
+            CommittedValue {
+                q: q_commitment,
+                f: f_commitment,
+            },
+            AllocatedValue {
+                q,
+                f,
+                assignment: Some(*self),
+            },
+        ))
+    }
+}
+
+impl CommittedValue {
+    /// Add the commitments of the value to the verifier.
+    pub fn verifier_commit(&self, verifier: &mut Verifier) -> AllocatedValue {
+        AllocatedValue {
+            q: verifier.commit(self.q),
+            f: verifier.commit(self.f),
+            assignment: None,
+        }
+    }
+}
+
+impl AllocatedValue {
+    /// Allocate the variables of a value with one multiplier.
+    pub fn allocate<CS: ConstraintSystem>(
+        cs: &mut CS,
+        assignment: Option<Value>,
+    ) -> Result<Self, String> {
+        let q = cs.allocate(assignment.map(|value| Scalar::from(value.q)))?;
+        let f = cs.allocate(assignment.map(|value| value.f))?;
+        Ok(Self { q, f, assignment })
+    }
+
+    /// Allocate a value whose quantity is constrained to be zero.
+    pub fn allocate_zero<CS: ConstraintSystem>(
+        cs: &mut CS,
+        assignment: Option<Value>,
+    ) -> Result<Self, String> {
+        let value = Self::allocate(cs, assignment)?;
+        cs.constrain(value.q.into());
+        Ok(value)
+    }
+
+    fn tuple(&self) -> Vec<Variable> {
+        vec![self.q, self.f]
+    }
+}
+
+/// Constrain `(c, d)` to either equal `(a, b)`, or, if `a` and `b` have the same flavor, to the
+/// zero quantity `c` and the sum `d` of `a` and `b`.
+///
+/// The gadget uses one multiplier in the second phase. The flavor of `c` is unconstrained when
+/// the values are combined.
+pub fn mix<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    a: AllocatedValue,
+    b: AllocatedValue,
+    c: AllocatedValue,
+    d: AllocatedValue,
+) -> Result<(), String> {
+    cs.specify_randomized_constraints(move |cs| {
+        let w = cs.challenge_scalar(b"mix challenge");
+        let w2 = w.multiply_scalar(w)?;
+        let w3 = w2.multiply_scalar(w)?;
+        // (a, b) = (c, d), or c.q = 0 and a.f = b.f and d = (a.q + b.q, a.f)
+        let (_, _, product) = cs.multiply(
+            (a.q - c.q) + (a.f - c.f) * w + (b.q - d.q) * w2 + (b.f - d.f) * w3,
+            LinearCombination::from(c.q)
+                + (a.f - b.f) * w
+                + (d.q - a.q - b.q) * w2
+                + (d.f - a.f) * w3,
+        )?;
+        cs.constrain(product.into());
+        Ok(())
+    })
+}
+
+/// Combine each run of adjacent values of the same flavor into its total, which takes the place
+/// of the last value of the run, and zero quantities.
+///
+/// The gadget allocates `2k - 2` values and uses `k - 1` multipliers in the second phase for `k`
+/// values.
+pub fn merge<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    values: &[AllocatedValue],
+) -> Result<Vec<AllocatedValue>, String> {
+    let (first, values) = match values.split_first() {
+        Some(split) => split,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut merged = Vec::with_capacity(values.len() + 1);
+    let mut total = *first;
+    for value in values {
+        let assignments = match (total.assignment, value.assignment) {
+            (Some(total), Some(value)) if total.f == value.f => {
+                let q = total
+                    .q
+                    .checked_add(value.q)
+                    .ok_or_else(|| "Total quantity of a flavor overflows".to_string())?;
+                Some((Value::zero(), Value { q, f: total.f }))
+            }
+            (Some(total), Some(value)) => Some((total, value)),
+            _ => None,
+        };
+        let c = AllocatedValue::allocate(cs, assignments.map(|(c, _)| c))?;
+        let d = AllocatedValue::allocate(cs, assignments.map(|(_, d)| d))?;
+        mix(cs, total, *value, c, d)?;
+        merged.push(c);
+        total = d;
+    }
+    merged.push(total);
+
+    Ok(merged)
+}
+
+/// Split the totals and zero quantities of `merge(values)` into `values`.
+///
+/// The mix relation is symmetric, so a split is a merge read backwards and has the same cost.
+pub fn split<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    values: &[AllocatedValue],
+) -> Result<Vec<AllocatedValue>, String> {
+    merge(cs, values)
+}
+
+/// Constrain the quantity of `value` to `[0, 2^n)`.
+pub fn range<CS: ConstraintSystem>(
+    cs: &mut CS,
+    value: &AllocatedValue,
+    n: usize,
+) -> Result<(), String> {
+    gadgets::range(cs, value.q.into(), value.assignment.map(|value| value.q), n)
+}
+
+/// Constrain `outputs` to have the same total quantity of every flavor as `inputs`.
+///
+/// The prover passes the assignments of all inputs and outputs, the verifier passes none.
+pub fn cloak<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    inputs: &[AllocatedValue],
+    outputs: &[AllocatedValue],
+) -> Result<(), String> {
+    let k = inputs.len().max(outputs.len());
+    let prover = inputs
+        .iter()
+        .chain(outputs)
+        .all(|value| value.assignment.is_some());
+
+    let grouped_inputs = group(cs, inputs)?;
+    let merge_out = merge(cs, &grouped_inputs)?;
+    let grouped_outputs = group(cs, outputs)?;
+    let split_in = split(cs, &grouped_outputs)?;
+    let merge_out = pad(cs, merge_out, k, prover)?;
+    let split_in = pad(cs, split_in, k, prover)?;
+    shuffle_values(cs, &merge_out, &split_in)?;
+
+    for output in outputs {
+        range(cs, output, QUANTITY_BIT_LENGTH)?;
+    }
+
+    Ok(())
+}
+
+/// Shuffle `values` so that equal flavors are adjacent.
+fn group<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    values: &[AllocatedValue],
+) -> Result<Vec<AllocatedValue>, String> {
+    let assignments = values
+        .iter()
+        .map(|value| value.assignment)
+        .collect::<Option<Vec<_>>>()
+        .map(|assignments| group_by_flavor(&assignments));
+    let mut grouped = Vec::with_capacity(values.len());
+    for i in 0..values.len() {
+        let assignment = assignments.as_ref().map(|assignments| assignments[i]);
+        grouped.push(AllocatedValue::allocate(cs, assignment)?);
+    }
+    shuffle_values(cs, values, &grouped)?;
+    Ok(grouped)
+}
+
+/// Pad `values` to `k` values with zero quantities.
+fn pad<CS: ConstraintSystem>(
+    cs: &mut CS,
+    mut values: Vec<AllocatedValue>,
+    k: usize,
+    prover: bool,
+) -> Result<Vec<AllocatedValue>, String> {
+    while values.len() < k {
+        values.push(AllocatedValue::allocate_zero(cs, prover.then(Value::zero))?);
+    }
+    Ok(values)
+}
+
+fn shuffle_values<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    inputs: &[AllocatedValue],
+    outputs: &[AllocatedValue],
+) -> Result<(), String> {
+    let inputs = inputs.iter().map(AllocatedValue::tuple).collect::<Vec<_>>();
+    let outputs = outputs
+        .iter()
+        .map(AllocatedValue::tuple)
+        .collect::<Vec<_>>();
+    gadgets::tuple_shuffle(cs, &inputs, &outputs)
+}
+
+/// Reorder `values` so that equal flavors are adjacent, in the order of their first occurrence.
+fn group_by_flavor(values: &[Value]) -> Vec<Value> {
+    let mut grouped: Vec<Value> = Vec::with_capacity(values.len());
+    for value in values {
+        if !grouped.iter().any(|grouped| grouped.f == value.f) {
+            grouped.extend(values.iter().filter(|other| other.f == value.f));
+        }
+    }
+    grouped
+}
@@ -0,0 +1,69 @@
+//! The interface that gadgets use to build constraints.
+
+use {
+    super::{LinearCombination, Variable},
+    solana_ristretto::scalar::Scalar,
+};
+
+/// A constraint system, which is either a `Prover` or a `Verifier`.
+///
+/// A gadget is a function that is generic over `ConstraintSystem`, so that the prover and the
+/// verifier build the same constraints. The prover passes the assignments of the variables it
+/// allocates, while the verifier passes `None`.
+pub trait ConstraintSystem {
+    /// Allocate a multiplier and constrain its left and right inputs to `left` and `right`.
+    ///
+    /// Returns the variables of the left input, the right input, and the output of the
+    /// multiplier.
+    fn multiply(
+        &mut self,
+        left: LinearCombination,
+        right: LinearCombination,
+    ) -> Result<(Variable, Variable, Variable), String>;
+
+    /// Allocate an unconstrained variable with the given assignment.
+    ///
+    /// Two consecutive allocations share a multiplier, whose output is left unconstrained.
+    fn allocate(&mut self, assignment: Option<Scalar>) -> Result<Variable, String>;
+
+    /// Allocate a multiplier with the given assignments of its left and right inputs.
+    ///
+    /// Returns the variables of the left input, the right input, and the output of the
+    /// multiplier.
+    fn allocate_multiplier(
+        &mut self,
+        input_assignments: Option<(Scalar, Scalar)>,
+    ) -> Result<(Variable, Variable, Variable), String>;
+
+    /// The number of multipliers that have been allocated so far.
+    fn multipliers_len(&self) -> usize;
+
+    /// The number of linear constraints that have been added so far.
+    fn constraints_len(&self) -> usize;
+
+    /// Constrain the linear combination `lc` to be zero.
+    fn constrain(&mut self, lc: LinearCombination);
+}
+
+/// A constraint system whose gadgets can add constraints that depend on challenges.
+///
+/// The challenges are squeezed from the transcript after the prover has committed to all
+/// variables that were allocated so far, so the constraints cannot be adapted to them. The
+/// variables that are allocated in the second phase are committed separately.
+pub trait RandomizableConstraintSystem: ConstraintSystem {
+    /// The constraint system in which the randomized constraints are built
+    type RandomizedCS: RandomizedConstraintSystem;
+
+    /// Defer `callback` until all variables of the first phase are committed.
+    ///
+    /// The callbacks run in the order in which they were specified.
+    fn specify_randomized_constraints<F>(&mut self, callback: F) -> Result<(), String>
+    where
+        F: 'static + FnOnce(&mut Self::RandomizedCS) -> Result<(), String>;
+}
+
+/// A constraint system in the second phase, which can squeeze challenges.
+pub trait RandomizedConstraintSystem: ConstraintSystem {
+    /// Squeeze a challenge from the transcript.
+    fn challenge_scalar(&mut self, label: &[u8]) -> Scalar;
+}
@@ -0,0 +1,300 @@
+//! Gadgets for common constraints.
+//!
+//! Every gadget is generic over `ConstraintSystem`, so the prover and the verifier call the same
+//! function, the prover with assignments and the verifier with `None`. The cost of a gadget is the
+//! number of multipliers it allocates, which determines the size of the proof and of the
+//! verifier's multiscalar multiplication, and the number of linear constraints it adds:
+//!
+//! | gadget                     | multipliers | constraints    |
+//! |----------------------------|-------------|----------------|
+//! | `allocate_bit`             | 1           | 2              |
+//! | `constrain_boolean`        | 1           | 3              |
+//! | `not`                      | 0           | 0              |
+//! | `and`                      | 1           | 2              |
+//! | `or`                       | 1           | 2              |
+//! | `bits`, `range`            | `n`         | `2n + 1`       |
+//! | `less_than`, `greater_than`| `n`         | `2n + 1`       |
+//! | `equal`                    | 0           | 1              |
+//! | `not_equal`                | 1           | 2              |
+//! | `select`                   | 1           | 2              |
+//! | `sum`                      | 0           | 0              |
+//! | `shuffle`, `tuple_shuffle` | `2(k - 1)`  | `4(k - 1) + 1` |
+//!
+//! Gadgets that take variables as booleans do not constrain them to be boolean, so the caller
+//! must allocate them with `allocate_bit` or constrain them with `constrain_boolean`.
+//!
+//! The shuffle gadgets of `k > 1` values need challenges, so they take a
+//! `RandomizableConstraintSystem` and allocate their multipliers in the second phase.
+
+use {
+    super::{
+        ConstraintSystem, LinearCombination, RandomizableConstraintSystem,
+        RandomizedConstraintSystem, Variable,
+    },
+    solana_ristretto::scalar::Scalar,
+};
+
+/// Allocate a variable that is constrained to be `0` or `1`.
+pub fn allocate_bit<CS: ConstraintSystem>(
+    cs: &mut CS,
+    assignment: Option<bool>,
+) -> Result<Variable, String> {
+    let (bit, not_bit, product) = cs.allocate_multiplier(
+        assignment.map(|bit| (Scalar::from(bit as u64), Scalar::from(!bit as u64))),
+    )?;
+    // bit * (1 - bit) = 0
+    cs.constrain(product.into());
+    cs.constrain(bit + not_bit - 1u64);
+    Ok(bit)
+}
+
+/// Constrain the linear combination `v` to be `0` or `1`.
+pub fn constrain_boolean<CS: ConstraintSystem>(
+    cs: &mut CS,
+    v: LinearCombination,
+) -> Result<(), String> {
+    let (_, _, product) = cs.multiply(v.clone(), LinearCombination::from(1u64) - v)?;
+    cs.constrain(product.into());
+    Ok(())
+}
+
+/// The negation `1 - a` of the boolean `a`.
+pub fn not(a: LinearCombination) -> LinearCombination {
+    LinearCombination::from(1u64) - a
+}
+
+/// The conjunction `a * b` of the booleans `a` and `b`.
+pub fn and<CS: ConstraintSystem>(
+    cs: &mut CS,
+    a: LinearCombination,
+    b: LinearCombination,
+) -> Result<Variable, String> {
+    let (_, _, product) = cs.multiply(a, b)?;
+    Ok(product)
+}
+
+/// The disjunction `a + b - a * b` of the booleans `a` and `b`.
+pub fn or<CS: ConstraintSystem>(
+    cs: &mut CS,
+    a: LinearCombination,
+    b: LinearCombination,
+) -> Result<LinearCombination, String> {
+    let (_, _, product) = cs.multiply(a.clone(), b.clone())?;
+    Ok(a + b - product)
+}
+
+/// Decompose `v` into `n` bits, least significant first, which constrains `v` to `[0, 2^n)`.
+///
+/// The prover passes the value of `v` as the assignment. `n` must be at most 64.
+pub fn bits<CS: ConstraintSystem>(
+    cs: &mut CS,
+    v: LinearCombination,
+    assignment: Option<u64>,
+    n: usize,
+) -> Result<Vec<Variable>, String> {
+    if n > 64 {
+        return Err("Bit length must be at most 64".to_string());
+    }
+
+    let mut bits = Vec::with_capacity(n);
+    let mut sum = LinearCombination::default();
+    for i in 0..n {
+        let bit = allocate_bit(cs, assignment.map(|v| (v >> i) & 1 == 1))?;
+        sum = sum + bit * (1u64 << i);
+        bits.push(bit);
+    }
+    cs.constrain(v - sum);
+
+    Ok(bits)
+}
+
+/// Constrain `v` to the range `[0, 2^n)`.
+///
+/// The prover passes the value of `v` as the assignment. `n` must be at most 64.
+pub fn range<CS: ConstraintSystem>(
+    cs: &mut CS,
+    v: LinearCombination,
+    assignment: Option<u64>,
+    n: usize,
+) -> Result<(), String> {
+    bits(cs, v, assignment, n).map(|_| ())
+}
+
+/// Constrain `left < right` for values in `[0, 2^n)`.
+///
+/// The gadget constrains `right - left - 1` to `[0, 2^n)`, so it is only sound if both values are
+/// known to be in `[0, 2^n)`, e.g. by `range`. The prover passes the values of `left` and
+/// `right` as the assignments.
+pub fn less_than<CS: ConstraintSystem>(
+    cs: &mut CS,
+    left: LinearCombination,
+    right: LinearCombination,
+    assignments: Option<(u64, u64)>,
+    n: usize,
+) -> Result<(), String> {
+    range(
+        cs,
+        right - left - 1u64,
+        assignments.map(|(left, right)| right.wrapping_sub(left).wrapping_sub(1)),
+        n,
+    )
+}
+
+/// Constrain `left > right` for values in `[0, 2^n)`, see `less_than`.
+pub fn greater_than<CS: ConstraintSystem>(
+    cs: &mut CS,
+    left: LinearCombination,
+    right: LinearCombination,
+    assignments: Option<(u64, u64)>,
+    n: usize,
+) -> Result<(), String> {
+    less_than(
+        cs,
+        right,
+        left,
+        assignments.map(|(left, right)| (right, left)),
+        n,
+    )
+}
+
+/// Constrain `left = right`.
+pub fn equal<CS: ConstraintSystem>(cs: &mut CS, left: LinearCombination, right: LinearCombination) {
+    cs.constrain(left - right);
+}
+
+/// Constrain `left != right` by showing that `left - right` has an inverse.
+///
+/// The prover passes the value of `left - right` as the assignment.
+pub fn not_equal<CS: ConstraintSystem>(
+    cs: &mut CS,
+    left: LinearCombination,
+    right: LinearCombination,
+    assignment: Option<Scalar>,
+) -> Result<(), String> {
+    let assignment = match assignment {
+        Some(difference) => Some((difference, difference.invert()?)),
+        None => None,
+    };
+    let (difference, _, product) = cs.allocate_multiplier(assignment)?;
+    cs.constrain(left - right - difference);
+    cs.constrain(product - 1u64);
+    Ok(())
+}
+
+/// Select `when_true` if the boolean `condition` is `1`, and `when_false` if it is `0`.
+///
+/// Returns `when_false + condition * (when_true - when_false)`.
+pub fn select<CS: ConstraintSystem>(
+    cs: &mut CS,
+    condition: LinearCombination,
+    when_true: LinearCombination,
+    when_false: LinearCombination,
+) -> Result<LinearCombination, String> {
+    let (_, _, product) = cs.multiply(condition, when_true - when_false.clone())?;
+    Ok(when_false + product)
+}
+
+/// The sum of the linear combinations `terms`.
+pub fn sum<I: IntoIterator<Item = LinearCombination>>(terms: I) -> LinearCombination {
+    terms
+        .into_iter()
+        .fold(LinearCombination::default(), |sum, term| sum + term)
+}
+
+/// Constrain `outputs` to be a permutation of `inputs`.
+///
+/// The gadget compares the products of `inputs[i] - z` and `outputs[i] - z` at a challenge `z`,
+/// which are equal polynomials in `z` if and only if `outputs` is a permutation of `inputs`.
+pub fn shuffle<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    inputs: &[Variable],
+    outputs: &[Variable],
+) -> Result<(), String> {
+    if inputs.len() != outputs.len() {
+        return Err("Shuffle inputs and outputs have different lengths".to_string());
+    }
+    if inputs.len() <= 1 {
+        for (input, output) in inputs.iter().zip(outputs) {
+            equal(cs, (*input).into(), (*output).into());
+        }
+        return Ok(());
+    }
+
+    let inputs = inputs.iter().map(|v| (*v).into()).collect::<Vec<_>>();
+    let outputs = outputs.iter().map(|v| (*v).into()).collect::<Vec<_>>();
+    cs.specify_randomized_constraints(move |cs| randomized_shuffle(cs, inputs, outputs))
+}
+
+/// Constrain the tuples `outputs` to be a permutation of the tuples `inputs`.
+///
+/// The gadget combines the entries of each tuple with the powers of a challenge `w` and shuffles
+/// the combinations as in `shuffle`, so the tuples move as a whole. All tuples must have the same
+/// width.
+pub fn tuple_shuffle<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    inputs: &[Vec<Variable>],
+    outputs: &[Vec<Variable>],
+) -> Result<(), String> {
+    if inputs.len() != outputs.len() {
+        return Err("Shuffle inputs and outputs have different lengths".to_string());
+    }
+    let width = inputs.first().map_or(0, Vec::len);
+    if inputs
+        .iter()
+        .chain(outputs)
+        .any(|tuple| tuple.len() != width)
+    {
+        return Err("Shuffle tuples have different widths".to_string());
+    }
+    if inputs.len() <= 1 {
+        for (input, output) in inputs.iter().zip(outputs) {
+            for (input, output) in input.iter().zip(output) {
+                equal(cs, (*input).into(), (*output).into());
+            }
+        }
+        return Ok(());
+    }
+
+    let inputs = inputs.to_vec();
+    let outputs = outputs.to_vec();
+    cs.specify_randomized_constraints(move |cs| {
+        let w = cs.challenge_scalar(b"tuple shuffle challenge");
+        let combine = |tuple: &Vec<Variable>| -> Result<LinearCombination, String> {
+            let mut combination = LinearCombination::default();
+            let mut power = Scalar::one();
+            for v in tuple {
+                combination = combination + *v * power;
+                power = power.multiply_scalar(w)?;
+            }
+            Ok(combination)
+        };
+        let inputs = inputs.iter().map(combine).collect::<Result<Vec<_>, _>>()?;
+        let outputs = outputs.iter().map(combine).collect::<Result<Vec<_>, _>>()?;
+        randomized_shuffle(cs, inputs, outputs)
+    })
+}
+
+/// Constrain the products of `inputs[i] - z` and `outputs[i] - z` to be equal at a challenge `z`.
+fn randomized_shuffle<CS: RandomizedConstraintSystem>(
+    cs: &mut CS,
+    inputs: Vec<LinearCombination>,
+    outputs: Vec<LinearCombination>,
+) -> Result<(), String> {
+    let z = cs.challenge_scalar(b"shuffle challenge");
+    let inputs = product(cs, inputs.into_iter().map(|v| v - z))?;
+    let outputs = product(cs, outputs.into_iter().map(|v| v - z))?;
+    cs.constrain(inputs - outputs);
+    Ok(())
+}
+
+/// The product of `factors`, with one multiplier per factor after the first.
+fn product<CS: ConstraintSystem>(
+    cs: &mut CS,
+    mut factors: impl Iterator<Item = LinearCombination>,
+) -> Result<LinearCombination, String> {
+    let first = factors.next().unwrap_or_else(|| 1u64.into());
+    factors.try_fold(first, |product, factor| {
+        let (_, _, output) = cs.multiply(product, factor)?;
+        Ok(output.into())
+    })
+}
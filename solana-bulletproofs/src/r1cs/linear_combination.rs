@@ -0,0 +1,140 @@
+//! Variables and linear combinations of variables of a constraint system.
+//!
+//! The arithmetic operators build linear combinations symbolically, e.g. `a + b * 2u64 - 1u64`
+//! for variables `a` and `b`. Coefficients are reduced modulo the group order when a linear
+//! combination is built, so the operators cannot fail.
+
+use {
+    solana_ristretto::scalar::Scalar,
+    std::{
+        iter::FromIterator,
+        ops::{Add, Mul, Neg, Sub},
+    },
+};
+
+/// A variable of a constraint system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variable {
+    /// The value of the `i`-th commitment of the prover or the verifier
+    Committed(usize),
+    /// The left input of the `i`-th multiplier
+    MultiplierLeft(usize),
+    /// The right input of the `i`-th multiplier
+    MultiplierRight(usize),
+    /// The output of the `i`-th multiplier
+    MultiplierOutput(usize),
+    /// The constant `1`
+    One(),
+}
+
+/// A linear combination `sum_i c_i * v_i` of variables `v_i` with coefficients `c_i`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinearCombination {
+    pub(super) terms: Vec<(Variable, Scalar)>,
+}
+
+impl LinearCombination {
+    /// The terms of the linear combination.
+    pub fn terms(&self) -> &[(Variable, Scalar)] {
+        &self.terms
+    }
+}
+
+impl From<Variable> for LinearCombination {
+    fn from(variable: Variable) -> Self {
+        LinearCombination {
+            terms: vec![(variable, Scalar::one())],
+        }
+    }
+}
+
+impl<S: Into<Scalar>> From<S> for LinearCombination {
+    fn from(scalar: S) -> Self {
+        LinearCombination {
+            terms: vec![(Variable::One(), scalar.into().reduce())],
+        }
+    }
+}
+
+impl FromIterator<(Variable, Scalar)> for LinearCombination {
+    fn from_iter<I: IntoIterator<Item = (Variable, Scalar)>>(iter: I) -> Self {
+        LinearCombination {
+            terms: iter
+                .into_iter()
+                .map(|(variable, coefficient)| (variable, coefficient.reduce()))
+                .collect(),
+        }
+    }
+}
+
+impl<L: Into<LinearCombination>> Add<L> for LinearCombination {
+    type Output = Self;
+
+    fn add(mut self, rhs: L) -> Self {
+        self.terms.extend(rhs.into().terms);
+        self
+    }
+}
+
+impl<L: Into<LinearCombination>> Sub<L> for LinearCombination {
+    type Output = Self;
+
+    fn sub(self, rhs: L) -> Self {
+        self + -rhs.into()
+    }
+}
+
+impl Neg for LinearCombination {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        for (_, coefficient) in &mut self.terms {
+            *coefficient = coefficient.negate_reduced();
+        }
+        self
+    }
+}
+
+impl<S: Into<Scalar>> Mul<S> for LinearCombination {
+    type Output = Self;
+
+    fn mul(mut self, rhs: S) -> Self {
+        let rhs = rhs.into();
+        for (_, coefficient) in &mut self.terms {
+            *coefficient = coefficient.multiply_reduced(rhs);
+        }
+        self
+    }
+}
+
+impl<L: Into<LinearCombination>> Add<L> for Variable {
+    type Output = LinearCombination;
+
+    fn add(self, rhs: L) -> LinearCombination {
+        LinearCombination::from(self) + rhs
+    }
+}
+
+impl<L: Into<LinearCombination>> Sub<L> for Variable {
+    type Output = LinearCombination;
+
+    fn sub(self, rhs: L) -> LinearCombination {
+        LinearCombination::from(self) - rhs
+    }
+}
+
+impl Neg for Variable {
+    type Output = LinearCombination;
+
+    fn neg(self) -> LinearCombination {
+        -LinearCombination::from(self)
+    }
+}
+
+impl<S: Into<Scalar>> Mul<S> for Variable {
+    type Output = LinearCombination;
+
+    fn mul(self, rhs: S) -> LinearCombination {
+        LinearCombination::from(self) * rhs
+    }
+}
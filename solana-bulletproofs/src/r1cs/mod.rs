@@ -0,0 +1,123 @@
+//! Proofs that committed values satisfy a rank-1 constraint system.
+//!
+//! The proof follows section 5 of the Bulletproofs paper. A constraint system consists of `n`
+//! multipliers with left inputs `a_L`, right inputs `a_R` and outputs `a_O = a_L o a_R`, and of
+//! linear constraints on the multiplier variables and the values of the commitments `V`. Gadgets
+//! build the constraints through the `ConstraintSystem` trait, which both the `Prover` and the
+//! `Verifier` implement, so that both sides build the same system.
+//!
+//! The constraints are combined with powers of the challenge `z` into the weight vectors `W_L`,
+//! `W_R`, `W_O` and `W_V` and the constant `w_c`, and the prover shows that
+//! `<W_L, a_L> + <W_R, a_R> + <W_O, a_O> = <W_V, v> + w_c` with an inner product argument over
+//! `n` rounded up to a power of two. The message schedule is the R1CS proof schedule of the
+//! `transcript` module.
+//!
+//! Gadgets that need challenges, e.g. to compare multisets, specify randomized constraints through
+//! the `RandomizableConstraintSystem` trait. They run in a second phase, after the prover has
+//! committed to the variables of the first phase with `A_I1`, `A_O1` and `S1`, and squeeze their
+//! challenges from the transcript. The variables of the second phase are committed with `A_I2`,
+//! `A_O2` and `S2`, and the challenge `u` scales their generators in the inner product argument.
+//!
+//! The `gadgets` module provides gadgets for booleans, bit decompositions, comparisons, equality,
+//! selection, sums and shuffles, and the `cloak` module gadgets for transactions with multiple
+//! assets.
+//!
+//! The verifier combines all of its checks into one multiscalar multiplication of size
+//! `2n + 2 lg(n) + m + 10` for `m` commitments, or `2n + 2 lg(n) + m + 13` with randomized
+//! constraints, which the syscall backend evaluates on-chain. The prover only runs off-chain.
+
+#![allow(non_snake_case)]
+
+pub mod cloak;
+mod constraint_system;
+pub mod gadgets;
+mod linear_combination;
+mod proof;
+#[cfg(not(target_os = "solana"))]
+mod prover;
+mod verifier;
+
+#[cfg(not(target_os = "solana"))]
+pub use prover::{Prover, RandomizingProver};
+use {crate::generators::BulletproofGens, solana_ristretto::scalar::Scalar};
+pub use {
+    constraint_system::{
+        ConstraintSystem, RandomizableConstraintSystem, RandomizedConstraintSystem,
+    },
+    linear_combination::{LinearCombination, Variable},
+    proof::{R1CSProof, R1CSProofView},
+    verifier::{RandomizingVerifier, Verifier},
+};
+
+/// The callbacks of randomized constraints, which run in the constraint system `CS` of the
+/// second phase.
+type DeferredConstraints<CS> = Vec<Box<dyn FnOnce(&mut CS) -> Result<(), String>>>;
+
+/// The constraints of a constraint system, combined with powers of the challenge `z`.
+struct FlattenedConstraints {
+    /// Weights of the left inputs of the multipliers
+    wL: Vec<Scalar>,
+    /// Weights of the right inputs of the multipliers
+    wR: Vec<Scalar>,
+    /// Weights of the outputs of the multipliers
+    wO: Vec<Scalar>,
+    /// Weights of the committed values
+    wV: Vec<Scalar>,
+    /// The constant of the combined constraint
+    wc: Scalar,
+}
+
+/// Combine the constraints into weights, where constraint `q` is multiplied by `z^(q+1)`, for a
+/// constraint system with `n` multipliers and `m` commitments.
+///
+/// The weights of the committed values and the constant move to the other side of the combined
+/// constraint, so they are subtracted.
+fn flatten_constraints(
+    constraints: &[LinearCombination],
+    z: &Scalar,
+    n: usize,
+    m: usize,
+) -> Result<FlattenedConstraints, String> {
+    let mut wL = vec![Scalar::zero(); n];
+    let mut wR = vec![Scalar::zero(); n];
+    let mut wO = vec![Scalar::zero(); n];
+    let mut wV = vec![Scalar::zero(); m];
+    let mut wc = Scalar::zero();
+
+    let mut exp_z = *z;
+    for lc in constraints {
+        for (variable, coefficient) in &lc.terms {
+            let weight = exp_z.multiply_scalar(*coefficient)?;
+            match variable {
+                Variable::MultiplierLeft(i) => add_weight(&mut wL, *i, weight)?,
+                Variable::MultiplierRight(i) => add_weight(&mut wR, *i, weight)?,
+                Variable::MultiplierOutput(i) => add_weight(&mut wO, *i, weight)?,
+                Variable::Committed(i) => add_weight(&mut wV, *i, weight.negate()?)?,
+                Variable::One() => wc = wc.subtract_scalar(weight)?,
+            }
+        }
+        exp_z = exp_z.multiply_scalar(*z)?;
+    }
+
+    Ok(FlattenedConstraints { wL, wR, wO, wV, wc })
+}
+
+fn add_weight(weights: &mut [Scalar], i: usize, weight: Scalar) -> Result<(), String> {
+    let entry = weights
+        .get_mut(i)
+        .ok_or_else(|| "R1CS constraint contains an unknown variable".to_string())?;
+    *entry = entry.add_scalar(weight)?;
+    Ok(())
+}
+
+/// The number of multipliers of the proof for `n` multipliers, i.e. `n` rounded up to a power of
+/// two, for which the first party of the generators must have capacity.
+fn padded_multipliers_len(bp_gens: &BulletproofGens, n: usize) -> Result<usize, String> {
+    let padded_n = n
+        .checked_next_power_of_two()
+        .ok_or_else(|| "R1CS proof has too many multipliers".to_string())?;
+    if bp_gens.gens_capacity < padded_n || bp_gens.party_capacity < 1 {
+        return Err("Bulletproof generators have insufficient capacity".to_string());
+    }
+    Ok(padded_n)
+}
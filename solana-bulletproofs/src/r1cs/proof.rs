@@ -0,0 +1,313 @@
+//! The R1CS proof and its serialization.
+
+use {
+    crate::{
+        encoding::{self, ProofBytes, ProofType, HEADER_SIZE},
+        inner_product_proof::{InnerProductElements, InnerProductProof, InnerProductProofView},
+    },
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// The number of points and scalars of the proof before its inner product proof.
+const NUM_ELEMENTS: usize = 11;
+
+/// The number of points of the second phase.
+const NUM_SECOND_PHASE_ELEMENTS: usize = 3;
+
+/// The commitments to the variables that are allocated by randomized constraints.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct SecondPhaseCommitments {
+    /// Commitment to the left and right inputs of the second phase multipliers
+    pub(super) A_I2: RistrettoPoint,
+    /// Commitment to the outputs of the second phase multipliers
+    pub(super) A_O2: RistrettoPoint,
+    /// Commitment to the second phase blinding factors
+    pub(super) S2: RistrettoPoint,
+}
+
+/// A proof that the assignments of a constraint system satisfy all of its constraints.
+#[derive(Clone, Debug, PartialEq)]
+pub struct R1CSProof {
+    /// Commitment to the left and right inputs of the multipliers
+    pub(super) A_I1: RistrettoPoint,
+    /// Commitment to the outputs of the multipliers
+    pub(super) A_O1: RistrettoPoint,
+    /// Commitment to the blinding factors
+    pub(super) S1: RistrettoPoint,
+    /// Commitments of the second phase, if the constraint system has randomized constraints
+    pub(super) second_phase: Option<SecondPhaseCommitments>,
+    /// Commitment to the `t_1` coefficient of `t(x)`
+    pub(super) T_1: RistrettoPoint,
+    /// Commitment to the `t_3` coefficient of `t(x)`
+    pub(super) T_3: RistrettoPoint,
+    /// Commitment to the `t_4` coefficient of `t(x)`
+    pub(super) T_4: RistrettoPoint,
+    /// Commitment to the `t_5` coefficient of `t(x)`
+    pub(super) T_5: RistrettoPoint,
+    /// Commitment to the `t_6` coefficient of `t(x)`
+    pub(super) T_6: RistrettoPoint,
+    /// Evaluation of the polynomial `t(x)` at the challenge point `x`
+    pub(super) t_x: Scalar,
+    /// Blinding factor for the synthetic commitment to `t(x)`
+    pub(super) t_x_blinding: Scalar,
+    /// Blinding factor for the synthetic commitment to the inner-product arguments
+    pub(super) e_blinding: Scalar,
+    /// Proof data for the inner-product argument.
+    pub(super) ipp_proof: InnerProductProof,
+}
+
+impl R1CSProof {
+    /// The size of the proof in bytes, `2 + 32 * (2 lg(n) + 13)` for `n` multipliers rounded up
+    /// to a power of two, and `2 + 32 * (2 lg(n) + 16)` with randomized constraints.
+    pub fn serialized_size(&self) -> usize {
+        HEADER_SIZE + (2 * self.ipp_proof.L_vec.len() + self.num_elements() + 2) * 32
+    }
+
+    fn num_elements(&self) -> usize {
+        match self.second_phase {
+            Some(_) => NUM_ELEMENTS + NUM_SECOND_PHASE_ELEMENTS,
+            None => NUM_ELEMENTS,
+        }
+    }
+
+    /// Serialize the proof as
+    /// `header || A_I1 || A_O1 || S1 || T_1 || T_3 || T_4 || T_5 || T_6 || t_x || t_x_blinding ||
+    /// e_blinding || ipp`, where `header` is the header of the `encoding` module and `ipp` is the
+    /// body of the serialized inner product proof.
+    ///
+    /// A proof with randomized constraints has the type `R1CSRandomized` and the second phase
+    /// commitments `A_I2 || A_O2 || S2` after `S1`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.serialized_size());
+        let mut points = vec![&self.A_I1, &self.A_O1, &self.S1];
+        match &self.second_phase {
+            Some(second_phase) => {
+                buf.extend_from_slice(&encoding::header(ProofType::R1CSRandomized));
+                points.extend([&second_phase.A_I2, &second_phase.A_O2, &second_phase.S2]);
+            }
+            None => buf.extend_from_slice(&encoding::header(ProofType::R1CS)),
+        }
+        points.extend([&self.T_1, &self.T_3, &self.T_4, &self.T_5, &self.T_6]);
+        for point in points {
+            buf.extend_from_slice(&point.to_bytes());
+        }
+        buf.extend_from_slice(&self.t_x.to_bytes());
+        buf.extend_from_slice(&self.t_x_blinding.to_bytes());
+        buf.extend_from_slice(&self.e_blinding.to_bytes());
+        self.ipp_proof.write_body(&mut buf);
+        buf
+    }
+
+    /// Deserialize a proof that was serialized with `to_bytes` by any version of the encoding.
+    ///
+    /// Every point must be a valid Ristretto encoding and every scalar must be canonical.
+    pub fn from_bytes(slice: &[u8]) -> Result<Self, String> {
+        R1CSProofView::from_bytes(slice).map(|view| view.to_proof())
+    }
+}
+
+/// A validated R1CS proof that borrows its encoding.
+///
+/// `from_bytes` validates the encoding like `R1CSProof::from_bytes`, but does not copy the body
+/// into an owned proof: `Verifier::verify_view` reads every point and scalar from the borrowed
+/// bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct R1CSProofView<'a> {
+    /// The points and scalars before the inner product proof
+    head: &'a [u8],
+    /// The inner product proof
+    ipp_proof: InnerProductProofView<'a>,
+}
+
+impl<'a> R1CSProofView<'a> {
+    /// Validate a proof that was serialized with `R1CSProof::to_bytes` by any version of the
+    /// encoding, and borrow it.
+    pub fn from_bytes(slice: &'a [u8]) -> Result<Self, String> {
+        let (body, proof_type) =
+            ProofBytes::body_of_any(slice, &[ProofType::R1CS, ProofType::R1CSRandomized])?;
+        let num_elements = match proof_type {
+            ProofType::R1CSRandomized => NUM_ELEMENTS + NUM_SECOND_PHASE_ELEMENTS,
+            _ => NUM_ELEMENTS,
+        };
+        if body.len() < num_elements * 32 {
+            return Err("R1CS proof has an invalid length".to_string());
+        }
+        let (head, ipp_bytes) = body.split_at(num_elements * 32);
+        for index in 0..num_elements - 3 {
+            if !encoding::point_at(head, index).is_valid() {
+                return Err("R1CS proof contains an invalid point".to_string());
+            }
+        }
+        for index in num_elements - 3..num_elements {
+            if !encoding::scalar_at(head, index).is_canonical() {
+                return Err("R1CS proof contains a non-canonical scalar".to_string());
+            }
+        }
+        let ipp_proof = InnerProductProofView::from_body(ipp_bytes)?;
+
+        Ok(Self { head, ipp_proof })
+    }
+
+    /// Copy the proof into an owned proof.
+    pub fn to_proof(&self) -> R1CSProof {
+        R1CSProof {
+            A_I1: self.A_I1(),
+            A_O1: self.A_O1(),
+            S1: self.S1(),
+            second_phase: self.second_phase(),
+            T_1: self.T_1(),
+            T_3: self.T_3(),
+            T_4: self.T_4(),
+            T_5: self.T_5(),
+            T_6: self.T_6(),
+            t_x: self.t_x(),
+            t_x_blinding: self.t_x_blinding(),
+            e_blinding: self.e_blinding(),
+            ipp_proof: self.ipp_proof.to_proof(),
+        }
+    }
+
+    /// The index of the first element after the commitments of the first and second phase.
+    fn t_offset(&self) -> usize {
+        self.head.len() / 32 - 8
+    }
+}
+
+/// The points and scalars of an R1CS proof, which `R1CSProof` owns and `R1CSProofView` reads from
+/// its encoding.
+pub(super) trait R1CSProofElements {
+    type InnerProduct: InnerProductElements;
+
+    fn A_I1(&self) -> RistrettoPoint;
+    fn A_O1(&self) -> RistrettoPoint;
+    fn S1(&self) -> RistrettoPoint;
+    fn second_phase(&self) -> Option<SecondPhaseCommitments>;
+    fn T_1(&self) -> RistrettoPoint;
+    fn T_3(&self) -> RistrettoPoint;
+    fn T_4(&self) -> RistrettoPoint;
+    fn T_5(&self) -> RistrettoPoint;
+    fn T_6(&self) -> RistrettoPoint;
+    fn t_x(&self) -> Scalar;
+    fn t_x_blinding(&self) -> Scalar;
+    fn e_blinding(&self) -> Scalar;
+    fn ipp_proof(&self) -> &Self::InnerProduct;
+}
+
+impl R1CSProofElements for R1CSProof {
+    type InnerProduct = InnerProductProof;
+
+    fn A_I1(&self) -> RistrettoPoint {
+        self.A_I1
+    }
+
+    fn A_O1(&self) -> RistrettoPoint {
+        self.A_O1
+    }
+
+    fn S1(&self) -> RistrettoPoint {
+        self.S1
+    }
+
+    fn second_phase(&self) -> Option<SecondPhaseCommitments> {
+        self.second_phase
+    }
+
+    fn T_1(&self) -> RistrettoPoint {
+        self.T_1
+    }
+
+    fn T_3(&self) -> RistrettoPoint {
+        self.T_3
+    }
+
+    fn T_4(&self) -> RistrettoPoint {
+        self.T_4
+    }
+
+    fn T_5(&self) -> RistrettoPoint {
+        self.T_5
+    }
+
+    fn T_6(&self) -> RistrettoPoint {
+        self.T_6
+    }
+
+    fn t_x(&self) -> Scalar {
+        self.t_x
+    }
+
+    fn t_x_blinding(&self) -> Scalar {
+        self.t_x_blinding
+    }
+
+    fn e_blinding(&self) -> Scalar {
+        self.e_blinding
+    }
+
+    fn ipp_proof(&self) -> &InnerProductProof {
+        &self.ipp_proof
+    }
+}
+
+impl<'a> R1CSProofElements for R1CSProofView<'a> {
+    type InnerProduct = InnerProductProofView<'a>;
+
+    fn A_I1(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, 0)
+    }
+
+    fn A_O1(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, 1)
+    }
+
+    fn S1(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, 2)
+    }
+
+    fn second_phase(&self) -> Option<SecondPhaseCommitments> {
+        if self.t_offset() == 3 {
+            return None;
+        }
+        Some(SecondPhaseCommitments {
+            A_I2: encoding::point_at(self.head, 3),
+            A_O2: encoding::point_at(self.head, 4),
+            S2: encoding::point_at(self.head, 5),
+        })
+    }
+
+    fn T_1(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, self.t_offset())
+    }
+
+    fn T_3(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, self.t_offset() + 1)
+    }
+
+    fn T_4(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, self.t_offset() + 2)
+    }
+
+    fn T_5(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, self.t_offset() + 3)
+    }
+
+    fn T_6(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, self.t_offset() + 4)
+    }
+
+    fn t_x(&self) -> Scalar {
+        encoding::scalar_at(self.head, self.t_offset() + 5)
+    }
+
+    fn t_x_blinding(&self) -> Scalar {
+        encoding::scalar_at(self.head, self.t_offset() + 6)
+    }
+
+    fn e_blinding(&self) -> Scalar {
+        encoding::scalar_at(self.head, self.t_offset() + 7)
+    }
+
+    fn ipp_proof(&self) -> &InnerProductProofView<'a> {
+        &self.ipp_proof
+    }
+}
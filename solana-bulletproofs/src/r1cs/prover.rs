@@ -0,0 +1,499 @@
+//! The prover of an R1CS proof.
+
+use {
+    super::{
+        flatten_constraints, padded_multipliers_len, proof::SecondPhaseCommitments,
+        ConstraintSystem, DeferredConstraints, LinearCombination, R1CSProof,
+        RandomizableConstraintSystem, RandomizedConstraintSystem, Variable,
+    },
+    crate::{
+        generators::{BulletproofGens, PedersenGens},
+        inner_product_proof::InnerProductProof,
+        transcript::{
+            self as schedule, r1cs_randomized_constraints, r1cs_second_phase, r1cs_transcript,
+        },
+        util::{self, random_scalar, Poly6, VecPoly3},
+    },
+    rand_core::{CryptoRng, OsRng, RngCore},
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+    std::mem,
+};
+
+/// A constraint system that holds the assignments of all variables and creates a proof.
+pub struct Prover<'t, 'g> {
+    transcript: &'t mut Transcript,
+    pc_gens: &'g PedersenGens,
+    /// The constraints, each of which requires a linear combination to be zero
+    constraints: Vec<LinearCombination>,
+    /// Assignments of the left inputs of the multipliers
+    a_L: Vec<Scalar>,
+    /// Assignments of the right inputs of the multipliers
+    a_R: Vec<Scalar>,
+    /// Assignments of the outputs of the multipliers
+    a_O: Vec<Scalar>,
+    /// The committed values
+    v: Vec<Scalar>,
+    /// The blinding factors of the committed values
+    v_blinding: Vec<Scalar>,
+    /// The commitments to the values
+    V: Vec<RistrettoPoint>,
+    /// The multiplier whose right input is the next allocated variable
+    pending_multiplier: Option<usize>,
+    /// The callbacks of the randomized constraints, which run after the first phase commitments
+    deferred_constraints: DeferredConstraints<RandomizingProver<'t, 'g>>,
+}
+
+/// A prover in the second phase, which builds randomized constraints.
+pub struct RandomizingProver<'t, 'g> {
+    prover: Prover<'t, 'g>,
+}
+
+impl<'t, 'g> Prover<'t, 'g> {
+    /// Create a prover whose proof is bound to `transcript`.
+    ///
+    /// The transcript is only used when the proof is created, after all commitments.
+    pub fn new(pc_gens: &'g PedersenGens, transcript: &'t mut Transcript) -> Self {
+        Prover {
+            transcript,
+            pc_gens,
+            constraints: Vec::new(),
+            a_L: Vec::new(),
+            a_R: Vec::new(),
+            a_O: Vec::new(),
+            v: Vec::new(),
+            v_blinding: Vec::new(),
+            V: Vec::new(),
+            pending_multiplier: None,
+            deferred_constraints: Vec::new(),
+        }
+    }
+
+    /// Commit to the value `v` with the blinding factor `v_blinding`.
+    ///
+    /// Returns the commitment, which the verifier needs, and the variable of the value.
+    pub fn commit(
+        &mut self,
+        v: Scalar,
+        v_blinding: Scalar,
+    ) -> Result<(RistrettoPoint, Variable), String> {
+        check_canonical(&[v, v_blinding])?;
+        let V = self.pc_gens.commit(v, v_blinding)?;
+        let variable = Variable::Committed(self.v.len());
+        self.v.push(v);
+        self.v_blinding.push(v_blinding);
+        self.V.push(V);
+        Ok((V, variable))
+    }
+
+    /// Create a proof for the constraint system with randomness from the operating system.
+    pub fn prove(self, bp_gens: &BulletproofGens) -> Result<R1CSProof, String> {
+        self.prove_with_rng(bp_gens, &mut OsRng)
+    }
+
+    /// Create a proof for the constraint system with randomness from `rng`.
+    ///
+    /// The randomness of the prover is derived from the transcript, the witness, and `rng`, so a
+    /// weak `rng` does not leak the witness on its own. The assignments must satisfy all
+    /// constraints.
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(
+        mut self,
+        bp_gens: &BulletproofGens,
+        rng: &mut R,
+    ) -> Result<R1CSProof, String> {
+        self.check_constraints(0)?;
+
+        let n1 = self.a_L.len();
+        padded_multipliers_len(bp_gens, n1)?;
+        let share = bp_gens.share(0);
+        let pc_gens = self.pc_gens;
+
+        let mut transcript = r1cs_transcript(&mut *self.transcript, self.V.len() as u64);
+        for V in &self.V {
+            transcript = transcript.append::<schedule::V>(V);
+        }
+
+        let mut rng_builder = transcript.as_transcript().build_rng();
+        for (v, v_blinding) in self.v.iter().zip(&self.v_blinding) {
+            rng_builder = rng_builder
+                .rekey_with_witness_bytes(b"v", &v.to_bytes())
+                .rekey_with_witness_bytes(b"v_blinding", &v_blinding.to_bytes());
+        }
+        let mut rng = rng_builder.finalize(rng);
+
+        let i_blinding1 = random_scalar(&mut rng);
+        let o_blinding1 = random_scalar(&mut rng);
+        let s_blinding1 = random_scalar(&mut rng);
+        let mut s_L = (0..n1).map(|_| random_scalar(&mut rng)).collect::<Vec<_>>();
+        let mut s_R = (0..n1).map(|_| random_scalar(&mut rng)).collect::<Vec<_>>();
+
+        let points = [
+            &[pc_gens.B_blinding()],
+            &share.G(n1).copied().collect::<Vec<_>>()[..],
+            &share.H(n1).copied().collect::<Vec<_>>()[..],
+        ]
+        .concat();
+        let A_I1_scalars = [&[i_blinding1], &self.a_L[..], &self.a_R[..]].concat();
+        let A_I1 = RistrettoPoint::multiscalar_multiply(&A_I1_scalars, &points)?;
+        let A_O1_scalars = [&[o_blinding1], &self.a_O[..]].concat();
+        let A_O1 = RistrettoPoint::multiscalar_multiply(&A_O1_scalars, &points[..n1 + 1])?;
+        let S1_scalars = [&[s_blinding1], &s_L[..], &s_R[..]].concat();
+        let S1 = RistrettoPoint::multiscalar_multiply(&S1_scalars, &points)?;
+
+        let transcript = transcript
+            .append::<schedule::AI1>(&A_I1)
+            .append::<schedule::AO1>(&A_O1)
+            .append::<schedule::S1>(&S1);
+
+        // without randomized constraints, all multipliers belong to the first phase and the
+        // challenge u that scales the generators of the second phase is one
+        let (mut i_blinding, mut o_blinding, mut s_blinding) =
+            (i_blinding1, o_blinding1, s_blinding1);
+        let mut u = Scalar::one();
+        let mut second_phase = None;
+        let transcript = if self.deferred_constraints.is_empty() {
+            transcript
+        } else {
+            r1cs_randomized_constraints(transcript);
+            let num_constraints = self.constraints.len();
+            self = self.create_randomized_constraints()?;
+            self.check_constraints(num_constraints)?;
+
+            let n = self.a_L.len();
+            padded_multipliers_len(bp_gens, n)?;
+            let i_blinding2 = random_scalar(&mut rng);
+            let o_blinding2 = random_scalar(&mut rng);
+            let s_blinding2 = random_scalar(&mut rng);
+            let s_L2 = (n1..n).map(|_| random_scalar(&mut rng)).collect::<Vec<_>>();
+            let s_R2 = (n1..n).map(|_| random_scalar(&mut rng)).collect::<Vec<_>>();
+
+            let points = [
+                &[pc_gens.B_blinding()],
+                &share.G(n).skip(n1).copied().collect::<Vec<_>>()[..],
+                &share.H(n).skip(n1).copied().collect::<Vec<_>>()[..],
+            ]
+            .concat();
+            let A_I2_scalars = [&[i_blinding2], &self.a_L[n1..], &self.a_R[n1..]].concat();
+            let A_I2 = RistrettoPoint::multiscalar_multiply(&A_I2_scalars, &points)?;
+            let A_O2_scalars = [&[o_blinding2], &self.a_O[n1..]].concat();
+            let A_O2 = RistrettoPoint::multiscalar_multiply(&A_O2_scalars, &points[..n - n1 + 1])?;
+            let S2_scalars = [&[s_blinding2], &s_L2[..], &s_R2[..]].concat();
+            let S2 = RistrettoPoint::multiscalar_multiply(&S2_scalars, &points)?;
+
+            let transcript = r1cs_second_phase(&mut *self.transcript)
+                .append::<schedule::AI2>(&A_I2)
+                .append::<schedule::AO2>(&A_O2)
+                .append::<schedule::S2>(&S2);
+            let (transcript, challenge) = transcript.challenge_scalar::<schedule::U>();
+            u = challenge;
+
+            i_blinding = i_blinding.add_scalar(u.multiply_scalar(i_blinding2)?)?;
+            o_blinding = o_blinding.add_scalar(u.multiply_scalar(o_blinding2)?)?;
+            s_blinding = s_blinding.add_scalar(u.multiply_scalar(s_blinding2)?)?;
+            s_L.extend(s_L2);
+            s_R.extend(s_R2);
+            second_phase = Some(SecondPhaseCommitments { A_I2, A_O2, S2 });
+            transcript
+        };
+
+        let n = self.a_L.len();
+        let padded_n = padded_multipliers_len(bp_gens, n)?;
+
+        let (transcript, y) = transcript.challenge_scalar::<schedule::Y>();
+        let (transcript, z) = transcript.challenge_scalar::<schedule::Z>();
+
+        let weights = flatten_constraints(&self.constraints, &z, n, self.v.len())?;
+
+        // l(x) = (a_L + y^-n o W_R) x + a_O x^2 + s_L x^3
+        // r(x) = (W_O - y^n) + (y^n o a_R + W_L) x + y^n o s_R x^3
+        let exp_y_inv = util::exp_iter(y.invert()?)
+            .take(padded_n)
+            .collect::<Vec<_>>();
+        let mut l_poly = VecPoly3::zero(n);
+        let mut r_poly = VecPoly3::zero(n);
+        let mut exp_y = Scalar::one();
+        for i in 0..n {
+            l_poly.1[i] = self.a_L[i].add_scalar(exp_y_inv[i].multiply_scalar(weights.wR[i])?)?;
+            l_poly.2[i] = self.a_O[i];
+            l_poly.3[i] = s_L[i];
+            r_poly.0[i] = weights.wO[i].subtract_scalar(exp_y)?;
+            r_poly.1[i] = exp_y
+                .multiply_scalar(self.a_R[i])?
+                .add_scalar(weights.wL[i])?;
+            r_poly.3[i] = exp_y.multiply_scalar(s_R[i])?;
+
+            exp_y = exp_y.multiply_scalar(y)?;
+        }
+
+        let t_poly = l_poly
+            .special_inner_product(&r_poly)
+            .ok_or_else(|| "Polynomial lengths do not match".to_string())?;
+
+        let t_1_blinding = random_scalar(&mut rng);
+        let t_3_blinding = random_scalar(&mut rng);
+        let t_4_blinding = random_scalar(&mut rng);
+        let t_5_blinding = random_scalar(&mut rng);
+        let t_6_blinding = random_scalar(&mut rng);
+        let T_1 = pc_gens.commit(t_poly.0, t_1_blinding)?;
+        let T_3 = pc_gens.commit(t_poly.2, t_3_blinding)?;
+        let T_4 = pc_gens.commit(t_poly.3, t_4_blinding)?;
+        let T_5 = pc_gens.commit(t_poly.4, t_5_blinding)?;
+        let T_6 = pc_gens.commit(t_poly.5, t_6_blinding)?;
+
+        let transcript = transcript
+            .append::<schedule::T1>(&T_1)
+            .append::<schedule::T3>(&T_3)
+            .append::<schedule::T4>(&T_4)
+            .append::<schedule::T5>(&T_5)
+            .append::<schedule::T6>(&T_6);
+        let (transcript, x) = transcript.challenge_scalar::<schedule::X>();
+
+        // the coefficient t_2 is <W_V, v>, so its blinding is <W_V, v_blinding>
+        let t_2_blinding = util::inner_product(&weights.wV, &self.v_blinding)
+            .ok_or_else(|| "Polynomial lengths do not match".to_string())?;
+        let t_blinding_poly = Poly6(
+            t_1_blinding,
+            t_2_blinding,
+            t_3_blinding,
+            t_4_blinding,
+            t_5_blinding,
+            t_6_blinding,
+        );
+
+        let t_x = t_poly.eval(x);
+        let t_x_blinding = t_blinding_poly.eval(x);
+
+        // the padding multipliers have zero inputs and weights, so only r(x) has the entries
+        // -y^i at their positions
+        let mut l_vec = l_poly.eval(x);
+        l_vec.resize(padded_n, Scalar::zero());
+        let mut r_vec = r_poly.eval(x);
+        for _ in n..padded_n {
+            r_vec.push(exp_y.negate()?);
+            exp_y = exp_y.multiply_scalar(y)?;
+        }
+
+        // e_blinding = i_blinding x + o_blinding x^2 + s_blinding x^3
+        let e_blinding = x.multiply_scalar(i_blinding.add_scalar(
+            x.multiply_scalar(o_blinding.add_scalar(x.multiply_scalar(s_blinding)?)?)?,
+        )?)?;
+
+        let transcript = transcript
+            .append::<schedule::Tx>(&t_x)
+            .append::<schedule::TxBlinding>(&t_x_blinding)
+            .append::<schedule::EBlinding>(&e_blinding);
+        let (transcript, w) = transcript.challenge_scalar::<schedule::W>();
+
+        // the inner product argument proves <l, r> = t(x) with respect to the generators G and
+        // H' = y^-n o H, where the generators of the second phase and of the padding are also
+        // scaled by u; the factors apply the scaling instead of rescaling the generators
+        let Q = pc_gens.B().multiply(&w)?;
+        let G_factors = (0..padded_n)
+            .map(|i| if i < n1 { Scalar::one() } else { u })
+            .collect::<Vec<_>>();
+        let H_factors = exp_y_inv
+            .iter()
+            .zip(&G_factors)
+            .map(|(exp_y_inv, G_factor)| exp_y_inv.multiply_scalar(*G_factor))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ipp_proof = InnerProductProof::create(
+            transcript.into_transcript(),
+            &Q,
+            &G_factors,
+            &H_factors,
+            share.G(padded_n).copied().collect(),
+            share.H(padded_n).copied().collect(),
+            l_vec,
+            r_vec,
+        )?;
+
+        Ok(R1CSProof {
+            A_I1,
+            A_O1,
+            S1,
+            second_phase,
+            T_1,
+            T_3,
+            T_4,
+            T_5,
+            T_6,
+            t_x,
+            t_x_blinding,
+            e_blinding,
+            ipp_proof,
+        })
+    }
+
+    /// Run the randomized constraints after the first phase commitments.
+    fn create_randomized_constraints(mut self) -> Result<Self, String> {
+        // a pending multiplier was committed in the first phase, so it cannot be completed
+        self.pending_multiplier = None;
+
+        let deferred_constraints = mem::take(&mut self.deferred_constraints);
+        let mut randomizing_prover = RandomizingProver { prover: self };
+        for callback in deferred_constraints {
+            callback(&mut randomizing_prover)?;
+        }
+        Ok(randomizing_prover.prover)
+    }
+
+    /// Check that the assignments satisfy the constraints from the index `start` on.
+    fn check_constraints(&self, start: usize) -> Result<(), String> {
+        for lc in &self.constraints[start..] {
+            if self.eval(lc)? != Scalar::zero() {
+                return Err("R1CS constraints are not satisfied".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluate a linear combination with the assignments of the prover.
+    fn eval(&self, lc: &LinearCombination) -> Result<Scalar, String> {
+        let mut result = Scalar::zero();
+        for (variable, coefficient) in &lc.terms {
+            let value = match variable {
+                Variable::MultiplierLeft(i) => self.a_L.get(*i).copied(),
+                Variable::MultiplierRight(i) => self.a_R.get(*i).copied(),
+                Variable::MultiplierOutput(i) => self.a_O.get(*i).copied(),
+                Variable::Committed(i) => self.v.get(*i).copied(),
+                Variable::One() => Some(Scalar::one()),
+            }
+            .ok_or_else(|| "R1CS constraint contains an unknown variable".to_string())?;
+            result = result.add_scalar(coefficient.multiply_scalar(value)?)?;
+        }
+        Ok(result)
+    }
+}
+
+impl ConstraintSystem for Prover<'_, '_> {
+    fn multiply(
+        &mut self,
+        mut left: LinearCombination,
+        mut right: LinearCombination,
+    ) -> Result<(Variable, Variable, Variable), String> {
+        let l = self.eval(&left)?;
+        let r = self.eval(&right)?;
+        let (l_var, r_var, o_var) = self.allocate_multiplier(Some((l, r)))?;
+
+        let minus_one = Scalar::one().negate()?;
+        left.terms.push((l_var, minus_one));
+        right.terms.push((r_var, minus_one));
+        self.constrain(left);
+        self.constrain(right);
+
+        Ok((l_var, r_var, o_var))
+    }
+
+    fn allocate(&mut self, assignment: Option<Scalar>) -> Result<Variable, String> {
+        let scalar =
+            assignment.ok_or_else(|| "R1CS prover is missing an assignment".to_string())?;
+        check_canonical(&[scalar])?;
+
+        match self.pending_multiplier {
+            None => {
+                let i = self.a_L.len();
+                self.pending_multiplier = Some(i);
+                self.a_L.push(scalar);
+                self.a_R.push(Scalar::zero());
+                self.a_O.push(Scalar::zero());
+                Ok(Variable::MultiplierLeft(i))
+            }
+            Some(i) => {
+                self.pending_multiplier = None;
+                self.a_R[i] = scalar;
+                self.a_O[i] = self.a_L[i].multiply_scalar(scalar)?;
+                Ok(Variable::MultiplierRight(i))
+            }
+        }
+    }
+
+    fn allocate_multiplier(
+        &mut self,
+        input_assignments: Option<(Scalar, Scalar)>,
+    ) -> Result<(Variable, Variable, Variable), String> {
+        let (l, r) =
+            input_assignments.ok_or_else(|| "R1CS prover is missing an assignment".to_string())?;
+        check_canonical(&[l, r])?;
+
+        let i = self.a_L.len();
+        self.a_L.push(l);
+        self.a_R.push(r);
+        self.a_O.push(l.multiply_scalar(r)?);
+
+        Ok((
+            Variable::MultiplierLeft(i),
+            Variable::MultiplierRight(i),
+            Variable::MultiplierOutput(i),
+        ))
+    }
+
+    fn multipliers_len(&self) -> usize {
+        self.a_L.len()
+    }
+
+    fn constraints_len(&self) -> usize {
+        self.constraints.len()
+    }
+
+    fn constrain(&mut self, lc: LinearCombination) {
+        self.constraints.push(lc);
+    }
+}
+
+impl<'t, 'g> RandomizableConstraintSystem for Prover<'t, 'g> {
+    type RandomizedCS = RandomizingProver<'t, 'g>;
+
+    fn specify_randomized_constraints<F>(&mut self, callback: F) -> Result<(), String>
+    where
+        F: 'static + FnOnce(&mut Self::RandomizedCS) -> Result<(), String>,
+    {
+        self.deferred_constraints.push(Box::new(callback));
+        Ok(())
+    }
+}
+
+impl ConstraintSystem for RandomizingProver<'_, '_> {
+    fn multiply(
+        &mut self,
+        left: LinearCombination,
+        right: LinearCombination,
+    ) -> Result<(Variable, Variable, Variable), String> {
+        self.prover.multiply(left, right)
+    }
+
+    fn allocate(&mut self, assignment: Option<Scalar>) -> Result<Variable, String> {
+        self.prover.allocate(assignment)
+    }
+
+    fn allocate_multiplier(
+        &mut self,
+        input_assignments: Option<(Scalar, Scalar)>,
+    ) -> Result<(Variable, Variable, Variable), String> {
+        self.prover.allocate_multiplier(input_assignments)
+    }
+
+    fn multipliers_len(&self) -> usize {
+        self.prover.multipliers_len()
+    }
+
+    fn constraints_len(&self) -> usize {
+        self.prover.constraints_len()
+    }
+
+    fn constrain(&mut self, lc: LinearCombination) {
+        self.prover.constrain(lc)
+    }
+}
+
+impl RandomizedConstraintSystem for RandomizingProver<'_, '_> {
+    fn challenge_scalar(&mut self, label: &[u8]) -> Scalar {
+        self.prover.transcript.challenge_scalar(label)
+    }
+}
+
+fn check_canonical(scalars: &[Scalar]) -> Result<(), String> {
+    if scalars.iter().any(|scalar| !scalar.is_canonical()) {
+        return Err("R1CS witness contains a non-canonical scalar".to_string());
+    }
+    Ok(())
+}
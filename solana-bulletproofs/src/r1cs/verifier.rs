@@ -0,0 +1,426 @@
+//! The verifier of an R1CS proof.
+
+use {
+    super::{
+        flatten_constraints, padded_multipliers_len,
+        proof::{R1CSProofElements, R1CSProofView},
+        ConstraintSystem, DeferredConstraints, LinearCombination, R1CSProof,
+        RandomizableConstraintSystem, RandomizedConstraintSystem, Variable,
+    },
+    crate::{
+        generators::{BulletproofGens, PedersenGens},
+        inner_product_proof::InnerProductElements,
+        transcript::{
+            self as schedule, r1cs_randomized_constraints, r1cs_second_phase, r1cs_transcript,
+        },
+        util,
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+    std::mem,
+};
+
+/// A constraint system that only knows the commitments and verifies a proof.
+pub struct Verifier<'t> {
+    transcript: &'t mut Transcript,
+    /// The constraints, each of which requires a linear combination to be zero
+    constraints: Vec<LinearCombination>,
+    /// The number of multipliers
+    num_vars: usize,
+    /// The commitments to the values
+    V: Vec<RistrettoPoint>,
+    /// The multiplier whose right input is the next allocated variable
+    pending_multiplier: Option<usize>,
+    /// The callbacks of the randomized constraints, which run after the first phase commitments
+    deferred_constraints: DeferredConstraints<RandomizingVerifier<'t>>,
+}
+
+/// A verifier in the second phase, which builds randomized constraints.
+pub struct RandomizingVerifier<'t> {
+    verifier: Verifier<'t>,
+}
+
+impl<'t> Verifier<'t> {
+    /// Create a verifier for a proof that is bound to `transcript`.
+    pub fn new(transcript: &'t mut Transcript) -> Self {
+        Verifier {
+            transcript,
+            constraints: Vec::new(),
+            num_vars: 0,
+            V: Vec::new(),
+            pending_multiplier: None,
+            deferred_constraints: Vec::new(),
+        }
+    }
+
+    /// Add the commitment `V` of the prover and return the variable of its value.
+    ///
+    /// The commitments must be added in the order in which the prover created them.
+    pub fn commit(&mut self, V: RistrettoPoint) -> Variable {
+        self.V.push(V);
+        Variable::Committed(self.V.len() - 1)
+    }
+
+    /// Verify the proof for the constraint system.
+    ///
+    /// The proof must have a second phase exactly if randomized constraints were specified.
+    pub fn verify(
+        self,
+        proof: &R1CSProof,
+        pc_gens: &PedersenGens,
+        bp_gens: &BulletproofGens,
+    ) -> Result<(), String> {
+        self.verify_elements(proof, pc_gens, bp_gens)
+    }
+
+    /// Verify a proof that borrows its encoding, as `verify` does.
+    pub fn verify_view(
+        self,
+        proof: &R1CSProofView,
+        pc_gens: &PedersenGens,
+        bp_gens: &BulletproofGens,
+    ) -> Result<(), String> {
+        self.verify_elements(proof, pc_gens, bp_gens)
+    }
+
+    /// Verify the proof, whether it is owned or borrows its encoding.
+    fn verify_elements(
+        mut self,
+        proof: &impl R1CSProofElements,
+        pc_gens: &PedersenGens,
+        bp_gens: &BulletproofGens,
+    ) -> Result<(), String> {
+        if self.V.iter().any(|V| !V.is_valid()) {
+            return Err("R1CS commitment is not a valid point".to_string());
+        }
+        let second_phase = proof.second_phase();
+        let mut points = vec![
+            proof.A_I1(),
+            proof.A_O1(),
+            proof.S1(),
+            proof.T_1(),
+            proof.T_3(),
+            proof.T_4(),
+            proof.T_5(),
+            proof.T_6(),
+        ];
+        if let Some(second_phase) = &second_phase {
+            points.extend([second_phase.A_I2, second_phase.A_O2, second_phase.S2]);
+        }
+        if points.iter().any(is_identity) {
+            return Err("R1CS proof contains the identity".to_string());
+        }
+
+        let n1 = self.num_vars;
+        let mut transcript = r1cs_transcript(&mut *self.transcript, self.V.len() as u64);
+        for V in &self.V {
+            transcript = transcript.append::<schedule::V>(V);
+        }
+        let transcript = transcript
+            .append::<schedule::AI1>(&proof.A_I1())
+            .append::<schedule::AO1>(&proof.A_O1())
+            .append::<schedule::S1>(&proof.S1());
+
+        // without randomized constraints, the challenge u that scales the generators of the
+        // second phase is one
+        let mut u = Scalar::one();
+        let transcript = match (self.deferred_constraints.is_empty(), &second_phase) {
+            (true, None) => transcript,
+            (false, Some(second_phase)) => {
+                r1cs_randomized_constraints(transcript);
+                self = self.create_randomized_constraints()?;
+
+                let transcript = r1cs_second_phase(&mut *self.transcript)
+                    .append::<schedule::AI2>(&second_phase.A_I2)
+                    .append::<schedule::AO2>(&second_phase.A_O2)
+                    .append::<schedule::S2>(&second_phase.S2);
+                let (transcript, challenge) = transcript.challenge_scalar::<schedule::U>();
+                u = challenge;
+                transcript
+            }
+            _ => {
+                return Err("R1CS proof does not match the randomized constraints".to_string());
+            }
+        };
+
+        let n = self.num_vars;
+        let padded_n = padded_multipliers_len(bp_gens, n)?;
+
+        let (transcript, y) = transcript.challenge_scalar::<schedule::Y>();
+        let (transcript, z) = transcript.challenge_scalar::<schedule::Z>();
+        let transcript = transcript
+            .append::<schedule::T1>(&proof.T_1())
+            .append::<schedule::T3>(&proof.T_3())
+            .append::<schedule::T4>(&proof.T_4())
+            .append::<schedule::T5>(&proof.T_5())
+            .append::<schedule::T6>(&proof.T_6());
+        let (transcript, x) = transcript.challenge_scalar::<schedule::X>();
+        let transcript = transcript
+            .append::<schedule::Tx>(&proof.t_x())
+            .append::<schedule::TxBlinding>(&proof.t_x_blinding())
+            .append::<schedule::EBlinding>(&proof.e_blinding());
+        let (transcript, w) = transcript.challenge_scalar::<schedule::W>();
+        let transcript = transcript.into_transcript();
+
+        let weights = flatten_constraints(&self.constraints, &z, n, self.V.len())?;
+
+        // y^-1 is inverted in the same batch as the inner product challenges
+        let mut y_inv = [y];
+        let (u_sq, u_inv_sq, s) = proof
+            .ipp_proof()
+            .verification_scalars(padded_n, transcript, &mut y_inv)?;
+        let [y_inv] = y_inv;
+
+        // the two checks of the verifier are combined with a random challenge `c`, which the
+        // verifier squeezes after the prover's last message from a copy of the transcript, so
+        // that the transcript of the caller ends in the same state as the prover's
+        let c = transcript.clone().challenge_scalar(b"c");
+
+        let a = proof.ipp_proof().a();
+        let b = proof.ipp_proof().b();
+        let exp_y_inv = util::exp_iter(y_inv).take(padded_n).collect::<Vec<_>>();
+
+        // y^-n o W_R, and delta(y, z) = <y^-n o W_R, W_L>
+        let yneg_wR = weights
+            .wR
+            .iter()
+            .zip(&exp_y_inv)
+            .map(|(wR_i, exp_y_inv)| wR_i.multiply_scalar(*exp_y_inv))
+            .collect::<Result<Vec<_>, _>>()?;
+        let delta = util::inner_product(&yneg_wR, &weights.wL)
+            .ok_or_else(|| "Polynomial lengths do not match".to_string())?;
+
+        let xx = x.multiply_scalar(x)?;
+        let xxx = xx.multiply_scalar(x)?;
+        let cx = c.multiply_scalar(x)?;
+        let cxx = c.multiply_scalar(xx)?;
+        let cxxx = cxx.multiply_scalar(x)?;
+        let cxxxx = cxxx.multiply_scalar(x)?;
+
+        let size = 2 * padded_n + 2 * u_sq.len() + self.V.len() + 13;
+        let mut scalars = Vec::with_capacity(size);
+        let mut points = Vec::with_capacity(size);
+
+        // x A_I1 + x^2 A_O1 + x^3 S1 + u (x A_I2 + x^2 A_O2 + x^3 S2)
+        scalars.extend([x, xx, xxx]);
+        points.extend([proof.A_I1(), proof.A_O1(), proof.S1()]);
+        if let Some(second_phase) = &second_phase {
+            scalars.extend([
+                u.multiply_scalar(x)?,
+                u.multiply_scalar(xx)?,
+                u.multiply_scalar(xxx)?,
+            ]);
+            points.extend([second_phase.A_I2, second_phase.A_O2, second_phase.S2]);
+        }
+
+        // sum_j c x^2 W_V,j V_j
+        for (wV_j, V) in weights.wV.iter().zip(&self.V) {
+            scalars.push(cxx.multiply_scalar(*wV_j)?);
+            points.push(*V);
+        }
+
+        // c x T_1 + c x^3 T_3 + c x^4 T_4 + c x^5 T_5 + c x^6 T_6
+        scalars.extend([
+            cx,
+            cxxx,
+            cxxxx,
+            cxxxx.multiply_scalar(x)?,
+            cxxxx.multiply_scalar(xx)?,
+        ]);
+        points.extend([
+            proof.T_1(),
+            proof.T_3(),
+            proof.T_4(),
+            proof.T_5(),
+            proof.T_6(),
+        ]);
+
+        // (w (t_x - a b) + c (x^2 (w_c + delta(y, z)) - t_x)) B
+        let B_scalar = w
+            .multiply_scalar(proof.t_x().subtract_scalar(a.multiply_scalar(b)?)?)?
+            .add_scalar(
+                c.multiply_scalar(
+                    xx.multiply_scalar(weights.wc.add_scalar(delta)?)?
+                        .subtract_scalar(proof.t_x())?,
+                )?,
+            )?;
+        scalars.push(B_scalar);
+        points.push(pc_gens.B());
+
+        // -(e_blinding + c t_x_blinding) B_blinding
+        let B_blinding_scalar = proof
+            .e_blinding()
+            .add_scalar(c.multiply_scalar(proof.t_x_blinding())?)?
+            .negate()?;
+        scalars.push(B_blinding_scalar);
+        points.push(pc_gens.B_blinding());
+
+        // the generators of the second phase and of the padding are scaled by u
+        let u_or_one = |i: usize| if i < n1 { Scalar::one() } else { u };
+
+        // sum_i u_i (x y^-i W_R,i - a s_i) G_i, where the weights of the padding are zero
+        let share = bp_gens.share(0);
+        for (i, s_i) in s.iter().enumerate() {
+            let wR_i = yneg_wR.get(i).copied().unwrap_or_else(Scalar::zero);
+            let g_i = x
+                .multiply_scalar(wR_i)?
+                .subtract_scalar(a.multiply_scalar(*s_i)?)?;
+            scalars.push(u_or_one(i).multiply_scalar(g_i)?);
+        }
+        points.extend(share.G(padded_n).copied());
+
+        // sum_i u_i (y^-i (x W_L,i + W_O,i - b s_i^-1) - 1) H_i, where s_i^-1 = s_(n - 1 - i)
+        let minus_one = Scalar::one().negate()?;
+        for (i, (s_i_inv, exp_y_inv)) in s.iter().rev().zip(&exp_y_inv).enumerate() {
+            let wL_i = weights.wL.get(i).copied().unwrap_or_else(Scalar::zero);
+            let wO_i = weights.wO.get(i).copied().unwrap_or_else(Scalar::zero);
+            let h_i = x
+                .multiply_scalar(wL_i)?
+                .add_scalar(wO_i)?
+                .subtract_scalar(b.multiply_scalar(*s_i_inv)?)?;
+            let h_i = exp_y_inv.multiply_scalar(h_i)?.add_scalar(minus_one)?;
+            scalars.push(u_or_one(i).multiply_scalar(h_i)?);
+        }
+        points.extend(share.H(padded_n).copied());
+
+        // sum_j u_j^2 L_j + u_j^-2 R_j
+        scalars.extend(u_sq);
+        scalars.extend(u_inv_sq);
+        proof.ipp_proof().extend_rounds(&mut points);
+
+        let mega_check = RistrettoPoint::multiscalar_multiply(&scalars, &points)?;
+        if is_identity(&mega_check) {
+            Ok(())
+        } else {
+            Err("R1CS proof verification failed".to_string())
+        }
+    }
+}
+
+impl<'t> Verifier<'t> {
+    /// Run the randomized constraints after the first phase commitments.
+    fn create_randomized_constraints(mut self) -> Result<Self, String> {
+        // a pending multiplier was committed in the first phase, so it cannot be completed
+        self.pending_multiplier = None;
+
+        let deferred_constraints = mem::take(&mut self.deferred_constraints);
+        let mut randomizing_verifier = RandomizingVerifier { verifier: self };
+        for callback in deferred_constraints {
+            callback(&mut randomizing_verifier)?;
+        }
+        Ok(randomizing_verifier.verifier)
+    }
+}
+
+impl ConstraintSystem for Verifier<'_> {
+    fn multiply(
+        &mut self,
+        mut left: LinearCombination,
+        mut right: LinearCombination,
+    ) -> Result<(Variable, Variable, Variable), String> {
+        let (l_var, r_var, o_var) = self.allocate_multiplier(None)?;
+
+        let minus_one = Scalar::one().negate()?;
+        left.terms.push((l_var, minus_one));
+        right.terms.push((r_var, minus_one));
+        self.constrain(left);
+        self.constrain(right);
+
+        Ok((l_var, r_var, o_var))
+    }
+
+    fn allocate(&mut self, _: Option<Scalar>) -> Result<Variable, String> {
+        match self.pending_multiplier {
+            None => {
+                let i = self.num_vars;
+                self.num_vars += 1;
+                self.pending_multiplier = Some(i);
+                Ok(Variable::MultiplierLeft(i))
+            }
+            Some(i) => {
+                self.pending_multiplier = None;
+                Ok(Variable::MultiplierRight(i))
+            }
+        }
+    }
+
+    fn allocate_multiplier(
+        &mut self,
+        _: Option<(Scalar, Scalar)>,
+    ) -> Result<(Variable, Variable, Variable), String> {
+        let i = self.num_vars;
+        self.num_vars += 1;
+        Ok((
+            Variable::MultiplierLeft(i),
+            Variable::MultiplierRight(i),
+            Variable::MultiplierOutput(i),
+        ))
+    }
+
+    fn multipliers_len(&self) -> usize {
+        self.num_vars
+    }
+
+    fn constraints_len(&self) -> usize {
+        self.constraints.len()
+    }
+
+    fn constrain(&mut self, lc: LinearCombination) {
+        self.constraints.push(lc);
+    }
+}
+
+impl<'t> RandomizableConstraintSystem for Verifier<'t> {
+    type RandomizedCS = RandomizingVerifier<'t>;
+
+    fn specify_randomized_constraints<F>(&mut self, callback: F) -> Result<(), String>
+    where
+        F: 'static + FnOnce(&mut Self::RandomizedCS) -> Result<(), String>,
+    {
+        self.deferred_constraints.push(Box::new(callback));
+        Ok(())
+    }
+}
+
+impl ConstraintSystem for RandomizingVerifier<'_> {
+    fn multiply(
+        &mut self,
+        left: LinearCombination,
+        right: LinearCombination,
+    ) -> Result<(Variable, Variable, Variable), String> {
+        self.verifier.multiply(left, right)
+    }
+
+    fn allocate(&mut self, assignment: Option<Scalar>) -> Result<Variable, String> {
+        self.verifier.allocate(assignment)
+    }
+
+    fn allocate_multiplier(
+        &mut self,
+        input_assignments: Option<(Scalar, Scalar)>,
+    ) -> Result<(Variable, Variable, Variable), String> {
+        self.verifier.allocate_multiplier(input_assignments)
+    }
+
+    fn multipliers_len(&self) -> usize {
+        self.verifier.multipliers_len()
+    }
+
+    fn constraints_len(&self) -> usize {
+        self.verifier.constraints_len()
+    }
+
+    fn constrain(&mut self, lc: LinearCombination) {
+        self.verifier.constrain(lc)
+    }
+}
+
+impl RandomizedConstraintSystem for RandomizingVerifier<'_> {
+    fn challenge_scalar(&mut self, label: &[u8]) -> Scalar {
+        self.verifier.transcript.challenge_scalar(label)
+    }
+}
+
+fn is_identity(point: &RistrettoPoint) -> bool {
+    point.to_bytes() == [0; 32]
+}
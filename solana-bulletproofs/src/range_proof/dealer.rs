@@ -0,0 +1,423 @@
+//! The dealer of the multi-party computation, which aggregates the messages of the parties into
+//! one range proof.
+//!
+//! The dealer moves through the states `DealerAwaitingBitCommitments`,
+//! `DealerAwaitingPolyCommitments` and `DealerAwaitingProofShares`. Each state holds the range
+//! proof transcript in the matching typed state, so the dealer derives the challenges with the
+//! same schedule as `RangeProof::prove_multiple`, and the resulting proof verifies with
+//! `RangeProof::verify_multiple`.
+
+use {
+    super::{
+        messages::{BitChallenge, BitCommitment, PolyChallenge, PolyCommitment, ProofShare},
+        RangeProof, SUPPORTED_BIT_SIZES,
+    },
+    crate::{
+        errors::MPCError,
+        generators::{BulletproofGens, PedersenGens},
+        inner_product_proof::InnerProductProof,
+        transcript::{
+            self as schedule, range_proof_transcript, AwaitingCommitments, AwaitingT1, AwaitingTx,
+        },
+        util,
+    },
+    solana_merlin::{typed::TypedTranscript, Transcript},
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// The entry point of the dealer.
+pub struct Dealer {}
+
+impl Dealer {
+    /// Create a dealer for `m` parties with values of `n` bits each.
+    ///
+    /// The number of parties must be a power of two. The dealer runs the range proof schedule
+    /// on `transcript`, which must be in the same state as the transcript of the verifier.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a, 'b>(
+        bp_gens: &'b BulletproofGens,
+        pc_gens: &'b PedersenGens,
+        transcript: &'a mut Transcript,
+        n: usize,
+        m: usize,
+    ) -> Result<DealerAwaitingBitCommitments<'a, 'b>, MPCError> {
+        if !SUPPORTED_BIT_SIZES.contains(&n) || bp_gens.gens_capacity < n {
+            return Err(MPCError::InvalidBitsize);
+        }
+        if !m.is_power_of_two() || bp_gens.party_capacity < m {
+            return Err(MPCError::InvalidAggregation);
+        }
+
+        // the dealer verifies the aggregated proof at the end, which replays the schedule from
+        // the initial state of the transcript
+        let initial_transcript = transcript.clone();
+        let transcript = range_proof_transcript(transcript, n as u64, m as u64);
+
+        Ok(DealerAwaitingBitCommitments {
+            bp_gens,
+            pc_gens,
+            transcript,
+            initial_transcript,
+            n,
+            m,
+        })
+    }
+}
+
+/// A dealer that waits for the bit commitments of all parties.
+pub struct DealerAwaitingBitCommitments<'a, 'b> {
+    bp_gens: &'b BulletproofGens,
+    pc_gens: &'b PedersenGens,
+    transcript: TypedTranscript<AwaitingCommitments, &'a mut Transcript>,
+    initial_transcript: Transcript,
+    n: usize,
+    m: usize,
+}
+
+impl<'a, 'b> DealerAwaitingBitCommitments<'a, 'b> {
+    /// Combine the bit commitments of the parties, ordered by position, and derive the
+    /// challenges `y` and `z`.
+    pub fn receive_bit_commitments(
+        self,
+        bit_commitments: Vec<BitCommitment>,
+    ) -> Result<(DealerAwaitingPolyCommitments<'a, 'b>, BitChallenge), MPCError> {
+        if bit_commitments.len() != self.m {
+            return Err(MPCError::WrongNumBitCommitments);
+        }
+
+        let mut transcript = self.transcript;
+        for bit_commitment in &bit_commitments {
+            transcript = transcript.append::<schedule::V>(&bit_commitment.V_j);
+        }
+        let A = sum(bit_commitments
+            .iter()
+            .map(|bit_commitment| bit_commitment.A_j))?;
+        let S = sum(bit_commitments
+            .iter()
+            .map(|bit_commitment| bit_commitment.S_j))?;
+
+        let transcript = transcript
+            .append::<schedule::A>(&A)
+            .append::<schedule::S>(&S);
+        let (transcript, y) = transcript.challenge_scalar::<schedule::Y>();
+        let (transcript, z) = transcript.challenge_scalar::<schedule::Z>();
+        let bit_challenge = BitChallenge { y, z };
+
+        let next_state = DealerAwaitingPolyCommitments {
+            bp_gens: self.bp_gens,
+            pc_gens: self.pc_gens,
+            transcript,
+            initial_transcript: self.initial_transcript,
+            n: self.n,
+            m: self.m,
+            bit_commitments,
+            bit_challenge,
+            A,
+            S,
+        };
+        Ok((next_state, bit_challenge))
+    }
+}
+
+/// A dealer that waits for the polynomial commitments of all parties.
+pub struct DealerAwaitingPolyCommitments<'a, 'b> {
+    bp_gens: &'b BulletproofGens,
+    pc_gens: &'b PedersenGens,
+    transcript: TypedTranscript<AwaitingT1, &'a mut Transcript>,
+    initial_transcript: Transcript,
+    n: usize,
+    m: usize,
+    bit_commitments: Vec<BitCommitment>,
+    bit_challenge: BitChallenge,
+    A: RistrettoPoint,
+    S: RistrettoPoint,
+}
+
+impl<'a, 'b> DealerAwaitingPolyCommitments<'a, 'b> {
+    /// Combine the polynomial commitments of the parties, ordered by position, and derive the
+    /// challenge `x`.
+    pub fn receive_poly_commitments(
+        self,
+        poly_commitments: Vec<PolyCommitment>,
+    ) -> Result<(DealerAwaitingProofShares<'a, 'b>, PolyChallenge), MPCError> {
+        if poly_commitments.len() != self.m {
+            return Err(MPCError::WrongNumPolyCommitments);
+        }
+
+        let T_1 = sum(poly_commitments
+            .iter()
+            .map(|poly_commitment| poly_commitment.T_1_j))?;
+        let T_2 = sum(poly_commitments
+            .iter()
+            .map(|poly_commitment| poly_commitment.T_2_j))?;
+
+        let transcript = self
+            .transcript
+            .append::<schedule::T1>(&T_1)
+            .append::<schedule::T2>(&T_2);
+        let (transcript, x) = transcript.challenge_scalar::<schedule::X>();
+        let poly_challenge = PolyChallenge { x };
+
+        let next_state = DealerAwaitingProofShares {
+            bp_gens: self.bp_gens,
+            pc_gens: self.pc_gens,
+            transcript,
+            initial_transcript: self.initial_transcript,
+            n: self.n,
+            m: self.m,
+            bit_commitments: self.bit_commitments,
+            bit_challenge: self.bit_challenge,
+            poly_commitments,
+            poly_challenge,
+            A: self.A,
+            S: self.S,
+            T_1,
+            T_2,
+        };
+        Ok((next_state, poly_challenge))
+    }
+}
+
+/// A dealer that waits for the proof shares of all parties.
+pub struct DealerAwaitingProofShares<'a, 'b> {
+    bp_gens: &'b BulletproofGens,
+    pc_gens: &'b PedersenGens,
+    transcript: TypedTranscript<AwaitingTx, &'a mut Transcript>,
+    initial_transcript: Transcript,
+    n: usize,
+    m: usize,
+    bit_commitments: Vec<BitCommitment>,
+    bit_challenge: BitChallenge,
+    poly_commitments: Vec<PolyCommitment>,
+    poly_challenge: PolyChallenge,
+    A: RistrettoPoint,
+    S: RistrettoPoint,
+    T_1: RistrettoPoint,
+    T_2: RistrettoPoint,
+}
+
+impl DealerAwaitingProofShares<'_, '_> {
+    /// Assemble the proof shares of the parties, ordered by position, into a range proof and
+    /// verify it.
+    ///
+    /// If the proof does not verify, every share is audited against the commitments of its
+    /// party, and the error blames the parties whose shares are malformed.
+    pub fn receive_shares(self, proof_shares: &[ProofShare]) -> Result<RangeProof, MPCError> {
+        let bp_gens = self.bp_gens;
+        let pc_gens = self.pc_gens;
+        let n = self.n;
+        let value_commitments = self
+            .bit_commitments
+            .iter()
+            .map(BitCommitment::value_commitment)
+            .collect::<Vec<_>>();
+        let mut initial_transcript = self.initial_transcript.clone();
+        let audit = Audit {
+            bp_gens,
+            pc_gens,
+            n,
+            bit_commitments: self.bit_commitments.clone(),
+            bit_challenge: self.bit_challenge,
+            poly_commitments: self.poly_commitments.clone(),
+            poly_challenge: self.poly_challenge,
+        };
+
+        let proof = self.assemble_shares(proof_shares)?;
+        if proof
+            .verify_multiple(
+                bp_gens,
+                pc_gens,
+                &mut initial_transcript,
+                &value_commitments,
+                n,
+            )
+            .is_ok()
+        {
+            return Ok(proof);
+        }
+
+        let bad_shares = proof_shares
+            .iter()
+            .enumerate()
+            .filter(|(j, share)| audit.audit_share(*j, share).is_err())
+            .map(|(j, _)| j)
+            .collect();
+        Err(MPCError::MalformedProofShares { bad_shares })
+    }
+
+    /// Assemble the proof shares of the parties into a range proof without verifying it.
+    ///
+    /// This is faster than `receive_shares`, but the proof is only valid if every party is
+    /// honest, so it should only be used when the parties are trusted.
+    pub fn receive_trusted_shares(
+        self,
+        proof_shares: &[ProofShare],
+    ) -> Result<RangeProof, MPCError> {
+        self.assemble_shares(proof_shares)
+    }
+
+    fn assemble_shares(self, proof_shares: &[ProofShare]) -> Result<RangeProof, MPCError> {
+        if proof_shares.len() != self.m {
+            return Err(MPCError::WrongNumProofShares);
+        }
+        // shares of the wrong length cannot be assembled, so their parties are blamed right away
+        let bad_shares = proof_shares
+            .iter()
+            .enumerate()
+            .filter(|(_, share)| share.l_vec.len() != self.n || share.r_vec.len() != self.n)
+            .map(|(j, _)| j)
+            .collect::<Vec<_>>();
+        if !bad_shares.is_empty() {
+            return Err(MPCError::MalformedProofShares { bad_shares });
+        }
+
+        let nm = self.n * self.m;
+        let mut t_x = Scalar::zero();
+        let mut t_x_blinding = Scalar::zero();
+        let mut e_blinding = Scalar::zero();
+        let mut l_vec = Vec::with_capacity(nm);
+        let mut r_vec = Vec::with_capacity(nm);
+        for share in proof_shares {
+            t_x = t_x.add_scalar(share.t_x)?;
+            t_x_blinding = t_x_blinding.add_scalar(share.t_x_blinding)?;
+            e_blinding = e_blinding.add_scalar(share.e_blinding)?;
+            l_vec.extend(&share.l_vec);
+            r_vec.extend(&share.r_vec);
+        }
+
+        let transcript = self
+            .transcript
+            .append::<schedule::Tx>(&t_x)
+            .append::<schedule::TxBlinding>(&t_x_blinding)
+            .append::<schedule::EBlinding>(&e_blinding);
+        let (transcript, w) = transcript.challenge_scalar::<schedule::W>();
+
+        let Q = self.pc_gens.B().multiply(&w)?;
+        let G_factors = vec![Scalar::one(); nm];
+        let H_factors = util::exp_iter(self.bit_challenge.y.invert()?)
+            .take(nm)
+            .collect::<Vec<_>>();
+
+        let ipp_proof = InnerProductProof::create(
+            transcript.into_transcript(),
+            &Q,
+            &G_factors,
+            &H_factors,
+            self.bp_gens.G(self.n, self.m).copied().collect(),
+            self.bp_gens.H(self.n, self.m).copied().collect(),
+            l_vec,
+            r_vec,
+        )?;
+
+        Ok(RangeProof {
+            A: self.A,
+            S: self.S,
+            T_1: self.T_1,
+            T_2: self.T_2,
+            t_x,
+            t_x_blinding,
+            e_blinding,
+            ipp_proof,
+        })
+    }
+}
+
+/// The public data of the protocol that the dealer checks the proof shares against.
+struct Audit<'b> {
+    bp_gens: &'b BulletproofGens,
+    pc_gens: &'b PedersenGens,
+    n: usize,
+    bit_commitments: Vec<BitCommitment>,
+    bit_challenge: BitChallenge,
+    poly_commitments: Vec<PolyCommitment>,
+    poly_challenge: PolyChallenge,
+}
+
+impl Audit<'_> {
+    /// Check the share of party `j` against its commitments and the challenges.
+    fn audit_share(&self, j: usize, share: &ProofShare) -> Result<(), String> {
+        let n = self.n;
+        let bit_commitment = &self.bit_commitments[j];
+        let poly_commitment = &self.poly_commitments[j];
+        let BitChallenge { y, z } = self.bit_challenge;
+        let x = self.poly_challenge.x;
+
+        // t_x = <l, r>
+        if Some(share.t_x) != util::inner_product(&share.l_vec, &share.r_vec) {
+            return Err("Proof share has an invalid inner product".to_string());
+        }
+
+        let zz = z.multiply_scalar(z)?;
+        let minus_z = z.negate()?;
+        let exp_z = util::exp_iter(z).nth(j).unwrap().multiply_scalar(zz)?;
+        let y_jn = util::exp_iter(y).nth(j * n).unwrap();
+        let mut inverses = [y_jn, y];
+        Scalar::batch_invert(&mut inverses)?;
+        let [y_jn_inv, y_inv] = inverses;
+
+        // A_j + x S_j - e_blinding B_blinding + <-z - l, G_j>
+        //   + <z + y^-(jn) y^-i (z^(2+j) 2^i - r_i), H_j> = 0
+        let mut scalars = vec![Scalar::one(), x, share.e_blinding.negate()?];
+        for l_i in &share.l_vec {
+            scalars.push(minus_z.subtract_scalar(*l_i)?);
+        }
+        let two = Scalar::from(2);
+        let mut exp_2 = Scalar::one();
+        for (r_i, exp_y_inv) in share.r_vec.iter().zip(util::exp_iter(y_inv)) {
+            let h_i = y_jn_inv
+                .multiply_scalar(exp_y_inv)?
+                .multiply_scalar(exp_z.multiply_scalar(exp_2)?.subtract_scalar(*r_i)?)?;
+            scalars.push(z.add_scalar(h_i)?);
+            exp_2 = exp_2.multiply_scalar(two)?;
+        }
+        let mut points = vec![
+            bit_commitment.A_j,
+            bit_commitment.S_j,
+            self.pc_gens.B_blinding(),
+        ];
+        points.extend(self.bp_gens.share(j).G(n));
+        points.extend(self.bp_gens.share(j).H(n));
+        if !is_identity(&RistrettoPoint::multiscalar_multiply(&scalars, &points)?) {
+            return Err("Proof share does not open the bit commitments".to_string());
+        }
+
+        // z^(2+j) V_j + x T_1_j + x^2 T_2_j + (delta_j - t_x) B - t_x_blinding B_blinding = 0,
+        // where delta_j = (z - z^2) y^(jn) <1, y^n> - z^(3+j) <1, 2^n>
+        let sum_2 = Scalar::from(((1u128 << n) - 1) as u64);
+        let delta_j = z
+            .subtract_scalar(zz)?
+            .multiply_scalar(y_jn)?
+            .multiply_scalar(util::sum_of_powers(&y, n))?
+            .subtract_scalar(exp_z.multiply_scalar(z)?.multiply_scalar(sum_2)?)?;
+        let scalars = [
+            exp_z,
+            x,
+            x.multiply_scalar(x)?,
+            delta_j.subtract_scalar(share.t_x)?,
+            share.t_x_blinding.negate()?,
+        ];
+        let points = [
+            bit_commitment.V_j,
+            poly_commitment.T_1_j,
+            poly_commitment.T_2_j,
+            self.pc_gens.B(),
+            self.pc_gens.B_blinding(),
+        ];
+        if !is_identity(&RistrettoPoint::multiscalar_multiply(&scalars, &points)?) {
+            return Err("Proof share does not open the polynomial commitments".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn sum(points: impl Iterator<Item = RistrettoPoint>) -> Result<RistrettoPoint, String> {
+    let mut result = RistrettoPoint::from_bytes(&[0; 32])?;
+    for point in points {
+        result = result.add(&point)?;
+    }
+    Ok(result)
+}
+
+fn is_identity(point: &RistrettoPoint) -> bool {
+    point.to_bytes() == [0; 32]
+}
@@ -0,0 +1,198 @@
+//! The messages of the multi-party computation of an aggregated range proof.
+//!
+//! Every party sends a `BitCommitment`, a `PolyCommitment` and a `ProofShare` to the dealer, and
+//! the dealer answers the first two with a `BitChallenge` and a `PolyChallenge`. All messages
+//! serialize to bytes, so they can travel through any channel.
+
+use solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar};
+
+/// The commitments of party `j` to its value and to the bits of its value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BitCommitment {
+    /// Commitment to the value
+    pub(super) V_j: RistrettoPoint,
+    /// Commitment to the bits of the value
+    pub(super) A_j: RistrettoPoint,
+    /// Commitment to the blinding factors of the bits
+    pub(super) S_j: RistrettoPoint,
+}
+
+impl BitCommitment {
+    /// The commitment of the party to its value.
+    pub fn value_commitment(&self) -> RistrettoPoint {
+        self.V_j
+    }
+
+    /// Serialize the message as `V_j || A_j || S_j`.
+    pub fn to_bytes(&self) -> [u8; 96] {
+        let mut buf = [0; 96];
+        buf[..32].copy_from_slice(&self.V_j.to_bytes());
+        buf[32..64].copy_from_slice(&self.A_j.to_bytes());
+        buf[64..].copy_from_slice(&self.S_j.to_bytes());
+        buf
+    }
+
+    /// Deserialize a message that was serialized with `to_bytes`.
+    pub fn from_bytes(slice: &[u8]) -> Result<Self, String> {
+        if slice.len() != 96 {
+            return Err("Bit commitment has an invalid length".to_string());
+        }
+        Ok(BitCommitment {
+            V_j: read_point(&slice[..32])?,
+            A_j: read_point(&slice[32..64])?,
+            S_j: read_point(&slice[64..])?,
+        })
+    }
+}
+
+/// The challenges `y` and `z` of the dealer for the bit commitments.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BitChallenge {
+    pub(super) y: Scalar,
+    pub(super) z: Scalar,
+}
+
+impl BitChallenge {
+    /// Serialize the message as `y || z`.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut buf = [0; 64];
+        buf[..32].copy_from_slice(&self.y.to_bytes());
+        buf[32..].copy_from_slice(&self.z.to_bytes());
+        buf
+    }
+
+    /// Deserialize a message that was serialized with `to_bytes`.
+    pub fn from_bytes(slice: &[u8]) -> Result<Self, String> {
+        if slice.len() != 64 {
+            return Err("Bit challenge has an invalid length".to_string());
+        }
+        Ok(BitChallenge {
+            y: read_scalar(&slice[..32])?,
+            z: read_scalar(&slice[32..])?,
+        })
+    }
+}
+
+/// The commitments of party `j` to the coefficients of its share of `t(x)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PolyCommitment {
+    /// Commitment to the `t_1` coefficient of the share
+    pub(super) T_1_j: RistrettoPoint,
+    /// Commitment to the `t_2` coefficient of the share
+    pub(super) T_2_j: RistrettoPoint,
+}
+
+impl PolyCommitment {
+    /// Serialize the message as `T_1_j || T_2_j`.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut buf = [0; 64];
+        buf[..32].copy_from_slice(&self.T_1_j.to_bytes());
+        buf[32..].copy_from_slice(&self.T_2_j.to_bytes());
+        buf
+    }
+
+    /// Deserialize a message that was serialized with `to_bytes`.
+    pub fn from_bytes(slice: &[u8]) -> Result<Self, String> {
+        if slice.len() != 64 {
+            return Err("Polynomial commitment has an invalid length".to_string());
+        }
+        Ok(PolyCommitment {
+            T_1_j: read_point(&slice[..32])?,
+            T_2_j: read_point(&slice[32..])?,
+        })
+    }
+}
+
+/// The challenge `x` of the dealer for the polynomial commitments.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PolyChallenge {
+    pub(super) x: Scalar,
+}
+
+impl PolyChallenge {
+    /// Serialize the message as `x`.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.x.to_bytes()
+    }
+
+    /// Deserialize a message that was serialized with `to_bytes`.
+    pub fn from_bytes(slice: &[u8]) -> Result<Self, String> {
+        if slice.len() != 32 {
+            return Err("Polynomial challenge has an invalid length".to_string());
+        }
+        Ok(PolyChallenge {
+            x: read_scalar(slice)?,
+        })
+    }
+}
+
+/// The share of party `j` of the evaluations at the challenge `x`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProofShare {
+    /// Evaluation of the share of `t(x)`
+    pub(super) t_x: Scalar,
+    /// Blinding factor for the synthetic commitment to the share of `t(x)`
+    pub(super) t_x_blinding: Scalar,
+    /// Blinding factor for the synthetic commitment to the share of `l(x)` and `r(x)`
+    pub(super) e_blinding: Scalar,
+    /// The `n` entries of `l(x)` of the party
+    pub(super) l_vec: Vec<Scalar>,
+    /// The `n` entries of `r(x)` of the party
+    pub(super) r_vec: Vec<Scalar>,
+}
+
+impl ProofShare {
+    /// The size of the message in bytes, `32 * (2n + 3)`.
+    pub fn serialized_size(&self) -> usize {
+        (3 + self.l_vec.len() + self.r_vec.len()) * 32
+    }
+
+    /// Serialize the message as `t_x || t_x_blinding || e_blinding || l_vec || r_vec`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.serialized_size());
+        buf.extend_from_slice(&self.t_x.to_bytes());
+        buf.extend_from_slice(&self.t_x_blinding.to_bytes());
+        buf.extend_from_slice(&self.e_blinding.to_bytes());
+        for scalar in self.l_vec.iter().chain(&self.r_vec) {
+            buf.extend_from_slice(&scalar.to_bytes());
+        }
+        buf
+    }
+
+    /// Deserialize a message that was serialized with `to_bytes`.
+    pub fn from_bytes(slice: &[u8]) -> Result<Self, String> {
+        let num_elements = slice.len() / 32;
+        if num_elements * 32 != slice.len() || num_elements < 3 || num_elements & 1 == 0 {
+            return Err("Proof share has an invalid length".to_string());
+        }
+        let n = (num_elements - 3) / 2;
+        let scalars = slice
+            .chunks(32)
+            .map(read_scalar)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ProofShare {
+            t_x: scalars[0],
+            t_x_blinding: scalars[1],
+            e_blinding: scalars[2],
+            l_vec: scalars[3..3 + n].to_vec(),
+            r_vec: scalars[3 + n..].to_vec(),
+        })
+    }
+}
+
+fn read_point(bytes: &[u8]) -> Result<RistrettoPoint, String> {
+    let point = RistrettoPoint::from_bytes(bytes)?;
+    if !point.is_valid() {
+        return Err("Message contains an invalid point".to_string());
+    }
+    Ok(point)
+}
+
+fn read_scalar(bytes: &[u8]) -> Result<Scalar, String> {
+    let scalar = Scalar::from_bytes(bytes)?;
+    if !scalar.is_canonical() {
+        return Err("Message contains a non-canonical scalar".to_string());
+    }
+    Ok(scalar)
+}
@@ -0,0 +1,1210 @@
+//! Range proofs that Pedersen commitments open to values in `[0, 2^n)`.
+//!
+//! The proof follows sections 4.2 and 4.3 of the Bulletproofs paper: the prover commits to the
+//! bits of the values, reduces the bit constraints to a single inner product
+//! `t(x) = <l(x), r(x)>`, and proves the inner product with an `InnerProductProof`. A proof for
+//! `m` values of `n` bits each uses the generators of `m` parties of `BulletproofGens` and has
+//! size `32 * (2 lg(nm) + 9)`. The message schedule is the range proof schedule of the
+//! `transcript` module.
+//!
+//! An interval proof shows that a commitment `V` opens to a value in a public interval `[a, b)`.
+//! It is an aggregated proof that the shifted commitments `V - a * B` and `V + (2^n - b) * B`
+//! open to values in `[0, 2^n)`, which the verifier derives from `V` and the interval.
+//!
+//! A batched proof instead proves that each value `j` is in `[0, 2^(n_j))` for its own bit length
+//! `n_j`. The bits of all values are concatenated into one vector of length `nm = sum_j n_j`,
+//! which must be a power of two, and the proof uses the first `nm` generators of the first party.
+//!
+//! The verifier combines all of its checks into one multiscalar multiplication of size
+//! `2nm + 2 lg(nm) + m + 6` for `m` values, and computes all inverses with a single scalar
+//! inversion. `RangeProof::batch_verify` checks many proofs with one multiscalar multiplication,
+//! in which the generators `G` and `H` are shared by all proofs. The prover only runs off-chain.
+//!
+//! The `dealer` and `party` modules compute the same aggregated proof with a multi-party
+//! computation, in which each of the `m` parties holds one secret value and only exchanges the
+//! messages of the `messages` module with the dealer.
+
+#![allow(non_snake_case)]
+
+#[cfg(not(target_os = "solana"))]
+pub mod dealer;
+pub mod messages;
+#[cfg(not(target_os = "solana"))]
+pub mod party;
+
+#[cfg(not(target_os = "solana"))]
+use {
+    crate::util::{random_scalar, Poly2, VecPoly1},
+    rand_core::{CryptoRng, OsRng, RngCore},
+    solana_merlin::TranscriptRng,
+};
+use {
+    crate::{
+        encoding::{self, ProofBytes, ProofType, HEADER_SIZE},
+        generators::{BulletproofGens, PedersenGens},
+        inner_product_proof::{InnerProductElements, InnerProductProof, InnerProductProofView},
+        transcript::{
+            self as schedule, append_interval, batched_range_proof_transcript,
+            range_proof_transcript, AwaitingCommitments,
+        },
+        util,
+    },
+    solana_merlin::{typed::TypedTranscript, Transcript},
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// The bit sizes that a range proof supports.
+pub const SUPPORTED_BIT_SIZES: [usize; 4] = [8, 16, 32, 64];
+
+/// A range proof for one committed value, or an aggregated range proof for several.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeProof {
+    /// Commitment to the bits of the value
+    A: RistrettoPoint,
+    /// Commitment to the blinding factors
+    S: RistrettoPoint,
+    /// Commitment to the `t_1` coefficient of `t(x)`
+    T_1: RistrettoPoint,
+    /// Commitment to the `t_2` coefficient of `t(x)`
+    T_2: RistrettoPoint,
+    /// Evaluation of the polynomial `t(x)` at the challenge point `x`
+    t_x: Scalar,
+    /// Blinding factor for the synthetic commitment to `t(x)`
+    t_x_blinding: Scalar,
+    /// Blinding factor for the synthetic commitment to the inner-product arguments
+    e_blinding: Scalar,
+    /// Proof data for the inner-product argument.
+    ipp_proof: InnerProductProof,
+}
+
+impl RangeProof {
+    /// Create a range proof that `v` is in `[0, 2^n)` with randomness from the operating system.
+    ///
+    /// Returns the proof together with the commitment `v * B + v_blinding * B_blinding`.
+    #[cfg(not(target_os = "solana"))]
+    pub fn prove_single(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        v: u64,
+        v_blinding: &Scalar,
+        n: usize,
+    ) -> Result<(RangeProof, RistrettoPoint), String> {
+        Self::prove_single_with_rng(bp_gens, pc_gens, transcript, v, v_blinding, n, &mut OsRng)
+    }
+
+    /// Create a range proof that `v` is in `[0, 2^n)` with randomness from `rng`.
+    ///
+    /// The randomness of the prover is derived from the transcript, the witness, and `rng`, so a
+    /// weak `rng` does not leak the witness on its own.
+    #[cfg(not(target_os = "solana"))]
+    pub fn prove_single_with_rng<R: RngCore + CryptoRng>(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        v: u64,
+        v_blinding: &Scalar,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<(RangeProof, RistrettoPoint), String> {
+        let (proof, value_commitments) = Self::prove_multiple_with_rng(
+            bp_gens,
+            pc_gens,
+            transcript,
+            &[v],
+            &[*v_blinding],
+            n,
+            rng,
+        )?;
+        Ok((proof, value_commitments[0]))
+    }
+
+    /// Create an aggregated range proof that every value in `values` is in `[0, 2^n)` with
+    /// randomness from the operating system.
+    ///
+    /// Returns the proof together with the commitments to the values.
+    #[cfg(not(target_os = "solana"))]
+    pub fn prove_multiple(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        values: &[u64],
+        blindings: &[Scalar],
+        n: usize,
+    ) -> Result<(RangeProof, Vec<RistrettoPoint>), String> {
+        Self::prove_multiple_with_rng(
+            bp_gens, pc_gens, transcript, values, blindings, n, &mut OsRng,
+        )
+    }
+
+    /// Create an aggregated range proof that every value in `values` is in `[0, 2^n)` with
+    /// randomness from `rng`.
+    ///
+    /// If the number of values is not a power of two, it is padded with commitments to zero with
+    /// zero blinding, i.e. the identity. The verifier pads the commitments in the same way.
+    #[cfg(not(target_os = "solana"))]
+    pub fn prove_multiple_with_rng<R: RngCore + CryptoRng>(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        values: &[u64],
+        blindings: &[Scalar],
+        n: usize,
+        rng: &mut R,
+    ) -> Result<(RangeProof, Vec<RistrettoPoint>), String> {
+        if values.len() != blindings.len() {
+            return Err("Number of values and blinding factors do not match".to_string());
+        }
+        let m = padded_party_count(values.len())?;
+        check_parameters(bp_gens, n, m)?;
+
+        let mut padded_values = values.to_vec();
+        let mut padded_blindings = blindings.to_vec();
+        padded_values.resize(m, 0);
+        padded_blindings.resize(m, Scalar::zero());
+
+        let rng = prover_rng(transcript, values, blindings, rng);
+        let G = bp_gens.G(n, m).copied().collect::<Vec<_>>();
+        let H = bp_gens.H(n, m).copied().collect::<Vec<_>>();
+        let transcript = range_proof_transcript(transcript, n as u64, m as u64);
+
+        let (proof, mut value_commitments) = Self::prove_with_bit_lengths(
+            pc_gens,
+            transcript,
+            G,
+            H,
+            &padded_values,
+            &padded_blindings,
+            &vec![n; m],
+            rng,
+        )?;
+        value_commitments.truncate(values.len());
+        Ok((proof, value_commitments))
+    }
+
+    /// Create a range proof that every value `values[j]` is in `[0, 2^bit_lengths[j])` with
+    /// randomness from the operating system.
+    ///
+    /// Returns the proof together with the commitments to the values.
+    #[cfg(not(target_os = "solana"))]
+    pub fn prove_batched(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        values: &[u64],
+        blindings: &[Scalar],
+        bit_lengths: &[usize],
+    ) -> Result<(RangeProof, Vec<RistrettoPoint>), String> {
+        Self::prove_batched_with_rng(
+            bp_gens,
+            pc_gens,
+            transcript,
+            values,
+            blindings,
+            bit_lengths,
+            &mut OsRng,
+        )
+    }
+
+    /// Create a range proof that every value `values[j]` is in `[0, 2^bit_lengths[j])` with
+    /// randomness from `rng`.
+    ///
+    /// Every bit length must be between 1 and 64, and the total number of bits must be a power
+    /// of two. The proof uses the first `sum_j bit_lengths[j]` generators of the first party.
+    #[cfg(not(target_os = "solana"))]
+    pub fn prove_batched_with_rng<R: RngCore + CryptoRng>(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        values: &[u64],
+        blindings: &[Scalar],
+        bit_lengths: &[usize],
+        rng: &mut R,
+    ) -> Result<(RangeProof, Vec<RistrettoPoint>), String> {
+        if values.len() != blindings.len() || values.len() != bit_lengths.len() {
+            return Err(
+                "Number of values, blinding factors, and bit lengths do not match".to_string(),
+            );
+        }
+        let nm = check_bit_lengths(bp_gens, bit_lengths)?;
+
+        let rng = prover_rng(transcript, values, blindings, rng);
+        let G = bp_gens.share(0).G(nm).copied().collect::<Vec<_>>();
+        let H = bp_gens.share(0).H(nm).copied().collect::<Vec<_>>();
+        let transcript = batched_range_proof_transcript(transcript, bit_lengths);
+
+        Self::prove_with_bit_lengths(
+            pc_gens,
+            transcript,
+            G,
+            H,
+            values,
+            blindings,
+            bit_lengths,
+            rng,
+        )
+    }
+
+    /// Create a range proof that `v` is in the interval `[a, b)` with randomness from the
+    /// operating system.
+    ///
+    /// Returns the proof together with the commitment `v * B + v_blinding * B_blinding`.
+    #[cfg(not(target_os = "solana"))]
+    pub fn prove_interval(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        v: u64,
+        v_blinding: &Scalar,
+        a: u64,
+        b: u64,
+    ) -> Result<(RangeProof, RistrettoPoint), String> {
+        Self::prove_interval_with_rng(
+            bp_gens, pc_gens, transcript, v, v_blinding, a, b, &mut OsRng,
+        )
+    }
+
+    /// Create a range proof that `v` is in the interval `[a, b)` with randomness from `rng`.
+    ///
+    /// The proof is an aggregated proof that both `v - a` and `v - b + 2^n` are in `[0, 2^n)`,
+    /// where `n = interval_bit_size(a, b)` is the smallest supported bit size with
+    /// `b - a <= 2^n`. It needs generators for `n` bits and 2 parties.
+    #[cfg(not(target_os = "solana"))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_interval_with_rng<R: RngCore + CryptoRng>(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        v: u64,
+        v_blinding: &Scalar,
+        a: u64,
+        b: u64,
+        rng: &mut R,
+    ) -> Result<(RangeProof, RistrettoPoint), String> {
+        let n = interval_bit_size(a, b)?;
+        if v < a || v >= b {
+            return Err("Value is out of range".to_string());
+        }
+        // v - b + 2^n is in [0, 2^n), since b - 2^n <= a <= v < b
+        let upper_value = (v as u128 + (1u128 << n) - b as u128) as u64;
+
+        append_interval(transcript, a, b);
+        let (proof, _) = Self::prove_multiple_with_rng(
+            bp_gens,
+            pc_gens,
+            transcript,
+            &[v - a, upper_value],
+            &[*v_blinding, *v_blinding],
+            n,
+            rng,
+        )?;
+        let V = pc_gens.commit(Scalar::from(v), *v_blinding)?;
+        Ok((proof, V))
+    }
+
+    /// Create a range proof for values with the given bit lengths, with respect to the
+    /// generators `G` and `H` of length `sum_j bit_lengths[j]`.
+    #[cfg(not(target_os = "solana"))]
+    #[allow(clippy::too_many_arguments)]
+    fn prove_with_bit_lengths(
+        pc_gens: &PedersenGens,
+        transcript: TypedTranscript<AwaitingCommitments, &mut Transcript>,
+        G: Vec<RistrettoPoint>,
+        H: Vec<RistrettoPoint>,
+        values: &[u64],
+        blindings: &[Scalar],
+        bit_lengths: &[usize],
+        mut rng: TranscriptRng,
+    ) -> Result<(RangeProof, Vec<RistrettoPoint>), String> {
+        let nm = G.len();
+        for (v, n) in values.iter().zip(bit_lengths) {
+            if *n < 64 && v >> n != 0 {
+                return Err("Value is out of range".to_string());
+            }
+        }
+
+        let value_commitments = values
+            .iter()
+            .zip(blindings)
+            .map(|(v, v_blinding)| pc_gens.commit(Scalar::from(*v), *v_blinding))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // a_L are the bits of all values and a_R = a_L - 1
+        let minus_one = Scalar::one().negate()?;
+        let mut a_L = Vec::with_capacity(nm);
+        let mut a_R = Vec::with_capacity(nm);
+        for (v, n) in values.iter().zip(bit_lengths) {
+            for i in 0..*n {
+                if (v >> i) & 1 == 1 {
+                    a_L.push(Scalar::one());
+                    a_R.push(Scalar::zero());
+                } else {
+                    a_L.push(Scalar::zero());
+                    a_R.push(minus_one);
+                }
+            }
+        }
+
+        let a_blinding = random_scalar(&mut rng);
+        let A_scalars = [&[a_blinding], &a_L[..], &a_R[..]].concat();
+        let A_points = [&[pc_gens.B_blinding()], &G[..], &H[..]].concat();
+        let A = RistrettoPoint::multiscalar_multiply(&A_scalars, &A_points)?;
+
+        let s_blinding = random_scalar(&mut rng);
+        let s_L = (0..nm).map(|_| random_scalar(&mut rng)).collect::<Vec<_>>();
+        let s_R = (0..nm).map(|_| random_scalar(&mut rng)).collect::<Vec<_>>();
+        let S_scalars = [&[s_blinding], &s_L[..], &s_R[..]].concat();
+        let S = RistrettoPoint::multiscalar_multiply(&S_scalars, &A_points)?;
+
+        let mut transcript = transcript;
+        for V in &value_commitments {
+            transcript = transcript.append::<schedule::V>(V);
+        }
+        let transcript = transcript
+            .append::<schedule::A>(&A)
+            .append::<schedule::S>(&S);
+        let (transcript, y) = transcript.challenge_scalar::<schedule::Y>();
+        let (transcript, z) = transcript.challenge_scalar::<schedule::Z>();
+
+        // l(x) = (a_L - z 1) + s_L x
+        // r(x) = y^nm o (a_R + z 1 + s_R x) + sum_j z^(2+j) (0 || 2^(n_j) || 0), where the
+        // powers of two of value j are at the positions of its bits
+        let mut l_poly = VecPoly1::zero(nm);
+        let mut r_poly = VecPoly1::zero(nm);
+        let mut exp_y = Scalar::one();
+        let two = Scalar::from(2);
+        let mut exp_z = z.multiply_scalar(z)?;
+        let mut i = 0;
+        for n in bit_lengths {
+            let mut exp_2 = Scalar::one();
+            for _ in 0..*n {
+                l_poly.0[i] = a_L[i].subtract_scalar(z)?;
+                l_poly.1[i] = s_L[i];
+                r_poly.0[i] = exp_y
+                    .multiply_scalar(a_R[i].add_scalar(z)?)?
+                    .add_scalar(exp_z.multiply_scalar(exp_2)?)?;
+                r_poly.1[i] = exp_y.multiply_scalar(s_R[i])?;
+
+                exp_y = exp_y.multiply_scalar(y)?;
+                exp_2 = exp_2.multiply_scalar(two)?;
+                i += 1;
+            }
+            exp_z = exp_z.multiply_scalar(z)?;
+        }
+
+        let t_poly = l_poly
+            .inner_product(&r_poly)
+            .ok_or_else(|| "Polynomial lengths do not match".to_string())?;
+
+        let t_1_blinding = random_scalar(&mut rng);
+        let t_2_blinding = random_scalar(&mut rng);
+        let T_1 = pc_gens.commit(t_poly.1, t_1_blinding)?;
+        let T_2 = pc_gens.commit(t_poly.2, t_2_blinding)?;
+
+        let transcript = transcript
+            .append::<schedule::T1>(&T_1)
+            .append::<schedule::T2>(&T_2);
+        let (transcript, x) = transcript.challenge_scalar::<schedule::X>();
+
+        // the blinding of t(x) is sum_j z^(2+j) v_blinding_j + t_1_blinding x + t_2_blinding x^2
+        let mut blinding_0 = Scalar::zero();
+        let mut exp_z = z.multiply_scalar(z)?;
+        for v_blinding in blindings {
+            blinding_0 = blinding_0.add_scalar(exp_z.multiply_scalar(*v_blinding)?)?;
+            exp_z = exp_z.multiply_scalar(z)?;
+        }
+        let t_blinding_poly = Poly2(blinding_0, t_1_blinding, t_2_blinding);
+
+        let t_x = t_poly.eval(x);
+        let t_x_blinding = t_blinding_poly.eval(x);
+        let e_blinding = a_blinding.add_scalar(s_blinding.multiply_scalar(x)?)?;
+        let l_vec = l_poly.eval(x);
+        let r_vec = r_poly.eval(x);
+
+        let transcript = transcript
+            .append::<schedule::Tx>(&t_x)
+            .append::<schedule::TxBlinding>(&t_x_blinding)
+            .append::<schedule::EBlinding>(&e_blinding);
+        let (transcript, w) = transcript.challenge_scalar::<schedule::W>();
+
+        // the inner product argument proves <l, r> = t(x) with respect to the generators G and
+        // H' = y^-nm o H, which are scaled with the factors instead of being rescaled
+        let Q = pc_gens.B().multiply(&w)?;
+        let G_factors = vec![Scalar::one(); nm];
+        let H_factors = util::exp_iter(y.invert()?).take(nm).collect::<Vec<_>>();
+
+        let ipp_proof = InnerProductProof::create(
+            transcript.into_transcript(),
+            &Q,
+            &G_factors,
+            &H_factors,
+            G,
+            H,
+            l_vec,
+            r_vec,
+        )?;
+
+        let proof = RangeProof {
+            A,
+            S,
+            T_1,
+            T_2,
+            t_x,
+            t_x_blinding,
+            e_blinding,
+            ipp_proof,
+        };
+        Ok((proof, value_commitments))
+    }
+
+    /// Verify a range proof that the commitment `V` opens to a value in `[0, 2^n)`.
+    pub fn verify_single(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        V: &RistrettoPoint,
+        n: usize,
+    ) -> Result<(), String> {
+        verify_multiple(self, bp_gens, pc_gens, transcript, &[*V], n)
+    }
+
+    /// Verify an aggregated range proof that every commitment in `value_commitments` opens to a
+    /// value in `[0, 2^n)`.
+    ///
+    /// The commitments are padded with the identity to a power of two, as by the prover.
+    pub fn verify_multiple(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        value_commitments: &[RistrettoPoint],
+        n: usize,
+    ) -> Result<(), String> {
+        verify_multiple(self, bp_gens, pc_gens, transcript, value_commitments, n)
+    }
+
+    /// Verify a range proof that every commitment opens to a value in `[0, 2^bit_length)`, for
+    /// the `(commitment, bit_length)` pairs in `commitments`.
+    pub fn verify_batched(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        commitments: &[(RistrettoPoint, usize)],
+    ) -> Result<(), String> {
+        verify_batched(self, bp_gens, pc_gens, transcript, commitments)
+    }
+
+    /// Verify a range proof that the commitment `V` opens to a value in the interval `[a, b)`.
+    ///
+    /// The verifier derives the shifted commitments `V - a * B` and `V + (2^n - b) * B` from `V`
+    /// and the public interval, and verifies the aggregated proof for them.
+    pub fn verify_interval(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        V: &RistrettoPoint,
+        a: u64,
+        b: u64,
+    ) -> Result<(), String> {
+        verify_interval(self, bp_gens, pc_gens, transcript, V, a, b)
+    }
+
+    /// Verify many independent aggregated range proofs of values in `[0, 2^n)` at once.
+    ///
+    /// Every item is a proof, its value commitments, and its transcript. The verification
+    /// equations of all proofs are combined with random weights into one multiscalar
+    /// multiplication, whose generators `G` and `H` are shared by all proofs. The weights are
+    /// derived from all transcripts, after every proof has been replayed on its own transcript.
+    /// Each transcript is left in the same state as if its proof had been checked with
+    /// `verify_multiple`.
+    ///
+    /// If the combined check fails, every proof is checked on its own, and the error names the
+    /// first proof that fails.
+    pub fn batch_verify(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        items: &mut [(&RangeProof, &[RistrettoPoint], &mut Transcript)],
+        n: usize,
+    ) -> Result<(), String> {
+        let mut max_m = 1;
+        for (_, value_commitments, _) in items.iter() {
+            max_m = max_m.max(padded_party_count(value_commitments.len())?);
+        }
+        check_parameters(bp_gens, n, max_m)?;
+
+        let identity = RistrettoPoint::from_bytes(&[0; 32])?;
+        let mut batch_transcript = Transcript::new(b"rangeproof batch v1");
+        let mut equations = Vec::with_capacity(items.len());
+        for (index, (proof, value_commitments, transcript)) in items.iter_mut().enumerate() {
+            let m = padded_party_count(value_commitments.len())?;
+            let mut value_commitments = value_commitments.to_vec();
+            value_commitments.resize(m, identity);
+
+            let equation = verification_equation(
+                *proof,
+                range_proof_transcript(&mut **transcript, n as u64, m as u64),
+                &value_commitments,
+                &vec![n; m],
+            )
+            .map_err(|err| format!("Range proof {} failed verification: {}", index, err))?;
+            equations.push(equation);
+
+            // the weights depend on every proof of the batch, and the digest is squeezed from a
+            // copy so that the transcript of the caller ends as after `verify_multiple`
+            let mut proof_challenge = [0; 32];
+            transcript
+                .clone()
+                .challenge_bytes(b"batch", &mut proof_challenge);
+            batch_transcript.append_message(b"proof", &proof_challenge);
+        }
+
+        // sum_k weight_k (equation_k), where the scalars of the shared points are summed
+        let nm = n * max_m;
+        let mut G_scalars = vec![Scalar::zero(); nm];
+        let mut H_scalars = vec![Scalar::zero(); nm];
+        let mut B_scalar = Scalar::zero();
+        let mut B_blinding_scalar = Scalar::zero();
+        let mut scalars = Vec::new();
+        let mut points = Vec::new();
+        for equation in &equations {
+            let weight = batch_transcript.challenge_scalar(b"weight");
+            for (sum, scalar) in G_scalars.iter_mut().zip(&equation.G_scalars) {
+                *sum = sum.add_scalar(weight.multiply_scalar(*scalar)?)?;
+            }
+            for (sum, scalar) in H_scalars.iter_mut().zip(&equation.H_scalars) {
+                *sum = sum.add_scalar(weight.multiply_scalar(*scalar)?)?;
+            }
+            B_scalar = B_scalar.add_scalar(weight.multiply_scalar(equation.B_scalar)?)?;
+            B_blinding_scalar = B_blinding_scalar
+                .add_scalar(weight.multiply_scalar(equation.B_blinding_scalar)?)?;
+            for scalar in &equation.scalars {
+                scalars.push(weight.multiply_scalar(*scalar)?);
+            }
+            points.extend(&equation.points);
+        }
+        scalars.extend([B_scalar, B_blinding_scalar]);
+        points.extend([pc_gens.B(), pc_gens.B_blinding()]);
+        scalars.extend(G_scalars);
+        points.extend(bp_gens.G(n, max_m));
+        scalars.extend(H_scalars);
+        points.extend(bp_gens.H(n, max_m));
+
+        let mega_check = RistrettoPoint::multiscalar_multiply(&scalars, &points)?;
+        if is_identity(&mega_check) {
+            return Ok(());
+        }
+
+        // find the proof that fails, which only costs time if the batch is invalid
+        let G = bp_gens.G(n, max_m).copied().collect::<Vec<_>>();
+        let H = bp_gens.H(n, max_m).copied().collect::<Vec<_>>();
+        for (index, equation) in equations.iter().enumerate() {
+            if equation.check(pc_gens, &G, &H).is_err() {
+                return Err(format!("Range proof {} failed verification", index));
+            }
+        }
+        Err("Range proof batch verification failed".to_string())
+    }
+
+    /// The size of the proof in bytes, `2 + 32 * (2 lg(n) + 9)`.
+    pub fn serialized_size(&self) -> usize {
+        HEADER_SIZE + (2 * self.ipp_proof.L_vec.len() + 9) * 32
+    }
+
+    /// Serialize the proof as
+    /// `header || A || S || T_1 || T_2 || t_x || t_x_blinding || e_blinding || ipp`, where
+    /// `header` is the header of the `encoding` module and `ipp` is the body of the serialized
+    /// inner product proof.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_type(ProofType::Range)
+    }
+
+    /// Deserialize a proof that was serialized with `to_bytes` by any version of the encoding.
+    ///
+    /// Every point must be a valid Ristretto encoding and every scalar must be canonical.
+    pub fn from_bytes(slice: &[u8]) -> Result<Self, String> {
+        RangeProofView::from_bytes(slice).map(|view| view.to_proof())
+    }
+
+    /// Serialize the proof with the type tag `proof_type`.
+    pub(crate) fn to_bytes_with_type(&self, proof_type: ProofType) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.serialized_size());
+        buf.extend_from_slice(&encoding::header(proof_type));
+        buf.extend_from_slice(&self.A.to_bytes());
+        buf.extend_from_slice(&self.S.to_bytes());
+        buf.extend_from_slice(&self.T_1.to_bytes());
+        buf.extend_from_slice(&self.T_2.to_bytes());
+        buf.extend_from_slice(&self.t_x.to_bytes());
+        buf.extend_from_slice(&self.t_x_blinding.to_bytes());
+        buf.extend_from_slice(&self.e_blinding.to_bytes());
+        self.ipp_proof.write_body(&mut buf);
+        buf
+    }
+}
+
+/// A validated range proof that borrows its encoding.
+///
+/// `from_bytes` validates the encoding like `RangeProof::from_bytes`, but does not copy the body
+/// into an owned proof: the verifier reads every point and scalar from the borrowed bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeProofView<'a> {
+    /// The points and scalars before the inner product proof
+    head: &'a [u8],
+    /// The inner product proof
+    ipp_proof: InnerProductProofView<'a>,
+}
+
+impl<'a> RangeProofView<'a> {
+    /// Validate a proof that was serialized with `RangeProof::to_bytes` by any version of the
+    /// encoding, and borrow it.
+    pub fn from_bytes(slice: &'a [u8]) -> Result<Self, String> {
+        Self::from_body(ProofBytes::body_of(slice, ProofType::Range)?)
+    }
+
+    /// Validate the body of the encoding, and borrow it.
+    pub(crate) fn from_body(body: &'a [u8]) -> Result<Self, String> {
+        if body.len() < 7 * 32 {
+            return Err("Range proof has an invalid length".to_string());
+        }
+        let (head, ipp_bytes) = body.split_at(7 * 32);
+        for index in 0..4 {
+            if !encoding::point_at(head, index).is_valid() {
+                return Err("Range proof contains an invalid point".to_string());
+            }
+        }
+        for index in 4..7 {
+            if !encoding::scalar_at(head, index).is_canonical() {
+                return Err("Range proof contains a non-canonical scalar".to_string());
+            }
+        }
+        let ipp_proof = InnerProductProofView::from_body(ipp_bytes)?;
+
+        Ok(Self { head, ipp_proof })
+    }
+
+    /// Verify the proof as `RangeProof::verify_single` does.
+    pub fn verify_single(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        V: &RistrettoPoint,
+        n: usize,
+    ) -> Result<(), String> {
+        verify_multiple(self, bp_gens, pc_gens, transcript, &[*V], n)
+    }
+
+    /// Verify the proof as `RangeProof::verify_multiple` does.
+    pub fn verify_multiple(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        value_commitments: &[RistrettoPoint],
+        n: usize,
+    ) -> Result<(), String> {
+        verify_multiple(self, bp_gens, pc_gens, transcript, value_commitments, n)
+    }
+
+    /// Verify the proof as `RangeProof::verify_batched` does.
+    pub fn verify_batched(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        commitments: &[(RistrettoPoint, usize)],
+    ) -> Result<(), String> {
+        verify_batched(self, bp_gens, pc_gens, transcript, commitments)
+    }
+
+    /// Verify the proof as `RangeProof::verify_interval` does.
+    pub fn verify_interval(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        V: &RistrettoPoint,
+        a: u64,
+        b: u64,
+    ) -> Result<(), String> {
+        verify_interval(self, bp_gens, pc_gens, transcript, V, a, b)
+    }
+
+    /// Copy the proof into an owned proof.
+    pub fn to_proof(&self) -> RangeProof {
+        RangeProof {
+            A: self.A(),
+            S: self.S(),
+            T_1: self.T_1(),
+            T_2: self.T_2(),
+            t_x: self.t_x(),
+            t_x_blinding: self.t_x_blinding(),
+            e_blinding: self.e_blinding(),
+            ipp_proof: self.ipp_proof.to_proof(),
+        }
+    }
+}
+
+/// The points and scalars of a range proof, which `RangeProof` owns and `RangeProofView` reads
+/// from its encoding.
+trait RangeProofElements {
+    type InnerProduct: InnerProductElements;
+
+    fn A(&self) -> RistrettoPoint;
+    fn S(&self) -> RistrettoPoint;
+    fn T_1(&self) -> RistrettoPoint;
+    fn T_2(&self) -> RistrettoPoint;
+    fn t_x(&self) -> Scalar;
+    fn t_x_blinding(&self) -> Scalar;
+    fn e_blinding(&self) -> Scalar;
+    fn ipp_proof(&self) -> &Self::InnerProduct;
+}
+
+impl RangeProofElements for RangeProof {
+    type InnerProduct = InnerProductProof;
+
+    fn A(&self) -> RistrettoPoint {
+        self.A
+    }
+
+    fn S(&self) -> RistrettoPoint {
+        self.S
+    }
+
+    fn T_1(&self) -> RistrettoPoint {
+        self.T_1
+    }
+
+    fn T_2(&self) -> RistrettoPoint {
+        self.T_2
+    }
+
+    fn t_x(&self) -> Scalar {
+        self.t_x
+    }
+
+    fn t_x_blinding(&self) -> Scalar {
+        self.t_x_blinding
+    }
+
+    fn e_blinding(&self) -> Scalar {
+        self.e_blinding
+    }
+
+    fn ipp_proof(&self) -> &InnerProductProof {
+        &self.ipp_proof
+    }
+}
+
+impl<'a> RangeProofElements for RangeProofView<'a> {
+    type InnerProduct = InnerProductProofView<'a>;
+
+    fn A(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, 0)
+    }
+
+    fn S(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, 1)
+    }
+
+    fn T_1(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, 2)
+    }
+
+    fn T_2(&self) -> RistrettoPoint {
+        encoding::point_at(self.head, 3)
+    }
+
+    fn t_x(&self) -> Scalar {
+        encoding::scalar_at(self.head, 4)
+    }
+
+    fn t_x_blinding(&self) -> Scalar {
+        encoding::scalar_at(self.head, 5)
+    }
+
+    fn e_blinding(&self) -> Scalar {
+        encoding::scalar_at(self.head, 6)
+    }
+
+    fn ipp_proof(&self) -> &InnerProductProofView<'a> {
+        &self.ipp_proof
+    }
+}
+
+/// Verify an aggregated range proof that every commitment in `value_commitments` opens to a
+/// value in `[0, 2^n)`.
+///
+/// The commitments are padded with the identity to a power of two, as by the prover.
+fn verify_multiple(
+    proof: &impl RangeProofElements,
+    bp_gens: &BulletproofGens,
+    pc_gens: &PedersenGens,
+    transcript: &mut Transcript,
+    value_commitments: &[RistrettoPoint],
+    n: usize,
+) -> Result<(), String> {
+    let m = padded_party_count(value_commitments.len())?;
+    check_parameters(bp_gens, n, m)?;
+
+    let mut value_commitments = value_commitments.to_vec();
+    value_commitments.resize(m, RistrettoPoint::from_bytes(&[0; 32])?);
+
+    let G = bp_gens.G(n, m).copied().collect::<Vec<_>>();
+    let H = bp_gens.H(n, m).copied().collect::<Vec<_>>();
+    let transcript = range_proof_transcript(transcript, n as u64, m as u64);
+
+    verify_with_bit_lengths(
+        proof,
+        pc_gens,
+        transcript,
+        G,
+        H,
+        &value_commitments,
+        &vec![n; m],
+    )
+}
+
+/// Verify a range proof that every commitment opens to a value in `[0, 2^bit_length)`, for
+/// the `(commitment, bit_length)` pairs in `commitments`.
+fn verify_batched(
+    proof: &impl RangeProofElements,
+    bp_gens: &BulletproofGens,
+    pc_gens: &PedersenGens,
+    transcript: &mut Transcript,
+    commitments: &[(RistrettoPoint, usize)],
+) -> Result<(), String> {
+    let (value_commitments, bit_lengths): (Vec<_>, Vec<_>) = commitments.iter().copied().unzip();
+    let nm = check_bit_lengths(bp_gens, &bit_lengths)?;
+
+    let G = bp_gens.share(0).G(nm).copied().collect::<Vec<_>>();
+    let H = bp_gens.share(0).H(nm).copied().collect::<Vec<_>>();
+    let transcript = batched_range_proof_transcript(transcript, &bit_lengths);
+
+    verify_with_bit_lengths(
+        proof,
+        pc_gens,
+        transcript,
+        G,
+        H,
+        &value_commitments,
+        &bit_lengths,
+    )
+}
+
+/// Verify a range proof that the commitment `V` opens to a value in the interval `[a, b)`.
+///
+/// The verifier derives the shifted commitments `V - a * B` and `V + (2^n - b) * B` from `V`
+/// and the public interval, and verifies the aggregated proof for them.
+fn verify_interval(
+    proof: &impl RangeProofElements,
+    bp_gens: &BulletproofGens,
+    pc_gens: &PedersenGens,
+    transcript: &mut Transcript,
+    V: &RistrettoPoint,
+    a: u64,
+    b: u64,
+) -> Result<(), String> {
+    let n = interval_bit_size(a, b)?;
+    if !V.is_valid() {
+        return Err("Range proof commitment is not a valid point".to_string());
+    }
+    let lower = V.subtract(&pc_gens.B().multiply(&Scalar::from(a))?)?;
+    // the shift 2^n - b is negative if b > 2^n
+    let upper = if b as u128 <= 1u128 << n {
+        let shift = ((1u128 << n) - b as u128) as u64;
+        V.add(&pc_gens.B().multiply(&Scalar::from(shift))?)?
+    } else {
+        let shift = (b as u128 - (1u128 << n)) as u64;
+        V.subtract(&pc_gens.B().multiply(&Scalar::from(shift))?)?
+    };
+
+    append_interval(transcript, a, b);
+    verify_multiple(proof, bp_gens, pc_gens, transcript, &[lower, upper], n)
+}
+
+/// Verify a range proof for commitments with the given bit lengths, with respect to the
+/// generators `G` and `H` of length `sum_j bit_lengths[j]`.
+fn verify_with_bit_lengths(
+    proof: &impl RangeProofElements,
+    pc_gens: &PedersenGens,
+    transcript: TypedTranscript<AwaitingCommitments, &mut Transcript>,
+    G: Vec<RistrettoPoint>,
+    H: Vec<RistrettoPoint>,
+    value_commitments: &[RistrettoPoint],
+    bit_lengths: &[usize],
+) -> Result<(), String> {
+    verification_equation(proof, transcript, value_commitments, bit_lengths)?.check(pc_gens, &G, &H)
+}
+
+/// Replay the proof on the transcript and compute the scalars of its verification equation.
+fn verification_equation(
+    proof: &impl RangeProofElements,
+    transcript: TypedTranscript<AwaitingCommitments, &mut Transcript>,
+    value_commitments: &[RistrettoPoint],
+    bit_lengths: &[usize],
+) -> Result<VerificationEquation, String> {
+    let nm = bit_lengths.iter().sum::<usize>();
+    if value_commitments.iter().any(|V| !V.is_valid()) {
+        return Err("Range proof commitment is not a valid point".to_string());
+    }
+    for point in [proof.A(), proof.S(), proof.T_1(), proof.T_2()] {
+        if is_identity(&point) {
+            return Err("Range proof contains the identity".to_string());
+        }
+    }
+
+    let mut transcript = transcript;
+    for V in value_commitments {
+        transcript = transcript.append::<schedule::V>(V);
+    }
+    let transcript = transcript
+        .append::<schedule::A>(&proof.A())
+        .append::<schedule::S>(&proof.S());
+    let (transcript, y) = transcript.challenge_scalar::<schedule::Y>();
+    let (transcript, z) = transcript.challenge_scalar::<schedule::Z>();
+    let transcript = transcript
+        .append::<schedule::T1>(&proof.T_1())
+        .append::<schedule::T2>(&proof.T_2());
+    let (transcript, x) = transcript.challenge_scalar::<schedule::X>();
+    let transcript = transcript
+        .append::<schedule::Tx>(&proof.t_x())
+        .append::<schedule::TxBlinding>(&proof.t_x_blinding())
+        .append::<schedule::EBlinding>(&proof.e_blinding());
+    let (transcript, w) = transcript.challenge_scalar::<schedule::W>();
+    let transcript = transcript.into_transcript();
+
+    // y^-1 is inverted in the same batch as the inner product challenges
+    let mut y_inv = [y];
+    let (x_sq, x_inv_sq, s) = proof
+        .ipp_proof()
+        .verification_scalars(nm, transcript, &mut y_inv)?;
+    let [y_inv] = y_inv;
+
+    // the two checks of the verifier are combined with a random challenge `c`, which the
+    // verifier squeezes after the prover's last message from a copy of the transcript, so
+    // that the transcript of the caller ends in the same state as the prover's
+    let c = transcript.clone().challenge_scalar(b"c");
+
+    let a = proof.ipp_proof().a();
+    let b = proof.ipp_proof().b();
+    let zz = z.multiply_scalar(z)?;
+    let minus_z = z.negate()?;
+
+    let size = 2 * x_sq.len() + value_commitments.len() + 4;
+    let mut scalars = Vec::with_capacity(size);
+    let mut points = Vec::with_capacity(size);
+
+    // A + x S + c x T_1 + c x^2 T_2
+    let cx = c.multiply_scalar(x)?;
+    scalars.extend([Scalar::one(), x, cx, cx.multiply_scalar(x)?]);
+    points.extend([proof.A(), proof.S(), proof.T_1(), proof.T_2()]);
+
+    // sum_j c z^(2+j) V_j
+    let mut c_exp_z = c.multiply_scalar(zz)?;
+    for V in value_commitments {
+        scalars.push(c_exp_z);
+        points.push(*V);
+        c_exp_z = c_exp_z.multiply_scalar(z)?;
+    }
+
+    // sum_j x_j^2 L_j + x_j^-2 R_j
+    scalars.extend(x_sq);
+    scalars.extend(x_inv_sq);
+    proof.ipp_proof().extend_rounds(&mut points);
+
+    // -(e_blinding + c t_x_blinding) B_blinding
+    let B_blinding_scalar = proof
+        .e_blinding()
+        .add_scalar(c.multiply_scalar(proof.t_x_blinding())?)?
+        .negate()?;
+
+    // (w (t_x - a b) + c (delta(y, z) - t_x)) B
+    let B_scalar = w
+        .multiply_scalar(proof.t_x().subtract_scalar(a.multiply_scalar(b)?)?)?
+        .add_scalar(
+            c.multiply_scalar(delta(bit_lengths, &y, &z)?.subtract_scalar(proof.t_x())?)?,
+        )?;
+
+    // sum_i (-z - a s_i) G_i
+    let G_scalars = s
+        .iter()
+        .map(|s_i| minus_z.subtract_scalar(a.multiply_scalar(*s_i)?))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // sum_i (z + y^-i (z^(2+j) 2^k - b s_i^-1)) H_i, where bit i is bit k of value j and
+    // s_i^-1 = s_(nm - 1 - i)
+    let two = Scalar::from(2);
+    let mut H_scalars = Vec::with_capacity(nm);
+    let mut s_inv = s.iter().rev();
+    let mut exp_y_inv = util::exp_iter(y_inv);
+    let mut exp_z = zz;
+    for n in bit_lengths {
+        let mut exp_2 = Scalar::one();
+        for (s_i_inv, exp_y_inv) in s_inv.by_ref().zip(exp_y_inv.by_ref()).take(*n) {
+            let z_2 = exp_z.multiply_scalar(exp_2)?;
+            let h_i =
+                exp_y_inv.multiply_scalar(z_2.subtract_scalar(b.multiply_scalar(*s_i_inv)?)?)?;
+            H_scalars.push(z.add_scalar(h_i)?);
+            exp_2 = exp_2.multiply_scalar(two)?;
+        }
+        exp_z = exp_z.multiply_scalar(z)?;
+    }
+
+    Ok(VerificationEquation {
+        scalars,
+        points,
+        B_scalar,
+        B_blinding_scalar,
+        G_scalars,
+        H_scalars,
+    })
+}
+
+/// The verification equation of a range proof, which holds if and only if the multiscalar
+/// multiplication of all scalars and points is the identity.
+///
+/// The scalars of the generators are kept apart from the points that are specific to the proof,
+/// so that the equations of several proofs can share the generators in a batch.
+struct VerificationEquation {
+    /// Scalars of the points of the proof and the value commitments
+    scalars: Vec<Scalar>,
+    /// The points of the proof and the value commitments
+    points: Vec<RistrettoPoint>,
+    /// Scalar of the Pedersen base `B`
+    B_scalar: Scalar,
+    /// Scalar of the Pedersen blinding base `B_blinding`
+    B_blinding_scalar: Scalar,
+    /// Scalars of the first `nm` generators `G`
+    G_scalars: Vec<Scalar>,
+    /// Scalars of the first `nm` generators `H`
+    H_scalars: Vec<Scalar>,
+}
+
+impl VerificationEquation {
+    /// Check the equation with one multiscalar multiplication of size
+    /// `2nm + 2 lg(nm) + m + 6`.
+    fn check(
+        &self,
+        pc_gens: &PedersenGens,
+        G: &[RistrettoPoint],
+        H: &[RistrettoPoint],
+    ) -> Result<(), String> {
+        let nm = self.G_scalars.len();
+        let scalars = [
+            &self.scalars[..],
+            &[self.B_scalar, self.B_blinding_scalar],
+            &self.G_scalars,
+            &self.H_scalars,
+        ]
+        .concat();
+        let points = [
+            &self.points[..],
+            &[pc_gens.B(), pc_gens.B_blinding()],
+            &G[..nm],
+            &H[..nm],
+        ]
+        .concat();
+
+        let mega_check = RistrettoPoint::multiscalar_multiply(&scalars, &points)?;
+        if is_identity(&mega_check) {
+            Ok(())
+        } else {
+            Err("Range proof verification failed".to_string())
+        }
+    }
+}
+
+/// The bit size of the range proofs for the interval `[a, b)`, i.e. the smallest supported bit
+/// size `n` with `b - a <= 2^n`.
+pub fn interval_bit_size(a: u64, b: u64) -> Result<usize, String> {
+    if a >= b {
+        return Err("Range proof interval is empty".to_string());
+    }
+    let width = (b - a) as u128;
+    Ok(*SUPPORTED_BIT_SIZES
+        .iter()
+        .find(|n| width <= 1u128 << **n)
+        .unwrap())
+}
+
+/// The number of parties of an aggregated proof for `num_values` values, i.e. `num_values`
+/// rounded up to a power of two.
+fn padded_party_count(num_values: usize) -> Result<usize, String> {
+    if num_values == 0 {
+        return Err("Range proof needs at least one value".to_string());
+    }
+    num_values
+        .checked_next_power_of_two()
+        .ok_or_else(|| "Range proof has too many values".to_string())
+}
+
+/// Check that `n` is a supported bit size and that the generators have capacity for `m` parties.
+fn check_parameters(bp_gens: &BulletproofGens, n: usize, m: usize) -> Result<(), String> {
+    if !SUPPORTED_BIT_SIZES.contains(&n) {
+        return Err("Range proof bit size must be 8, 16, 32, or 64".to_string());
+    }
+    if bp_gens.gens_capacity < n || bp_gens.party_capacity < m {
+        return Err("Bulletproof generators have insufficient capacity".to_string());
+    }
+    Ok(())
+}
+
+/// Check that every bit length is supported and that their sum is a power of two for which the
+/// first party of the generators has capacity. Returns the sum.
+fn check_bit_lengths(bp_gens: &BulletproofGens, bit_lengths: &[usize]) -> Result<usize, String> {
+    if bit_lengths.is_empty() {
+        return Err("Range proof needs at least one value".to_string());
+    }
+    if bit_lengths.iter().any(|n| *n == 0 || *n > 64) {
+        return Err("Range proof bit lengths must be between 1 and 64".to_string());
+    }
+    let nm = bit_lengths.iter().sum::<usize>();
+    if !nm.is_power_of_two() {
+        return Err("Range proof bit lengths must sum to a power of two".to_string());
+    }
+    if bp_gens.gens_capacity < nm || bp_gens.party_capacity < 1 {
+        return Err("Bulletproof generators have insufficient capacity".to_string());
+    }
+    Ok(nm)
+}
+
+/// Derive the randomness of the prover from the transcript, the witness, and `rng`.
+#[cfg(not(target_os = "solana"))]
+fn prover_rng<R: RngCore + CryptoRng>(
+    transcript: &Transcript,
+    values: &[u64],
+    blindings: &[Scalar],
+    rng: &mut R,
+) -> TranscriptRng {
+    let mut rng_builder = transcript.build_rng();
+    for (v, v_blinding) in values.iter().zip(blindings) {
+        rng_builder = rng_builder
+            .rekey_with_witness_bytes(b"v", &v.to_le_bytes())
+            .rekey_with_witness_bytes(b"v_blinding", &v_blinding.to_bytes());
+    }
+    rng_builder.finalize(rng)
+}
+
+/// Compute `delta(y, z) = (z - z^2) <1, y^nm> - sum_j z^(3+j) <1, 2^(n_j)>`.
+fn delta(bit_lengths: &[usize], y: &Scalar, z: &Scalar) -> Result<Scalar, String> {
+    let nm = bit_lengths.iter().sum::<usize>();
+    let zz = z.multiply_scalar(*z)?;
+    let sum_y = util::sum_of_powers(y, nm);
+    let mut delta = z.subtract_scalar(zz)?.multiply_scalar(sum_y)?;
+
+    let mut exp_z = zz.multiply_scalar(*z)?;
+    for n in bit_lengths {
+        // <1, 2^n> = 2^n - 1, which is at most u64::MAX
+        let sum_2 = Scalar::from(((1u128 << n) - 1) as u64);
+        delta = delta.subtract_scalar(exp_z.multiply_scalar(sum_2)?)?;
+        exp_z = exp_z.multiply_scalar(*z)?;
+    }
+    Ok(delta)
+}
+
+fn is_identity(point: &RistrettoPoint) -> bool {
+    point.to_bytes() == [0; 32]
+}
@@ -0,0 +1,254 @@
+//! The party of the multi-party computation, which holds one secret value.
+//!
+//! A party moves through the states `PartyAwaitingPosition`, `PartyAwaitingBitChallenge` and
+//! `PartyAwaitingPolyChallenge`, and every transition consumes the previous state, so a party can
+//! only answer each challenge once. The party never reveals its value or blinding factor; it
+//! only sends commitments and its share of the evaluations at the challenge `x`.
+
+use {
+    super::{
+        messages::{BitChallenge, BitCommitment, PolyChallenge, PolyCommitment, ProofShare},
+        SUPPORTED_BIT_SIZES,
+    },
+    crate::{
+        errors::MPCError,
+        generators::{BulletproofGens, PedersenGens},
+        util::{self, random_scalar, Poly2, VecPoly1},
+    },
+    rand_core::{CryptoRng, OsRng, RngCore},
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// The entry point of a party.
+pub struct Party {}
+
+impl Party {
+    /// Create a party that proves that `v` is in `[0, 2^n)`.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<'a>(
+        bp_gens: &'a BulletproofGens,
+        pc_gens: &'a PedersenGens,
+        v: u64,
+        v_blinding: Scalar,
+        n: usize,
+    ) -> Result<PartyAwaitingPosition<'a>, MPCError> {
+        if !SUPPORTED_BIT_SIZES.contains(&n) || bp_gens.gens_capacity < n {
+            return Err(MPCError::InvalidBitsize);
+        }
+        if n < 64 && v >> n != 0 {
+            return Err(MPCError::ValueOutOfRange);
+        }
+        let V = pc_gens.commit(Scalar::from(v), v_blinding)?;
+
+        Ok(PartyAwaitingPosition {
+            bp_gens,
+            pc_gens,
+            n,
+            v,
+            v_blinding,
+            V,
+        })
+    }
+}
+
+/// A party that waits for the dealer to assign its position `j`.
+pub struct PartyAwaitingPosition<'a> {
+    bp_gens: &'a BulletproofGens,
+    pc_gens: &'a PedersenGens,
+    n: usize,
+    v: u64,
+    v_blinding: Scalar,
+    V: RistrettoPoint,
+}
+
+impl<'a> PartyAwaitingPosition<'a> {
+    /// Take position `j` with randomness from the operating system.
+    pub fn assign_position(
+        self,
+        j: usize,
+    ) -> Result<(PartyAwaitingBitChallenge<'a>, BitCommitment), MPCError> {
+        self.assign_position_with_rng(j, &mut OsRng)
+    }
+
+    /// Take position `j` with randomness from `rng`, and commit to the bits of the value with the
+    /// generators of party `j`.
+    pub fn assign_position_with_rng<R: RngCore + CryptoRng>(
+        self,
+        j: usize,
+        rng: &mut R,
+    ) -> Result<(PartyAwaitingBitChallenge<'a>, BitCommitment), MPCError> {
+        if self.bp_gens.party_capacity <= j {
+            return Err(MPCError::InvalidAggregation);
+        }
+        let n = self.n;
+        let share = self.bp_gens.share(j);
+
+        // a_L are the bits of the value and a_R = a_L - 1
+        let minus_one = Scalar::one().negate()?;
+        let mut a_L = Vec::with_capacity(n);
+        let mut a_R = Vec::with_capacity(n);
+        for i in 0..n {
+            if (self.v >> i) & 1 == 1 {
+                a_L.push(Scalar::one());
+                a_R.push(Scalar::zero());
+            } else {
+                a_L.push(Scalar::zero());
+                a_R.push(minus_one);
+            }
+        }
+        let points = [
+            &[self.pc_gens.B_blinding()][..],
+            &share.G(n).copied().collect::<Vec<_>>(),
+            &share.H(n).copied().collect::<Vec<_>>(),
+        ]
+        .concat();
+
+        let a_blinding = random_scalar(rng);
+        let A_scalars = [&[a_blinding], &a_L[..], &a_R[..]].concat();
+        let A_j = RistrettoPoint::multiscalar_multiply(&A_scalars, &points)?;
+
+        let s_blinding = random_scalar(rng);
+        let s_L = (0..n).map(|_| random_scalar(rng)).collect::<Vec<_>>();
+        let s_R = (0..n).map(|_| random_scalar(rng)).collect::<Vec<_>>();
+        let S_scalars = [&[s_blinding], &s_L[..], &s_R[..]].concat();
+        let S_j = RistrettoPoint::multiscalar_multiply(&S_scalars, &points)?;
+
+        let bit_commitment = BitCommitment {
+            V_j: self.V,
+            A_j,
+            S_j,
+        };
+        let next_state = PartyAwaitingBitChallenge {
+            pc_gens: self.pc_gens,
+            n,
+            j,
+            v_blinding: self.v_blinding,
+            a_L,
+            a_R,
+            a_blinding,
+            s_L,
+            s_R,
+            s_blinding,
+        };
+        Ok((next_state, bit_commitment))
+    }
+}
+
+/// A party that waits for the challenges `y` and `z` of the dealer.
+pub struct PartyAwaitingBitChallenge<'a> {
+    pc_gens: &'a PedersenGens,
+    n: usize,
+    j: usize,
+    v_blinding: Scalar,
+    a_L: Vec<Scalar>,
+    a_R: Vec<Scalar>,
+    a_blinding: Scalar,
+    s_L: Vec<Scalar>,
+    s_R: Vec<Scalar>,
+    s_blinding: Scalar,
+}
+
+impl PartyAwaitingBitChallenge<'_> {
+    /// Apply the challenges `y` and `z` with randomness from the operating system.
+    pub fn apply_challenge(
+        self,
+        bit_challenge: &BitChallenge,
+    ) -> Result<(PartyAwaitingPolyChallenge, PolyCommitment), MPCError> {
+        self.apply_challenge_with_rng(bit_challenge, &mut OsRng)
+    }
+
+    /// Apply the challenges `y` and `z` with randomness from `rng`, and commit to the
+    /// coefficients of the share of `t(x)` of the party.
+    pub fn apply_challenge_with_rng<R: RngCore + CryptoRng>(
+        self,
+        bit_challenge: &BitChallenge,
+        rng: &mut R,
+    ) -> Result<(PartyAwaitingPolyChallenge, PolyCommitment), MPCError> {
+        let BitChallenge { y, z } = *bit_challenge;
+        if y == Scalar::zero() || z == Scalar::zero() {
+            return Err(MPCError::MaliciousDealer);
+        }
+        let n = self.n;
+
+        // the share of party j of
+        // l(x) = (a_L - z 1) + s_L x
+        // r(x) = y^nm o (a_R + z 1 + s_R x) + sum_j z^(2+j) (0 || 2^n || 0)
+        // are the n entries at the positions jn..(j+1)n
+        let exp_z = util::exp_iter(z)
+            .nth(self.j)
+            .unwrap()
+            .multiply_scalar(z.multiply_scalar(z)?)?;
+        let mut l_poly = VecPoly1::zero(n);
+        let mut r_poly = VecPoly1::zero(n);
+        let mut exp_y = util::exp_iter(y).nth(self.j * n).unwrap();
+        let mut exp_2 = Scalar::one();
+        let two = Scalar::from(2);
+        for i in 0..n {
+            l_poly.0[i] = self.a_L[i].subtract_scalar(z)?;
+            l_poly.1[i] = self.s_L[i];
+            r_poly.0[i] = exp_y
+                .multiply_scalar(self.a_R[i].add_scalar(z)?)?
+                .add_scalar(exp_z.multiply_scalar(exp_2)?)?;
+            r_poly.1[i] = exp_y.multiply_scalar(self.s_R[i])?;
+
+            exp_y = exp_y.multiply_scalar(y)?;
+            exp_2 = exp_2.multiply_scalar(two)?;
+        }
+
+        let t_poly = l_poly
+            .inner_product(&r_poly)
+            .ok_or_else(|| MPCError::ProofError("Polynomial lengths do not match".to_string()))?;
+
+        let t_1_blinding = random_scalar(rng);
+        let t_2_blinding = random_scalar(rng);
+        let T_1_j = self.pc_gens.commit(t_poly.1, t_1_blinding)?;
+        let T_2_j = self.pc_gens.commit(t_poly.2, t_2_blinding)?;
+
+        let poly_commitment = PolyCommitment { T_1_j, T_2_j };
+        let next_state = PartyAwaitingPolyChallenge {
+            l_poly,
+            r_poly,
+            t_poly,
+            t_blinding_poly: Poly2(
+                exp_z.multiply_scalar(self.v_blinding)?,
+                t_1_blinding,
+                t_2_blinding,
+            ),
+            a_blinding: self.a_blinding,
+            s_blinding: self.s_blinding,
+        };
+        Ok((next_state, poly_commitment))
+    }
+}
+
+/// A party that waits for the challenge `x` of the dealer.
+pub struct PartyAwaitingPolyChallenge {
+    l_poly: VecPoly1,
+    r_poly: VecPoly1,
+    t_poly: Poly2,
+    t_blinding_poly: Poly2,
+    a_blinding: Scalar,
+    s_blinding: Scalar,
+}
+
+impl PartyAwaitingPolyChallenge {
+    /// Apply the challenge `x` and evaluate the share of the party at `x`.
+    ///
+    /// A zero challenge would reveal the blinding factors of the party, so it is rejected.
+    pub fn apply_challenge(self, poly_challenge: &PolyChallenge) -> Result<ProofShare, MPCError> {
+        let x = poly_challenge.x;
+        if x == Scalar::zero() {
+            return Err(MPCError::MaliciousDealer);
+        }
+
+        Ok(ProofShare {
+            t_x: self.t_poly.eval(x),
+            t_x_blinding: self.t_blinding_poly.eval(x),
+            e_blinding: self
+                .a_blinding
+                .add_scalar(self.s_blinding.multiply_scalar(x)?)?,
+            l_vec: self.l_poly.eval(x),
+            r_vec: self.r_poly.eval(x),
+        })
+    }
+}
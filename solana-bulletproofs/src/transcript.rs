@@ -0,0 +1,369 @@
+//! The message schedules of the range proof, the R1CS proof, and the inner product argument.
+//!
+//! The prover and the verifier of each protocol are written against the states in this module, so
+//! a message that is skipped, reordered, or appended under a different label on one side does not
+//! compile. See `solana_merlin::typed` for how the states work.
+//!
+//! The range proof schedule is
+//!
+//! ```text
+//! AwaitingCommitments  --V*, A-->  AwaitingS  --S-->  AwaitingY  --y-->  AwaitingZ  --z-->
+//! AwaitingT1  --T_1-->  AwaitingT2  --T_2-->  AwaitingX  --x-->  AwaitingTx  --t_x-->
+//! AwaitingTxBlinding  --t_x_blinding-->  AwaitingEBlinding  --e_blinding-->  AwaitingW  --w-->
+//! AwaitingInnerProduct
+//! ```
+//!
+//! the R1CS proof schedule joins the range proof schedule at `AwaitingX`,
+//!
+//! ```text
+//! R1CSAwaitingCommitments  --V*, A_I1-->  R1CSAwaitingAO1  --A_O1-->  R1CSAwaitingS1  --S1-->
+//! R1CSAwaitingY  --y-->  R1CSAwaitingZ  --z-->  R1CSAwaitingT1  --T_1-->  R1CSAwaitingT3
+//! --T_3-->  R1CSAwaitingT4  --T_4-->  R1CSAwaitingT5  --T_5-->  R1CSAwaitingT6  --T_6-->
+//! AwaitingX
+//! ```
+//!
+//! where a proof with randomized constraints continues after `S1` with a second phase,
+//!
+//! ```text
+//! R1CSAwaitingY  [r1cs_randomized_constraints, challenges, r1cs_second_phase]  R1CSAwaitingAI2
+//! --A_I2-->  R1CSAwaitingAO2  --A_O2-->  R1CSAwaitingS2  --S2-->  R1CSAwaitingU  --u-->
+//! R1CSAwaitingY
+//! ```
+//!
+//! in which the randomized constraints squeeze their challenges under their own labels, and the
+//! inner product argument schedule is
+//!
+//! ```text
+//! AwaitingL  --L-->  AwaitingR  --R-->  AwaitingU  --u-->  AwaitingL
+//! ```
+//!
+//! where the number of rounds is checked by the prover and the verifier at runtime.
+
+use {
+    solana_merlin::{
+        typed::{Challenge, Message, Transition, TypedTranscript},
+        Transcript,
+    },
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+    std::borrow::BorrowMut,
+};
+
+macro_rules! define_message {
+    ($(#[$meta:meta])* $name:ident, $label:literal, $value:ty) => {
+        $(#[$meta])*
+        pub struct $name;
+
+        impl Message for $name {
+            const LABEL: &'static [u8] = $label;
+            type Value = $value;
+        }
+    };
+}
+
+macro_rules! define_challenge {
+    ($(#[$meta:meta])* $name:ident, $label:literal) => {
+        $(#[$meta])*
+        pub struct $name;
+
+        impl Challenge for $name {
+            const LABEL: &'static [u8] = $label;
+        }
+    };
+}
+
+macro_rules! define_transitions {
+    ($($state:ident: $($op:ident => $next:ident),+;)+) => {
+        $(
+            $(
+                impl Transition<$op> for $state {
+                    type Next = $next;
+                }
+            )+
+        )+
+    };
+}
+
+define_message!(
+    /// A value commitment of a range proof.
+    V, b"V", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the bit vectors of a range proof.
+    A, b"A", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the blinding vectors of a range proof.
+    S, b"S", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the first coefficient of `t(x)`.
+    T1, b"T_1", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the second coefficient of `t(x)`.
+    T2, b"T_2", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the left and right inputs of the multipliers of an R1CS proof.
+    AI1, b"A_I1", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the outputs of the multipliers of an R1CS proof.
+    AO1, b"A_O1", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the blinding vectors of an R1CS proof.
+    S1, b"S1", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the second phase inputs of the multipliers of an R1CS proof.
+    AI2, b"A_I2", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the second phase outputs of the multipliers of an R1CS proof.
+    AO2, b"A_O2", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the second phase blinding vectors of an R1CS proof.
+    S2, b"S2", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the third coefficient of `t(x)` of an R1CS proof.
+    T3, b"T_3", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the fourth coefficient of `t(x)` of an R1CS proof.
+    T4, b"T_4", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the fifth coefficient of `t(x)` of an R1CS proof.
+    T5, b"T_5", RistrettoPoint
+);
+define_message!(
+    /// The commitment to the sixth coefficient of `t(x)` of an R1CS proof.
+    T6, b"T_6", RistrettoPoint
+);
+define_message!(
+    /// The evaluation `t(x)`.
+    Tx, b"t_x", Scalar
+);
+define_message!(
+    /// The blinding factor of `t(x)`.
+    TxBlinding, b"t_x_blinding", Scalar
+);
+define_message!(
+    /// The blinding factor of the synthetic commitment to `l(x)` and `r(x)`.
+    EBlinding, b"e_blinding", Scalar
+);
+define_message!(
+    /// The left commitment of an inner product argument round.
+    L, b"L", RistrettoPoint
+);
+define_message!(
+    /// The right commitment of an inner product argument round.
+    R, b"R", RistrettoPoint
+);
+
+define_challenge!(
+    /// The challenge that combines the bits of a range proof.
+    Y, b"y"
+);
+define_challenge!(
+    /// The challenge that combines the range proof constraints.
+    Z, b"z"
+);
+define_challenge!(
+    /// The challenge at which `l(x)`, `r(x)`, and `t(x)` are evaluated.
+    X, b"x"
+);
+define_challenge!(
+    /// The challenge that binds `t(x)` into the inner product argument.
+    W, b"w"
+);
+define_challenge!(
+    /// The challenge of an inner product argument round, and the challenge that separates the
+    /// two phases of an R1CS proof.
+    U, b"u"
+);
+
+/// A range proof that expects value commitments, followed by `A`.
+pub struct AwaitingCommitments;
+/// A range proof that expects `S`.
+pub struct AwaitingS;
+/// A range proof that expects the challenge `y`.
+pub struct AwaitingY;
+/// A range proof that expects the challenge `z`.
+pub struct AwaitingZ;
+/// A range proof that expects `T_1`.
+pub struct AwaitingT1;
+/// A range proof that expects `T_2`.
+pub struct AwaitingT2;
+/// A range proof that expects the challenge `x`.
+pub struct AwaitingX;
+/// A range proof that expects `t_x`.
+pub struct AwaitingTx;
+/// A range proof that expects `t_x_blinding`.
+pub struct AwaitingTxBlinding;
+/// A range proof that expects `e_blinding`.
+pub struct AwaitingEBlinding;
+/// A range proof that expects the challenge `w`.
+pub struct AwaitingW;
+/// A range proof that continues with an inner product argument.
+pub struct AwaitingInnerProduct;
+
+/// An R1CS proof that expects value commitments, followed by `A_I1`.
+pub struct R1CSAwaitingCommitments;
+/// An R1CS proof that expects `A_O1`.
+pub struct R1CSAwaitingAO1;
+/// An R1CS proof that expects `S1`.
+pub struct R1CSAwaitingS1;
+/// An R1CS proof that expects `A_I2`.
+pub struct R1CSAwaitingAI2;
+/// An R1CS proof that expects `A_O2`.
+pub struct R1CSAwaitingAO2;
+/// An R1CS proof that expects `S2`.
+pub struct R1CSAwaitingS2;
+/// An R1CS proof that expects the challenge `u`.
+pub struct R1CSAwaitingU;
+/// An R1CS proof that expects the challenge `y`.
+pub struct R1CSAwaitingY;
+/// An R1CS proof that expects the challenge `z`.
+pub struct R1CSAwaitingZ;
+/// An R1CS proof that expects `T_1`.
+pub struct R1CSAwaitingT1;
+/// An R1CS proof that expects `T_3`.
+pub struct R1CSAwaitingT3;
+/// An R1CS proof that expects `T_4`.
+pub struct R1CSAwaitingT4;
+/// An R1CS proof that expects `T_5`.
+pub struct R1CSAwaitingT5;
+/// An R1CS proof that expects `T_6`.
+pub struct R1CSAwaitingT6;
+
+/// An inner product argument that expects `L` of the next round, or is complete.
+pub struct AwaitingL;
+/// An inner product argument that expects `R`.
+pub struct AwaitingR;
+/// An inner product argument that expects the challenge `u`.
+pub struct AwaitingU;
+
+define_transitions! {
+    AwaitingCommitments: V => AwaitingCommitments, A => AwaitingS;
+    AwaitingS: S => AwaitingY;
+    AwaitingY: Y => AwaitingZ;
+    AwaitingZ: Z => AwaitingT1;
+    AwaitingT1: T1 => AwaitingT2;
+    AwaitingT2: T2 => AwaitingX;
+    AwaitingX: X => AwaitingTx;
+    AwaitingTx: Tx => AwaitingTxBlinding;
+    AwaitingTxBlinding: TxBlinding => AwaitingEBlinding;
+    AwaitingEBlinding: EBlinding => AwaitingW;
+    AwaitingW: W => AwaitingInnerProduct;
+    R1CSAwaitingCommitments: V => R1CSAwaitingCommitments, AI1 => R1CSAwaitingAO1;
+    R1CSAwaitingAO1: AO1 => R1CSAwaitingS1;
+    R1CSAwaitingS1: S1 => R1CSAwaitingY;
+    R1CSAwaitingAI2: AI2 => R1CSAwaitingAO2;
+    R1CSAwaitingAO2: AO2 => R1CSAwaitingS2;
+    R1CSAwaitingS2: S2 => R1CSAwaitingU;
+    R1CSAwaitingU: U => R1CSAwaitingY;
+    R1CSAwaitingY: Y => R1CSAwaitingZ;
+    R1CSAwaitingZ: Z => R1CSAwaitingT1;
+    R1CSAwaitingT1: T1 => R1CSAwaitingT3;
+    R1CSAwaitingT3: T3 => R1CSAwaitingT4;
+    R1CSAwaitingT4: T4 => R1CSAwaitingT5;
+    R1CSAwaitingT5: T5 => R1CSAwaitingT6;
+    R1CSAwaitingT6: T6 => AwaitingX;
+    AwaitingL: L => AwaitingR;
+    AwaitingR: R => AwaitingU;
+    AwaitingU: U => AwaitingL;
+}
+
+/// Start a range proof for `m` values of `n` bits each.
+pub fn range_proof_transcript<T: BorrowMut<Transcript>>(
+    mut transcript: T,
+    n: u64,
+    m: u64,
+) -> TypedTranscript<AwaitingCommitments, T> {
+    let inner = transcript.borrow_mut();
+    inner.append_message(b"dom-sep", b"rangeproof v1");
+    inner.append_u64(b"n", n);
+    inner.append_u64(b"m", m);
+    TypedTranscript::from_transcript(transcript)
+}
+
+/// Start a range proof for values with the given bit lengths.
+///
+/// All bit lengths are appended, so a proof for one split of the total number of bits does not
+/// verify for another.
+pub fn batched_range_proof_transcript<T: BorrowMut<Transcript>>(
+    mut transcript: T,
+    bit_lengths: &[usize],
+) -> TypedTranscript<AwaitingCommitments, T> {
+    let inner = transcript.borrow_mut();
+    inner.append_message(b"dom-sep", b"batched rangeproof v1");
+    inner.append_u64(b"m", bit_lengths.len() as u64);
+    for n in bit_lengths {
+        inner.append_u64(b"n", *n as u64);
+    }
+    TypedTranscript::from_transcript(transcript)
+}
+
+/// Bind a range proof to the interval `[a, b)` of an interval range proof.
+///
+/// The interval is appended before the range proof schedule of the shifted commitments starts.
+pub fn append_interval(transcript: &mut Transcript, a: u64, b: u64) {
+    transcript.append_message(b"dom-sep", b"interval rangeproof v1");
+    transcript.append_u64(b"a", a);
+    transcript.append_u64(b"b", b);
+}
+
+/// Start an R1CS proof with `m` value commitments.
+pub fn r1cs_transcript<T: BorrowMut<Transcript>>(
+    mut transcript: T,
+    m: u64,
+) -> TypedTranscript<R1CSAwaitingCommitments, T> {
+    let inner = transcript.borrow_mut();
+    inner.append_message(b"dom-sep", b"r1cs v1");
+    inner.append_u64(b"m", m);
+    TypedTranscript::from_transcript(transcript)
+}
+
+/// Leave an R1CS proof after its first phase commitments to build its randomized constraints.
+///
+/// The randomized constraints squeeze their challenges from the returned transcript, before
+/// `r1cs_second_phase` resumes the schedule.
+pub fn r1cs_randomized_constraints<T: BorrowMut<Transcript>>(
+    transcript: TypedTranscript<R1CSAwaitingY, T>,
+) -> T {
+    let mut transcript = transcript.into_transcript();
+    transcript
+        .borrow_mut()
+        .append_message(b"dom-sep", b"r1cs randomized v1");
+    transcript
+}
+
+/// Resume an R1CS proof with its second phase commitments after its randomized constraints.
+pub fn r1cs_second_phase<T: BorrowMut<Transcript>>(
+    transcript: T,
+) -> TypedTranscript<R1CSAwaitingAI2, T> {
+    TypedTranscript::from_transcript(transcript)
+}
+
+/// Start an inner product argument for vectors of length `n`.
+pub fn inner_product_transcript<T: BorrowMut<Transcript>>(
+    mut transcript: T,
+    n: u64,
+) -> TypedTranscript<AwaitingL, T> {
+    let inner = transcript.borrow_mut();
+    inner.append_message(b"dom-sep", b"ipp v1");
+    inner.append_u64(b"n", n);
+    TypedTranscript::from_transcript(transcript)
+}
+
+/// Continue a range proof with its inner product argument for vectors of length `n`.
+pub fn range_proof_inner_product<T: BorrowMut<Transcript>>(
+    transcript: TypedTranscript<AwaitingInnerProduct, T>,
+    n: u64,
+) -> TypedTranscript<AwaitingL, T> {
+    inner_product_transcript(transcript.into_transcript(), n)
+}
@@ -1,3 +1,5 @@
+#[cfg(not(target_os = "solana"))]
+use rand_core::{CryptoRng, RngCore};
 use solana_ristretto::scalar::Scalar;
 
 /// Represents a degree-1 vector polynomial `a + b * x`.
@@ -51,6 +53,88 @@ impl Poly2 {
     }
 }
 
+/// Represents a degree-3 vector polynomial `a + b * x + c * x^2 + d * x^3`.
+pub struct VecPoly3(
+    pub Vec<Scalar>,
+    pub Vec<Scalar>,
+    pub Vec<Scalar>,
+    pub Vec<Scalar>,
+);
+
+impl VecPoly3 {
+    pub fn zero(n: usize) -> Self {
+        VecPoly3(
+            vec![Scalar::zero(); n],
+            vec![Scalar::zero(); n],
+            vec![Scalar::zero(); n],
+            vec![Scalar::zero(); n],
+        )
+    }
+
+    /// Compute the inner product of `self` and `rhs`, assuming that the constant term of `self`
+    /// and the quadratic term of `rhs` are zero, as they are for the polynomials of the R1CS
+    /// prover.
+    pub fn special_inner_product(&self, rhs: &VecPoly3) -> Option<Poly6> {
+        let l = self;
+        let r = rhs;
+
+        let t1 = inner_product(&l.1, &r.0)?;
+        let t2 = inner_product(&l.1, &r.1)?
+            .add_scalar(inner_product(&l.2, &r.0)?)
+            .unwrap();
+        let t3 = inner_product(&l.2, &r.1)?
+            .add_scalar(inner_product(&l.3, &r.0)?)
+            .unwrap();
+        let t4 = inner_product(&l.1, &r.3)?
+            .add_scalar(inner_product(&l.3, &r.1)?)
+            .unwrap();
+        let t5 = inner_product(&l.2, &r.3)?;
+        let t6 = inner_product(&l.3, &r.3)?;
+
+        Some(Poly6(t1, t2, t3, t4, t5, t6))
+    }
+
+    pub fn eval(&self, x: Scalar) -> Vec<Scalar> {
+        let n = self.0.len();
+        let mut result = vec![Scalar::zero(); n];
+        #[allow(clippy::needless_range_loop)]
+        for i in 0..n {
+            // compute `self.0 + x * (self.1 + x * (self.2 + x * self.3))`
+            let value = x.multiply_scalar(self.3[i]).unwrap();
+            let value = value.add_scalar(self.2[i]).unwrap();
+            let value = value.multiply_scalar(x).unwrap();
+            let value = value.add_scalar(self.1[i]).unwrap();
+            let value = value.multiply_scalar(x).unwrap();
+            result[i] = value.add_scalar(self.0[i]).unwrap();
+        }
+        result
+    }
+}
+
+/// Represents a degree-6 scalar polynomial without constant term
+/// `a * x + b * x^2 + c * x^3 + d * x^4 + e * x^5 + f * x^6`.
+pub struct Poly6(
+    pub Scalar,
+    pub Scalar,
+    pub Scalar,
+    pub Scalar,
+    pub Scalar,
+    pub Scalar,
+);
+
+impl Poly6 {
+    pub fn eval(&self, x: Scalar) -> Scalar {
+        // compute `x * (self.0 + x * (self.1 + ... + x * self.5))`
+        let coefficients = [self.0, self.1, self.2, self.3, self.4, self.5];
+        let mut result = Scalar::zero();
+        for coefficient in coefficients.iter().rev() {
+            result = result.add_scalar(*coefficient).unwrap();
+            result = result.multiply_scalar(x).unwrap();
+        }
+        result
+    }
+}
+
 /// Add the sum of two scalar vectors.
 pub fn add_vec(a: &[Scalar], b: &[Scalar]) -> Vec<Scalar> {
     if a.len() != b.len() {
@@ -82,3 +166,48 @@ pub fn inner_product(a: &[Scalar], b: &[Scalar]) -> Option<Scalar> {
     }
     Some(result)
 }
+
+/// Provides an iterator over the powers of a `Scalar`.
+///
+/// This struct is created by the `exp_iter` function.
+pub struct ScalarExp {
+    x: Scalar,
+    next_exp_x: Scalar,
+}
+
+impl Iterator for ScalarExp {
+    type Item = Scalar;
+
+    fn next(&mut self) -> Option<Scalar> {
+        let exp_x = self.next_exp_x;
+        self.next_exp_x = self.next_exp_x.multiply_scalar(self.x).unwrap();
+        Some(exp_x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// Return an iterator of the powers of `x`.
+pub fn exp_iter(x: Scalar) -> ScalarExp {
+    let next_exp_x = Scalar::one();
+    ScalarExp { x, next_exp_x }
+}
+
+/// Compute the sum of the powers `x^0 + x^1 + ... + x^(n-1)`.
+pub fn sum_of_powers(x: &Scalar, n: usize) -> Scalar {
+    let mut result = Scalar::zero();
+    for exp_x in exp_iter(*x).take(n) {
+        result = result.add_scalar(exp_x).unwrap();
+    }
+    result
+}
+
+/// Sample a uniformly distributed scalar.
+#[cfg(not(target_os = "solana"))]
+pub fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R) -> Scalar {
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
@@ -0,0 +1,239 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use {
+    common::blinding,
+    rand_chacha::{rand_core::SeedableRng, ChaChaRng},
+    solana_bulletproofs::{
+        batched_range_proof::{BatchedRangeProofU128, BatchedRangeProofU256, BatchedRangeProofU64},
+        encoding::HEADER_SIZE,
+        generators::{BulletproofGens, PedersenGens},
+        range_proof::RangeProof,
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+fn prove_batched(
+    values: &[u64],
+    bit_lengths: &[usize],
+) -> Result<(RangeProof, Vec<RistrettoPoint>), String> {
+    let bp_gens = BulletproofGens::new(256, 1);
+    let pc_gens = PedersenGens::default();
+    let blindings = values.iter().map(|v| blinding(*v)).collect::<Vec<_>>();
+    RangeProof::prove_batched_with_rng(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"batchedrangeprooftest"),
+        values,
+        &blindings,
+        bit_lengths,
+        &mut ChaChaRng::seed_from_u64(bit_lengths.len() as u64),
+    )
+}
+
+fn verify_batched(
+    proof: &RangeProof,
+    commitments: &[(RistrettoPoint, usize)],
+) -> Result<(), String> {
+    let bp_gens = BulletproofGens::new(256, 1);
+    let pc_gens = PedersenGens::default();
+    proof.verify_batched(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"batchedrangeprooftest"),
+        commitments,
+    )
+}
+
+#[test]
+fn test_prove_and_verify_batched() {
+    let pc_gens = PedersenGens::default();
+    let cases: [(&[u64], &[usize]); 5] = [
+        (&[7], &[4]),
+        (&[255, 65535, u32::MAX as u64, 0], &[8, 16, 32, 8]),
+        (&[u64::MAX, 1 << 40, 12], &[64, 48, 16]),
+        (&[3, 100, 1, 0, 1, 2, 5], &[2, 8, 1, 1, 1, 2, 17]),
+        (&[u64::MAX, 0, 1, 2, 3], &[64, 64, 64, 32, 32]),
+    ];
+
+    for (values, bit_lengths) in cases {
+        let (proof, value_commitments) = prove_batched(values, bit_lengths).unwrap();
+        for (V, v) in value_commitments.iter().zip(values) {
+            assert_eq!(*V, pc_gens.commit(Scalar::from(*v), blinding(*v)).unwrap());
+        }
+
+        let nm = bit_lengths.iter().sum::<usize>();
+        assert_eq!(
+            proof.serialized_size(),
+            HEADER_SIZE + (2 * nm.ilog2() as usize + 9) * 32
+        );
+
+        let commitments = value_commitments
+            .into_iter()
+            .zip(bit_lengths.iter().copied())
+            .collect::<Vec<_>>();
+        verify_batched(&proof, &commitments).unwrap();
+    }
+}
+
+#[test]
+fn test_verify_batched_rejects_wrong_statement() {
+    let (proof, value_commitments) = prove_batched(&[200, 3000, 5], &[8, 16, 8]).unwrap();
+    let with_bit_lengths = |bit_lengths: [usize; 3]| {
+        value_commitments
+            .iter()
+            .copied()
+            .zip(bit_lengths)
+            .collect::<Vec<_>>()
+    };
+
+    assert!(verify_batched(&proof, &with_bit_lengths([8, 16, 8])).is_ok());
+
+    // the same total split differently
+    assert!(verify_batched(&proof, &with_bit_lengths([16, 8, 8])).is_err());
+    assert!(verify_batched(&proof, &with_bit_lengths([8, 8, 16])).is_err());
+
+    // reordered commitments
+    let mut reordered = with_bit_lengths([8, 16, 8]);
+    reordered.swap(0, 2);
+    assert!(verify_batched(&proof, &reordered).is_err());
+
+    // a different total
+    assert!(verify_batched(&proof, &with_bit_lengths([8, 16, 40])).is_err());
+    assert!(verify_batched(&proof, &[]).is_err());
+}
+
+#[test]
+fn test_prove_batched_rejects_invalid_parameters() {
+    // the value does not fit into its own bit length
+    assert!(prove_batched(&[255, 256], &[8, 8]).is_err());
+    assert!(prove_batched(&[256, 255], &[16, 16]).is_ok());
+    // the bit lengths do not sum to a power of two
+    assert!(prove_batched(&[1, 1], &[8, 16]).is_err());
+    // unsupported bit lengths
+    assert!(prove_batched(&[0, 1], &[0, 8]).is_err());
+    assert!(prove_batched(&[1], &[128]).is_err());
+    assert!(prove_batched(&[], &[]).is_err());
+    // mismatched bit lengths
+    assert!(prove_batched(&[1, 2], &[16]).is_err());
+
+    // the generators are too small
+    let bp_gens = BulletproofGens::new(32, 2);
+    assert!(RangeProof::prove_batched(
+        &bp_gens,
+        &PedersenGens::default(),
+        &mut Transcript::new(b"batchedrangeprooftest"),
+        &[1, 2],
+        &[Scalar::one(), Scalar::one()],
+        &[32, 32],
+    )
+    .is_err());
+}
+
+#[test]
+fn test_batched_range_proof_u64() {
+    let bp_gens = BulletproofGens::new(64, 1);
+    let pc_gens = PedersenGens::default();
+    let prove = |bit_lengths: &[usize]| {
+        let values = vec![1; bit_lengths.len()];
+        let blindings = vec![blinding(1); bit_lengths.len()];
+        BatchedRangeProofU64::new(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"batchedrangeprooftest"),
+            &values,
+            &blindings,
+            bit_lengths,
+        )
+    };
+
+    let (proof, value_commitments) = prove(&[32, 16, 8, 8]).unwrap();
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), BatchedRangeProofU64::SIZE);
+    let proof = BatchedRangeProofU64::from_bytes(&bytes).unwrap();
+
+    let commitments = value_commitments
+        .into_iter()
+        .zip([32, 16, 8, 8])
+        .collect::<Vec<_>>();
+    proof
+        .verify(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"batchedrangeprooftest"),
+            &commitments,
+        )
+        .unwrap();
+
+    // the total is fixed, even if it is a power of two
+    assert!(prove(&[16, 16]).is_err());
+    assert!(proof
+        .verify(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"batchedrangeprooftest"),
+            &commitments[..3],
+        )
+        .is_err());
+
+    assert!(BatchedRangeProofU64::from_bytes(&bytes[..bytes.len() - 32]).is_err());
+    assert!(BatchedRangeProofU128::from_bytes(&bytes).is_err());
+}
+
+#[test]
+fn test_batched_range_proof_u128_and_u256() {
+    let bp_gens = BulletproofGens::new(256, 1);
+    let pc_gens = PedersenGens::default();
+    let values = [u64::MAX, u32::MAX as u64, 1000];
+    let blindings = values.iter().map(|v| blinding(*v)).collect::<Vec<_>>();
+
+    let (proof, value_commitments) = BatchedRangeProofU128::new(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"batchedrangeprooftest"),
+        &values,
+        &blindings,
+        &[64, 32, 32],
+    )
+    .unwrap();
+    assert_eq!(proof.to_bytes().len(), BatchedRangeProofU128::SIZE);
+    let commitments = value_commitments
+        .into_iter()
+        .zip([64, 32, 32])
+        .collect::<Vec<_>>();
+    proof
+        .verify(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"batchedrangeprooftest"),
+            &commitments,
+        )
+        .unwrap();
+
+    let values = [values[0], values[1], values[2], 0];
+    let blindings = values.iter().map(|v| blinding(*v)).collect::<Vec<_>>();
+    let (proof, value_commitments) = BatchedRangeProofU256::new(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"batchedrangeprooftest"),
+        &values,
+        &blindings,
+        &[64; 4],
+    )
+    .unwrap();
+    assert_eq!(proof.to_bytes().len(), BatchedRangeProofU256::SIZE);
+    let commitments = value_commitments
+        .into_iter()
+        .zip([64; 4])
+        .collect::<Vec<_>>();
+    proof
+        .verify(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"batchedrangeprooftest"),
+            &commitments,
+        )
+        .unwrap();
+}
@@ -0,0 +1,122 @@
+//! Fixtures that are shared by the integration tests.
+
+// every test crate compiles this module, but not every test crate uses all of it
+#![allow(dead_code, non_snake_case)]
+
+use {
+    rand_chacha::{rand_core::SeedableRng, ChaChaRng},
+    solana_bulletproofs::{
+        generators::{BulletproofGens, PedersenGens},
+        r1cs::{ConstraintSystem, Prover, R1CSProof, R1CSProofView, Variable, Verifier},
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// The number of multipliers that the R1CS helpers have generators for.
+pub const R1CS_CAPACITY: usize = 256;
+
+/// A blinding factor that is derived deterministically from `seed`.
+pub fn blinding(seed: u64) -> Scalar {
+    Transcript::new(&seed.to_le_bytes()).challenge_scalar(b"blinding")
+}
+
+/// Commit to `values` with the blinding factors `blinding(i)`, build the constraints with
+/// `gadget`, and prove them.
+///
+/// Returns the proof and the commitments in the order of `values`.
+pub fn prove<S, F>(values: &[S], gadget: F) -> Result<(R1CSProof, Vec<RistrettoPoint>), String>
+where
+    S: Copy + Into<Scalar>,
+    F: FnOnce(&mut Prover<'_, '_>, &[Variable]) -> Result<(), String>,
+{
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(R1CS_CAPACITY, 1);
+    let mut transcript = Transcript::new(b"r1cstest");
+    let mut prover = Prover::new(&pc_gens, &mut transcript);
+
+    let mut commitments = Vec::with_capacity(values.len());
+    let mut variables = Vec::with_capacity(values.len());
+    for (i, v) in values.iter().enumerate() {
+        let (V, variable) = prover.commit((*v).into(), blinding(i as u64))?;
+        commitments.push(V);
+        variables.push(variable);
+    }
+    gadget(&mut prover, &variables)?;
+    let proof = prover.prove_with_rng(&bp_gens, &mut ChaChaRng::seed_from_u64(0))?;
+
+    Ok((proof, commitments))
+}
+
+/// Add `commitments` to a verifier, build the constraints with `gadget`, and verify `proof`.
+pub fn verify<F>(proof: &R1CSProof, commitments: &[RistrettoPoint], gadget: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Verifier<'_>, &[Variable]) -> Result<(), String>,
+{
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(R1CS_CAPACITY, 1);
+    let mut transcript = Transcript::new(b"r1cstest");
+    let mut verifier = Verifier::new(&mut transcript);
+
+    let variables = commitments
+        .iter()
+        .map(|V| verifier.commit(*V))
+        .collect::<Vec<_>>();
+    gadget(&mut verifier, &variables)?;
+    verifier.verify(proof, &pc_gens, &bp_gens)
+}
+
+/// Verify a proof that borrows its encoding, as `verify` does.
+pub fn verify_view<F>(
+    proof: &R1CSProofView,
+    commitments: &[RistrettoPoint],
+    gadget: F,
+) -> Result<(), String>
+where
+    F: FnOnce(&mut Verifier<'_>, &[Variable]) -> Result<(), String>,
+{
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(R1CS_CAPACITY, 1);
+    let mut transcript = Transcript::new(b"r1cstest");
+    let mut verifier = Verifier::new(&mut transcript);
+
+    let variables = commitments
+        .iter()
+        .map(|V| verifier.commit(*V))
+        .collect::<Vec<_>>();
+    gadget(&mut verifier, &variables)?;
+    verifier.verify_view(proof, &pc_gens, &bp_gens)
+}
+
+/// Prove `values` with the constraints of `prove`, and verify the proof with the constraints of
+/// `verify`.
+///
+/// Returns the numbers of multipliers and of linear constraints, after checking that both sides
+/// built the same numbers.
+pub fn prove_and_verify<S, P, V>(
+    values: &[S],
+    prove: P,
+    verify: V,
+) -> Result<(usize, usize), String>
+where
+    S: Copy + Into<Scalar>,
+    P: FnOnce(&mut Prover<'_, '_>, &[Variable]) -> Result<(), String>,
+    V: FnOnce(&mut Verifier<'_>, &[Variable]) -> Result<(), String>,
+{
+    let mut prover_lens = (0, 0);
+    let (proof, commitments) = self::prove(values, |cs, v| {
+        prove(cs, v)?;
+        prover_lens = (cs.multipliers_len(), cs.constraints_len());
+        Ok(())
+    })?;
+
+    let mut verifier_lens = (0, 0);
+    self::verify(&proof, &commitments, |cs, v| {
+        verify(cs, v)?;
+        verifier_lens = (cs.multipliers_len(), cs.constraints_len());
+        Ok(())
+    })?;
+    assert_eq!(verifier_lens, prover_lens);
+
+    Ok(prover_lens)
+}
@@ -0,0 +1,264 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use {
+    common::blinding,
+    rand_chacha::{rand_core::SeedableRng, ChaChaRng},
+    solana_bulletproofs::{
+        batched_range_proof::{
+            BatchedRangeProofU128, BatchedRangeProofU64, BatchedRangeProofU64View,
+        },
+        encoding::{ProofBytes, ProofType, ENCODING_VERSION, HEADER_SIZE},
+        generators::{BulletproofGens, PedersenGens},
+        inner_product_proof::{InnerProductProof, InnerProductProofView},
+        range_proof::{RangeProof, RangeProofView},
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// A commitment to 200 and a version 1 proof that it opens to an 8-bit value, with the transcript
+/// label `encodingtest`. The vector is frozen: it must verify with every later version.
+const COMMITMENT_V1: &str = "860a534fed11578d68064d79774ab739c1f92a5b65b5d19dea50bb3484f7ac28";
+const RANGE_PROOF_V1: [&str; 16] = [
+    "0102a8a38748c869024bbdce980f9c6a88d3751b8afe2127ee987ed7e502dd70",
+    "1c7094dfb4553e1bbe1c9721480644ca34b61236982cb3ed5578a4ba60d1c113",
+    "534278e6b783059c4434ab1d6ef0b05b18c034fad83a8c466ddf03d6bb56e2da",
+    "0268cc7386134b1956b80b89b3e34699a622afdc703c75ac0ac62145ca6c621d",
+    "4547fd732a9573ca84f4f154f201b698035956954c97f04a8768a8fc93aece4f",
+    "51040572ee26e11287f581a66e873c470278e144f8ebac8b53328e0eea8ff90a",
+    "3c01a9fa7ea7c59a6e2094cf3766232d4576398afe82b260c595bfdef37713bb",
+    "2f039a98a3488bb9f3eb947f42b59684c829d62a1b45158b342d910c7e6c95ec",
+    "5737f83ebe0bd709d367fcbe04be297602284d38352f09e287c27455d4e27d39",
+    "d6362892798a7617c807941d3962a7f325c4a5e5978b4bbbee0fcafefc9b6844",
+    "e27a78f0d6af2782af489b52b213367376a34d344e28f72ce933907751531373",
+    "b713bcd3c514eaa164b81fea6b69fef1f89017d5fc93b374d43e15f47867e2cf",
+    "1214320c1fbdeb11e9ee459755642fecf19db85623e7918b6b464c4f552694c1",
+    "4129f25bc34bbb58d9c2116864f182e3dc75caf8534212717832c5b7b748a30d",
+    "6101d3ddac1b7a8f74bb837bd20379d1224670426240d36acb71792f2baf328a",
+    "3a09",
+];
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn prove(n: usize, v: u64) -> (RangeProof, RistrettoPoint) {
+    RangeProof::prove_single_with_rng(
+        &BulletproofGens::new(64, 1),
+        &PedersenGens::default(),
+        &mut Transcript::new(b"encodingtest"),
+        v,
+        &blinding(v),
+        n,
+        &mut ChaChaRng::seed_from_u64(v),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_frozen_range_proof_verifies() {
+    let bytes = from_hex(&RANGE_PROOF_V1.concat());
+    let V = RistrettoPoint::from_bytes(&from_hex(COMMITMENT_V1)).unwrap();
+
+    let proof_bytes = ProofBytes::parse(&bytes).unwrap();
+    assert_eq!(proof_bytes.version, 1);
+    assert_eq!(proof_bytes.proof_type, ProofType::Range);
+
+    let proof = RangeProof::from_bytes(&bytes).unwrap();
+    proof
+        .verify_single(
+            &BulletproofGens::new(8, 1),
+            &PedersenGens::default(),
+            &mut Transcript::new(b"encodingtest"),
+            &V,
+            8,
+        )
+        .unwrap();
+    assert_eq!(proof.to_bytes(), bytes);
+
+    let view = RangeProofView::from_bytes(&bytes).unwrap();
+    view.verify_single(
+        &BulletproofGens::new(8, 1),
+        &PedersenGens::default(),
+        &mut Transcript::new(b"encodingtest"),
+        &V,
+        8,
+    )
+    .unwrap();
+    assert_eq!(view.to_proof(), proof);
+}
+
+fn inner_product_proof() -> InnerProductProof {
+    let bp_gens = BulletproofGens::new(2, 1);
+    let G = bp_gens.share(0).G(2).copied().collect::<Vec<_>>();
+    let H = bp_gens.share(0).H(2).copied().collect::<Vec<_>>();
+    let ones = [Scalar::one(); 2];
+    InnerProductProof::create(
+        &mut Transcript::new(b"encodingtest"),
+        &PedersenGens::default().B(),
+        &ones,
+        &ones,
+        G,
+        H,
+        vec![blinding(1), blinding(2)],
+        vec![blinding(3), blinding(4)],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_header_is_versioned_and_tagged() {
+    let (proof, _) = prove(16, 300);
+    let bytes = proof.to_bytes();
+    assert_eq!(
+        &bytes[..HEADER_SIZE],
+        &[ENCODING_VERSION, ProofType::Range as u8]
+    );
+    let proof_bytes = ProofBytes::parse(&bytes).unwrap();
+    assert_eq!(proof_bytes.version, ENCODING_VERSION);
+    assert_eq!(proof_bytes.proof_type, ProofType::Range);
+    assert_eq!(proof_bytes.body, &bytes[HEADER_SIZE..]);
+
+    let bytes = inner_product_proof().to_bytes();
+    assert_eq!(
+        ProofBytes::parse(&bytes).unwrap().proof_type,
+        ProofType::InnerProduct
+    );
+    assert_eq!(
+        InnerProductProof::from_bytes(&bytes).unwrap(),
+        inner_product_proof()
+    );
+}
+
+#[test]
+fn test_from_bytes_rejects_wrong_header() {
+    let (proof, _) = prove(8, 1);
+    let bytes = proof.to_bytes();
+
+    for header in [
+        [0, 2],
+        [ENCODING_VERSION + 1, 2],
+        [ENCODING_VERSION, 0],
+        [ENCODING_VERSION, 0xff],
+    ] {
+        let mut modified = bytes.clone();
+        modified[..HEADER_SIZE].copy_from_slice(&header);
+        assert!(ProofBytes::parse(&modified).is_err());
+        assert!(RangeProof::from_bytes(&modified).is_err());
+    }
+
+    // the header is required
+    assert!(RangeProof::from_bytes(&bytes[HEADER_SIZE..]).is_err());
+    assert!(
+        InnerProductProof::from_bytes(&inner_product_proof().to_bytes()[HEADER_SIZE..]).is_err()
+    );
+
+    // the type tag must match the type of the proof
+    assert!(InnerProductProof::from_bytes(&bytes).is_err());
+    assert!(RangeProof::from_bytes(&inner_product_proof().to_bytes()).is_err());
+
+    // the body must consist of 32-byte elements
+    assert!(ProofBytes::parse(&bytes[..1]).is_err());
+    assert!(ProofBytes::parse(&bytes[..bytes.len() - 1]).is_err());
+    assert!(RangeProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn test_views_validate_like_from_bytes() {
+    let (proof, V) = prove(16, 300);
+    let bytes = proof.to_bytes();
+    let view = RangeProofView::from_bytes(&bytes).unwrap();
+    assert_eq!(view.to_proof(), proof);
+    view.verify_single(
+        &BulletproofGens::new(64, 1),
+        &PedersenGens::default(),
+        &mut Transcript::new(b"encodingtest"),
+        &V,
+        16,
+    )
+    .unwrap();
+    assert!(view
+        .verify_single(
+            &BulletproofGens::new(64, 1),
+            &PedersenGens::default(),
+            &mut Transcript::new(b"encodingtest"),
+            &V,
+            8,
+        )
+        .is_err());
+
+    let ipp_bytes = inner_product_proof().to_bytes();
+    let view = InnerProductProofView::from_bytes(&ipp_bytes).unwrap();
+    assert_eq!(view.to_proof(), inner_product_proof());
+
+    // every encoding that `from_bytes` rejects is rejected by the view
+    let mut malformed = vec![
+        bytes[HEADER_SIZE..].to_vec(),
+        bytes[..bytes.len() - 1].to_vec(),
+        bytes[..bytes.len() - 32].to_vec(),
+        ipp_bytes.clone(),
+    ];
+    for offset in [
+        HEADER_SIZE,
+        HEADER_SIZE + 4 * 32,
+        bytes.len() - 96,
+        bytes.len() - 32,
+    ] {
+        let mut modified = bytes.clone();
+        modified[offset..offset + 32].copy_from_slice(&[0xff; 32]);
+        malformed.push(modified);
+    }
+    for malformed in &malformed {
+        assert!(RangeProof::from_bytes(malformed).is_err());
+        assert!(RangeProofView::from_bytes(malformed).is_err());
+    }
+    assert!(InnerProductProofView::from_bytes(&bytes).is_err());
+}
+
+#[test]
+fn test_batched_range_proofs_have_their_own_tag() {
+    let bp_gens = BulletproofGens::new(64, 1);
+    let pc_gens = PedersenGens::default();
+    let (proof, commitments) = BatchedRangeProofU64::new_with_rng(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"encodingtest"),
+        &[1, 2],
+        &[blinding(1), blinding(2)],
+        &[32, 32],
+        &mut ChaChaRng::seed_from_u64(0),
+    )
+    .unwrap();
+    let bytes = proof.to_bytes();
+    assert_eq!(
+        ProofBytes::parse(&bytes).unwrap().proof_type,
+        ProofType::BatchedRangeU64
+    );
+    assert_eq!(BatchedRangeProofU64::from_bytes(&bytes).unwrap(), proof);
+    assert!(BatchedRangeProofU64::from_bytes(&bytes[HEADER_SIZE..]).is_err());
+    let view = BatchedRangeProofU64View::from_bytes(&bytes).unwrap();
+    assert_eq!(view.to_proof(), proof);
+    view.verify(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"encodingtest"),
+        &[(commitments[0], 32), (commitments[1], 32)],
+    )
+    .unwrap();
+    assert!(RangeProofView::from_bytes(&bytes).is_err());
+
+    assert!(RangeProof::from_bytes(&bytes).is_err());
+    assert!(BatchedRangeProofU128::from_bytes(&bytes).is_err());
+    let mut modified = bytes.to_vec();
+    modified[1] = ProofType::BatchedRangeU128 as u8;
+    assert!(BatchedRangeProofU128::from_bytes(&modified).is_err());
+
+    let range_bytes = RangeProof::from(proof).to_bytes();
+    assert!(BatchedRangeProofU64::from_bytes(&range_bytes).is_err());
+    assert_eq!(&range_bytes[HEADER_SIZE..], &bytes[HEADER_SIZE..]);
+}
@@ -0,0 +1,143 @@
+#![allow(non_snake_case)]
+
+use {
+    curve25519_dalek::{
+        constants::{RISTRETTO_BASEPOINT_COMPRESSED, RISTRETTO_BASEPOINT_POINT},
+        ristretto::RistrettoPoint as DalekRistrettoPoint,
+        scalar::Scalar as DalekScalar,
+    },
+    sha3::Sha3_512,
+    solana_bulletproofs::generators::{BulletproofGens, PedersenGens},
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+fn scalar(x: u64) -> Scalar {
+    Scalar::from_bytes(&DalekScalar::from(x).to_bytes()).unwrap()
+}
+
+#[test]
+fn test_default_pedersen_gens_match_dalek() {
+    let pc_gens = PedersenGens::default();
+    let blinding_base =
+        DalekRistrettoPoint::hash_from_bytes::<Sha3_512>(RISTRETTO_BASEPOINT_COMPRESSED.as_bytes());
+
+    assert_eq!(
+        pc_gens.B().to_bytes(),
+        RISTRETTO_BASEPOINT_COMPRESSED.to_bytes()
+    );
+    assert_eq!(
+        pc_gens.B_blinding().to_bytes(),
+        blinding_base.compress().to_bytes()
+    );
+}
+
+#[test]
+fn test_commit_matches_dalek() {
+    let pc_gens = PedersenGens::default();
+    let blinding_base =
+        DalekRistrettoPoint::hash_from_bytes::<Sha3_512>(RISTRETTO_BASEPOINT_COMPRESSED.as_bytes());
+
+    let commitment = pc_gens.commit(scalar(55), scalar(77)).unwrap();
+    let expected = RISTRETTO_BASEPOINT_POINT * DalekScalar::from(55_u64)
+        + blinding_base * DalekScalar::from(77_u64);
+    assert_eq!(commitment.to_bytes(), expected.compress().to_bytes());
+}
+
+#[test]
+fn test_commit_is_additively_homomorphic() {
+    let pc_gens = PedersenGens::from_label(b"test");
+
+    let left = pc_gens.commit(scalar(3), scalar(10)).unwrap();
+    let right = pc_gens.commit(scalar(4), scalar(20)).unwrap();
+    let sum = pc_gens.commit(scalar(7), scalar(30)).unwrap();
+    assert_eq!(left.add(&right).unwrap(), sum);
+}
+
+#[test]
+fn test_label_derived_pedersen_gens() {
+    let pc_gens = PedersenGens::from_label(b"test");
+    assert!(pc_gens.B().is_valid());
+    assert!(pc_gens.B_blinding().is_valid());
+    assert_ne!(pc_gens.B(), pc_gens.B_blinding());
+
+    assert_eq!(pc_gens, PedersenGens::from_label(b"test"));
+    assert_ne!(pc_gens, PedersenGens::from_label(b"other"));
+    assert_ne!(pc_gens, PedersenGens::default());
+}
+
+#[test]
+fn test_custom_pedersen_gens() {
+    let default_gens = PedersenGens::default();
+    let pc_gens = PedersenGens::new(default_gens.B_blinding(), default_gens.B()).unwrap();
+
+    let commitment = pc_gens.commit(scalar(1), scalar(0)).unwrap();
+    assert_eq!(commitment, default_gens.B_blinding());
+}
+
+#[test]
+fn test_custom_pedersen_gens_must_be_valid() {
+    let default_gens = PedersenGens::default();
+    let invalid = RistrettoPoint::from_bytes(&[0xff; 32]).unwrap();
+    assert!(!invalid.is_valid());
+
+    assert_eq!(
+        PedersenGens::new(invalid, default_gens.B_blinding()).unwrap_err(),
+        "Pedersen generators are not valid points"
+    );
+    assert_eq!(
+        PedersenGens::new(default_gens.B(), invalid).unwrap_err(),
+        "Pedersen generators are not valid points"
+    );
+}
+
+#[test]
+fn test_bulletproof_gens_shares_match_aggregated_gens() {
+    let bp_gens = BulletproofGens::new(8, 3);
+
+    let G = bp_gens.G(8, 3).collect::<Vec<_>>();
+    let H = bp_gens.H(8, 3).collect::<Vec<_>>();
+    for j in 0..3 {
+        let share = bp_gens.share(j);
+        assert_eq!(share.G(8).collect::<Vec<_>>(), G[j * 8..(j + 1) * 8]);
+        assert_eq!(share.H(8).collect::<Vec<_>>(), H[j * 8..(j + 1) * 8]);
+    }
+}
+
+#[test]
+fn test_bulletproof_gens_are_distinct_and_valid() {
+    let bp_gens = BulletproofGens::new(8, 2);
+
+    let mut points = bp_gens
+        .G(8, 2)
+        .chain(bp_gens.H(8, 2))
+        .map(|point| point.to_bytes())
+        .collect::<Vec<_>>();
+    assert!(points
+        .iter()
+        .all(|point| RistrettoPoint::from_bytes(point).unwrap().is_valid()));
+
+    points.sort();
+    points.dedup();
+    assert_eq!(points.len(), 32);
+}
+
+#[test]
+fn test_bulletproof_gens_increase_capacity() {
+    let mut bp_gens = BulletproofGens::new(4, 2);
+    bp_gens.increase_capacity(8);
+    assert_eq!(bp_gens, BulletproofGens::new(8, 2));
+
+    // decreasing the capacity is a no-op
+    bp_gens.increase_capacity(2);
+    assert_eq!(bp_gens.gens_capacity, 8);
+}
+
+#[test]
+fn test_bulletproof_gens_prefix_is_independent_of_capacity() {
+    let small = BulletproofGens::new(4, 1);
+    let large = BulletproofGens::new(8, 2);
+    assert_eq!(
+        small.share(0).G(4).collect::<Vec<_>>(),
+        large.share(0).G(4).collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,305 @@
+#![allow(non_snake_case)]
+
+use {
+    solana_bulletproofs::{
+        encoding::HEADER_SIZE, generators::BulletproofGens, inner_product_proof::InnerProductProof,
+        transcript::inner_product_transcript, util,
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// Deterministic pseudorandom scalars squeezed from a transcript.
+fn scalars(label: &[u8], n: usize) -> Vec<Scalar> {
+    let mut transcript = Transcript::new(label);
+    (0..n)
+        .map(|_| transcript.challenge_scalar(b"scalar"))
+        .collect()
+}
+
+/// The powers `1, y^-1, ..., y^-(n-1)` that the range proof uses as `H_factors`.
+fn inverse_powers(y: Scalar, n: usize) -> Vec<Scalar> {
+    let y_inv = y.invert().unwrap();
+    let mut powers = vec![Scalar::one()];
+    for i in 1..n {
+        powers.push(powers[i - 1].multiply_scalar(y_inv).unwrap());
+    }
+    powers
+}
+
+struct Instance {
+    G: Vec<RistrettoPoint>,
+    H: Vec<RistrettoPoint>,
+    Q: RistrettoPoint,
+    G_factors: Vec<Scalar>,
+    H_factors: Vec<Scalar>,
+    P: RistrettoPoint,
+    proof: InnerProductProof,
+}
+
+fn prove(n: usize, uniform_factors: bool) -> Instance {
+    let bp_gens = BulletproofGens::new(n, 1);
+    let G = bp_gens.share(0).G(n).copied().collect::<Vec<_>>();
+    let H = bp_gens.share(0).H(n).copied().collect::<Vec<_>>();
+    let Q = RistrettoPoint::multiscalar_multiply(&scalars(b"Q", 1), &G[..1]).unwrap();
+
+    let a = scalars(b"a", n);
+    let b = scalars(b"b", n);
+    let (G_factors, H_factors) = if uniform_factors {
+        (vec![Scalar::one(); n], vec![Scalar::one(); n])
+    } else {
+        (scalars(b"G_factors", n), inverse_powers(Scalar::from(7), n))
+    };
+
+    // P = <a * G_factors, G> + <b * H_factors, H> + <a, b> * Q
+    let mut P_scalars = Vec::new();
+    for (a_i, g_i) in a.iter().zip(&G_factors) {
+        P_scalars.push(a_i.multiply_scalar(*g_i).unwrap());
+    }
+    for (b_i, h_i) in b.iter().zip(&H_factors) {
+        P_scalars.push(b_i.multiply_scalar(*h_i).unwrap());
+    }
+    P_scalars.push(util::inner_product(&a, &b).unwrap());
+    let P_points = [&G[..], &H[..], &[Q]].concat();
+    let P = RistrettoPoint::multiscalar_multiply(&P_scalars, &P_points).unwrap();
+
+    let proof = InnerProductProof::create(
+        &mut Transcript::new(b"innerproducttest"),
+        &Q,
+        &G_factors,
+        &H_factors,
+        G.clone(),
+        H.clone(),
+        a,
+        b,
+    )
+    .unwrap();
+
+    Instance {
+        G,
+        H,
+        Q,
+        G_factors,
+        H_factors,
+        P,
+        proof,
+    }
+}
+
+fn verify(instance: &Instance, proof: &InnerProductProof, n: usize) -> Result<(), String> {
+    proof.verify(
+        n,
+        &mut Transcript::new(b"innerproducttest"),
+        &instance.G_factors,
+        &instance.H_factors,
+        &instance.P,
+        &instance.Q,
+        &instance.G,
+        &instance.H,
+    )
+}
+
+#[test]
+fn test_create_and_verify() {
+    for n in [1, 2, 4, 8, 16, 32, 64] {
+        for uniform_factors in [true, false] {
+            let instance = prove(n, uniform_factors);
+            assert_eq!(
+                instance.proof.serialized_size(),
+                HEADER_SIZE + (2 * n.ilog2() as usize + 2) * 32
+            );
+            verify(&instance, &instance.proof, n).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_verify_rejects_wrong_statement() {
+    let mut instance = prove(16, false);
+
+    // a different transcript label
+    assert!(instance
+        .proof
+        .verify(
+            16,
+            &mut Transcript::new(b"other"),
+            &instance.G_factors,
+            &instance.H_factors,
+            &instance.P,
+            &instance.Q,
+            &instance.G,
+            &instance.H,
+        )
+        .is_err());
+
+    // a different commitment
+    let P = instance.P;
+    instance.P = P.add(&instance.Q).unwrap();
+    assert!(verify(&instance, &instance.proof, 16).is_err());
+    instance.P = P;
+
+    // different factors
+    instance.H_factors[3] = Scalar::one();
+    assert!(verify(&instance, &instance.proof, 16).is_err());
+}
+
+#[test]
+fn test_verify_rejects_modified_proof() {
+    let instance = prove(16, false);
+    let bytes = instance.proof.to_bytes();
+    let (L_0, L_1) = (HEADER_SIZE, HEADER_SIZE + 64);
+
+    // swap two round commitments
+    let mut modified = bytes.clone();
+    modified.copy_within(L_0..L_0 + 32, L_1);
+    modified[L_0..L_0 + 32].copy_from_slice(&bytes[L_1..L_1 + 32]);
+    let proof = InnerProductProof::from_bytes(&modified).unwrap();
+    assert!(verify(&instance, &proof, 16).is_err());
+
+    // change the final scalar
+    let mut modified = bytes.clone();
+    let b = Scalar::from_bytes(&bytes[bytes.len() - 32..]).unwrap();
+    let b = b.add_scalar(Scalar::one()).unwrap();
+    modified[bytes.len() - 32..].copy_from_slice(&b.to_bytes());
+    let proof = InnerProductProof::from_bytes(&modified).unwrap();
+    assert!(verify(&instance, &proof, 16).is_err());
+
+    // replace a round commitment with the identity
+    let mut modified = bytes;
+    modified[L_0..L_0 + 32].copy_from_slice(&[0; 32]);
+    let proof = InnerProductProof::from_bytes(&modified).unwrap();
+    assert!(verify(&instance, &proof, 16).is_err());
+}
+
+#[test]
+fn test_verify_rejects_wrong_length() {
+    let instance = prove(16, true);
+    assert!(verify(&instance, &instance.proof, 8).is_err());
+    assert!(verify(&instance, &instance.proof, 32).is_err());
+
+    let bytes = instance.proof.to_bytes();
+    let shortened = [
+        &bytes[..HEADER_SIZE],
+        &bytes[HEADER_SIZE + 64..bytes.len() - 64],
+        &bytes[bytes.len() - 64..],
+    ]
+    .concat();
+    let proof = InnerProductProof::from_bytes(&shortened).unwrap();
+    assert!(verify(&instance, &proof, 16).is_err());
+}
+
+#[test]
+fn test_create_rejects_invalid_lengths() {
+    let bp_gens = BulletproofGens::new(8, 1);
+    let G = bp_gens.share(0).G(6).copied().collect::<Vec<_>>();
+    let H = bp_gens.share(0).H(6).copied().collect::<Vec<_>>();
+    let ones = vec![Scalar::one(); 6];
+    assert!(InnerProductProof::create(
+        &mut Transcript::new(b"innerproducttest"),
+        &G[0],
+        &ones,
+        &ones,
+        G.clone(),
+        H.clone(),
+        scalars(b"a", 6),
+        scalars(b"b", 6),
+    )
+    .is_err());
+    assert!(InnerProductProof::create(
+        &mut Transcript::new(b"innerproducttest"),
+        &G[0],
+        &ones[..4],
+        &ones[..4],
+        G[..4].to_vec(),
+        H[..4].to_vec(),
+        scalars(b"a", 4),
+        scalars(b"b", 2),
+    )
+    .is_err());
+}
+
+#[test]
+fn test_bytes_round_trip() {
+    let instance = prove(32, false);
+    let bytes = instance.proof.to_bytes();
+    assert_eq!(bytes.len(), instance.proof.serialized_size());
+    assert_eq!(
+        InnerProductProof::from_bytes(&bytes).unwrap(),
+        instance.proof
+    );
+}
+
+#[test]
+fn test_from_bytes_rejects_malformed_proofs() {
+    let bytes = prove(4, true).proof.to_bytes();
+
+    assert!(InnerProductProof::from_bytes(&[]).is_err());
+    assert!(InnerProductProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(InnerProductProof::from_bytes(&bytes[..bytes.len() - 32]).is_err());
+
+    // an invalid point encoding
+    let mut modified = bytes.clone();
+    modified[HEADER_SIZE..HEADER_SIZE + 32].copy_from_slice(&[0xff; 32]);
+    assert!(InnerProductProof::from_bytes(&modified).is_err());
+
+    // a non-canonical scalar
+    let mut modified = bytes;
+    let len = modified.len();
+    modified[len - 32..].copy_from_slice(&[0xff; 32]);
+    assert!(InnerProductProof::from_bytes(&modified).is_err());
+}
+
+#[test]
+fn test_transcripts_agree_after_proof() {
+    let n = 8;
+    let bp_gens = BulletproofGens::new(n, 1);
+    let G = bp_gens.share(0).G(n).copied().collect::<Vec<_>>();
+    let H = bp_gens.share(0).H(n).copied().collect::<Vec<_>>();
+    let ones = vec![Scalar::one(); n];
+    let a = scalars(b"a", n);
+    let b = scalars(b"b", n);
+    let P_scalars = [&a[..], &b[..], &[util::inner_product(&a, &b).unwrap()]].concat();
+    let P_points = [&G[..], &H[..], &[G[0]]].concat();
+    let P = RistrettoPoint::multiscalar_multiply(&P_scalars, &P_points).unwrap();
+
+    let mut prover_transcript = Transcript::new(b"innerproducttest");
+    let proof = InnerProductProof::create(
+        &mut prover_transcript,
+        &G[0],
+        &ones,
+        &ones,
+        G.clone(),
+        H.clone(),
+        a,
+        b,
+    )
+    .unwrap();
+
+    let mut verifier_transcript = Transcript::new(b"innerproducttest");
+    proof
+        .verify(n, &mut verifier_transcript, &ones, &ones, &P, &G[0], &G, &H)
+        .unwrap();
+
+    // the proof runs the inner product schedule on the caller's transcript
+    let mut expected = Transcript::new(b"innerproducttest");
+    let mut schedule = inner_product_transcript(&mut expected, n as u64);
+    for (L, R) in proof.to_bytes()[HEADER_SIZE..HEADER_SIZE + 6 * 32]
+        .chunks(64)
+        .map(|c| c.split_at(32))
+    {
+        let (next, _) = schedule
+            .append::<solana_bulletproofs::transcript::L>(&RistrettoPoint::from_bytes(L).unwrap())
+            .append::<solana_bulletproofs::transcript::R>(&RistrettoPoint::from_bytes(R).unwrap())
+            .challenge_scalar::<solana_bulletproofs::transcript::U>();
+        schedule = next;
+    }
+
+    let mut prover_challenge = [0; 32];
+    prover_transcript.challenge_bytes(b"next", &mut prover_challenge);
+    let mut verifier_challenge = [0; 32];
+    verifier_transcript.challenge_bytes(b"next", &mut verifier_challenge);
+    let mut expected_challenge = [0; 32];
+    expected.challenge_bytes(b"next", &mut expected_challenge);
+    assert_eq!(prover_challenge, verifier_challenge);
+    assert_eq!(prover_challenge, expected_challenge);
+}
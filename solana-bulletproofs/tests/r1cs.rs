@@ -0,0 +1,318 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use {
+    common::blinding,
+    solana_bulletproofs::{
+        encoding::{ProofBytes, ProofType, HEADER_SIZE},
+        generators::{BulletproofGens, PedersenGens},
+        r1cs::{
+            ConstraintSystem, LinearCombination, Prover, R1CSProof, R1CSProofView,
+            RandomizableConstraintSystem, RandomizedConstraintSystem, Variable, Verifier,
+        },
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::scalar::Scalar,
+};
+
+/// Constrain `(v[0] + v[1]) * (v[2] + v[3]) = c`, where `v` is committed and `c` is public.
+fn example_gadget<CS: ConstraintSystem>(cs: &mut CS, v: &[Variable], c: u64) -> Result<(), String> {
+    let (_, _, o) = cs.multiply(v[0] + v[1], v[2] + v[3])?;
+    cs.constrain(o - c);
+    Ok(())
+}
+
+#[test]
+fn test_example_gadget() {
+    let (proof, commitments) =
+        common::prove(&[3, 4, 6, 1], |cs, v| example_gadget(cs, v, 49)).unwrap();
+    common::verify(&proof, &commitments, |cs, v| example_gadget(cs, v, 49)).unwrap();
+
+    // the proof does not verify for another public value or other commitments
+    assert!(common::verify(&proof, &commitments, |cs, v| example_gadget(cs, v, 48)).is_err());
+    let mut swapped = commitments.clone();
+    swapped.swap(0, 2);
+    assert!(common::verify(&proof, &swapped, |cs, v| example_gadget(cs, v, 49)).is_err());
+}
+
+#[test]
+fn test_prove_unsatisfied_constraints() {
+    assert_eq!(
+        common::prove(&[3, 4, 6, 1], |cs, v| example_gadget(cs, v, 48)).unwrap_err(),
+        "R1CS constraints are not satisfied"
+    );
+}
+
+/// Constrain the allocated variable `x_inv` to be the inverse of the committed value `x`, and the
+/// outputs of an allocated multiplier to be `x` and `2x`.
+fn inverse_gadget<CS: ConstraintSystem>(
+    cs: &mut CS,
+    x: Variable,
+    assignment: Option<Scalar>,
+) -> Result<(), String> {
+    let l = cs.allocate(assignment)?;
+    let r = cs.allocate(assignment.map(|x| x.invert().unwrap()))?;
+    assert_eq!(
+        (l, r),
+        (Variable::MultiplierLeft(0), Variable::MultiplierRight(0))
+    );
+    cs.constrain(l - x);
+    cs.constrain(LinearCombination::from(Variable::MultiplierOutput(0)) - 1u64);
+
+    let (a, b, o) = cs.allocate_multiplier(assignment.map(|x| (x, Scalar::from(2u64))))?;
+    cs.constrain(a - x);
+    cs.constrain(b - 2u64);
+    cs.constrain(o - x * 2u64);
+    assert_eq!(cs.multipliers_len(), 2);
+    assert_eq!(cs.constraints_len(), 5);
+    Ok(())
+}
+
+#[test]
+fn test_allocated_variables() {
+    let x = Scalar::from(5u64);
+    let lens = common::prove_and_verify(
+        &[x],
+        |cs, v| inverse_gadget(cs, v[0], Some(x)),
+        |cs, v| inverse_gadget(cs, v[0], None),
+    )
+    .unwrap();
+    assert_eq!(lens, (2, 5));
+}
+
+#[test]
+fn test_linear_constraints_without_multipliers() {
+    // a + b = 10 with committed a and b
+    let (proof, commitments) = common::prove(&[3, 7], |cs, v| {
+        cs.constrain(v[0] + v[1] - 10u64);
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(proof.serialized_size(), HEADER_SIZE + 13 * 32);
+
+    common::verify(&proof, &commitments, |cs, v| {
+        cs.constrain(v[0] + v[1] - 10u64);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_proof_bytes_round_trip() {
+    let (proof, commitments) =
+        common::prove(&[3, 4, 6, 1], |cs, v| example_gadget(cs, v, 49)).unwrap();
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), proof.serialized_size());
+    // a single multiplier needs no rounds of the inner product argument
+    assert_eq!(bytes.len(), HEADER_SIZE + 13 * 32);
+
+    let decoded = R1CSProof::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, proof);
+    common::verify(&decoded, &commitments, |cs, v| example_gadget(cs, v, 49)).unwrap();
+
+    let view = R1CSProofView::from_bytes(&bytes).unwrap();
+    assert_eq!(view.to_proof(), proof);
+    common::verify_view(&view, &commitments, |cs, v| example_gadget(cs, v, 49)).unwrap();
+    assert!(common::verify_view(&view, &commitments, |cs, v| example_gadget(cs, v, 50)).is_err());
+
+    // proofs without a header, truncated or mistagged proofs are rejected
+    let mut mistagged = bytes.clone();
+    mistagged[1] = 2;
+    for malformed in [
+        &bytes[HEADER_SIZE..],
+        &bytes[..bytes.len() - 32],
+        &mistagged,
+    ] {
+        assert!(R1CSProof::from_bytes(malformed).is_err());
+        assert!(R1CSProofView::from_bytes(malformed).is_err());
+    }
+
+    // as are invalid points and non-canonical scalars
+    for offset in [HEADER_SIZE, HEADER_SIZE + 10 * 32] {
+        let mut modified = bytes.clone();
+        modified[offset..offset + 32].copy_from_slice(&[0xff; 32]);
+        assert!(R1CSProof::from_bytes(&modified).is_err());
+        assert!(R1CSProofView::from_bytes(&modified).is_err());
+    }
+}
+
+#[test]
+fn test_non_canonical_coefficients_are_reduced() {
+    // the group order plus one, which is a non-canonical encoding of one
+    let mut bytes = [0; 32];
+    bytes[..16].copy_from_slice(&[
+        0xee, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde,
+        0x14,
+    ]);
+    bytes[31] = 0x10;
+    let non_canonical_one = Scalar::from_bytes(&bytes).unwrap();
+    assert!(!non_canonical_one.is_canonical());
+
+    let lc = LinearCombination::from(non_canonical_one);
+    assert_eq!(lc, LinearCombination::from(1u64));
+    assert_eq!(-lc.clone(), -LinearCombination::from(1u64));
+    assert_eq!(
+        LinearCombination::from(Variable::One()) * non_canonical_one,
+        LinearCombination::from(Variable::One())
+    );
+    let lc = [(Variable::One(), non_canonical_one)]
+        .into_iter()
+        .collect::<LinearCombination>();
+    assert_eq!(lc.terms(), &[(Variable::One(), Scalar::one())]);
+}
+
+#[test]
+fn test_insufficient_generators() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(1, 1);
+
+    let mut transcript = Transcript::new(b"r1cstest");
+    let mut prover = Prover::new(&pc_gens, &mut transcript);
+    let (_, x_var) = prover.commit(Scalar::from(5u64), blinding(1)).unwrap();
+    inverse_gadget(&mut prover, x_var, Some(Scalar::from(5u64))).unwrap();
+    assert_eq!(
+        prover.prove(&bp_gens).unwrap_err(),
+        "Bulletproof generators have insufficient capacity"
+    );
+}
+
+/// Constrain `v[0] * v[1] = product` in the first phase, and `{v[0], v[1]} = {v[2], v[3]}` as
+/// multisets in the second phase with `(v[0] - c)(v[1] - c) = (v[2] - c)(v[3] - c)` for a
+/// challenge `c`.
+fn shuffle_gadget<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    v: &[Variable],
+    product: u64,
+) -> Result<(), String> {
+    let (x, y) = ([v[0], v[1]], [v[2], v[3]]);
+    let (_, _, o) = cs.multiply(x[0].into(), x[1].into())?;
+    cs.constrain(o - product);
+
+    cs.specify_randomized_constraints(move |cs| {
+        let c = cs.challenge_scalar(b"shuffle challenge");
+        let (_, _, x_product) = cs.multiply(x[0] - c, x[1] - c)?;
+        let (_, _, y_product) = cs.multiply(y[0] - c, y[1] - c)?;
+        cs.constrain(x_product - y_product);
+        Ok(())
+    })
+}
+
+#[test]
+fn test_randomized_constraints() {
+    let (proof, commitments) =
+        common::prove(&[3, 7, 7, 3], |cs, v| shuffle_gadget(cs, v, 21)).unwrap();
+    common::verify(&proof, &commitments, |cs, v| shuffle_gadget(cs, v, 21)).unwrap();
+
+    assert!(common::verify(&proof, &commitments, |cs, v| shuffle_gadget(cs, v, 20)).is_err());
+    let mut swapped = commitments.clone();
+    swapped.swap(0, 2);
+    assert!(common::verify(&proof, &swapped, |cs, v| shuffle_gadget(cs, v, 21)).is_err());
+}
+
+#[test]
+fn test_prove_unsatisfied_randomized_constraints() {
+    assert_eq!(
+        common::prove(&[3, 7, 7, 4], |cs, v| shuffle_gadget(cs, v, 21)).unwrap_err(),
+        "R1CS constraints are not satisfied"
+    );
+}
+
+#[test]
+fn test_randomized_proof_bytes_round_trip() {
+    let (proof, commitments) =
+        common::prove(&[3, 7, 7, 3], |cs, v| shuffle_gadget(cs, v, 21)).unwrap();
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), proof.serialized_size());
+    assert_eq!(bytes.len(), HEADER_SIZE + (2 * 2 + 16) * 32);
+    assert_eq!(
+        ProofBytes::parse(&bytes).unwrap().proof_type,
+        ProofType::R1CSRandomized
+    );
+
+    let decoded = R1CSProof::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, proof);
+    common::verify(&decoded, &commitments, |cs, v| shuffle_gadget(cs, v, 21)).unwrap();
+
+    let view = R1CSProofView::from_bytes(&bytes).unwrap();
+    assert_eq!(view.to_proof(), proof);
+    common::verify_view(&view, &commitments, |cs, v| shuffle_gadget(cs, v, 21)).unwrap();
+
+    assert!(R1CSProof::from_bytes(&bytes[HEADER_SIZE..]).is_err());
+    assert!(R1CSProofView::from_bytes(&bytes[HEADER_SIZE..]).is_err());
+}
+
+#[test]
+fn test_proof_phases_must_match_constraints() {
+    // a verifier without randomized constraints rejects a proof with a second phase
+    let (proof, commitments) =
+        common::prove(&[3, 7, 7, 3], |cs, v| shuffle_gadget(cs, v, 21)).unwrap();
+    assert_eq!(
+        common::verify(&proof, &commitments, |cs, v| {
+            let (_, _, o) = cs.multiply(v[0].into(), v[1].into())?;
+            cs.constrain(o - 21u64);
+            Ok(())
+        })
+        .unwrap_err(),
+        "R1CS proof does not match the randomized constraints"
+    );
+
+    // a verifier with randomized constraints rejects a proof without a second phase
+    let (proof, commitments) =
+        common::prove(&[3, 4, 6, 1], |cs, v| example_gadget(cs, v, 49)).unwrap();
+    assert_eq!(
+        common::verify(&proof, &commitments, |cs, v| {
+            example_gadget(cs, v, 49)?;
+            cs.specify_randomized_constraints(|cs| {
+                cs.challenge_scalar(b"unused");
+                Ok(())
+            })
+        })
+        .unwrap_err(),
+        "R1CS proof does not match the randomized constraints"
+    );
+}
+
+#[test]
+fn test_transcripts_agree_after_proof() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(8, 1);
+
+    // a proof without and a proof with randomized constraints on the same transcript
+    let mut prover_transcript = Transcript::new(b"r1cstest");
+    let mut proofs = Vec::new();
+    for (values, randomized) in [([3, 4, 6, 1], false), ([3, 7, 7, 3], true)] {
+        let mut prover = Prover::new(&pc_gens, &mut prover_transcript);
+        let (commitments, variables): (Vec<_>, Vec<_>) = values
+            .into_iter()
+            .zip(0..)
+            .map(|(v, i)| prover.commit(Scalar::from(v), blinding(i)).unwrap())
+            .unzip();
+        if randomized {
+            shuffle_gadget(&mut prover, &variables, 21).unwrap();
+        } else {
+            example_gadget(&mut prover, &variables, 49).unwrap();
+        }
+        proofs.push((prover.prove(&bp_gens).unwrap(), commitments, randomized));
+    }
+
+    let mut verifier_transcript = Transcript::new(b"r1cstest");
+    for (proof, commitments, randomized) in &proofs {
+        let mut verifier = Verifier::new(&mut verifier_transcript);
+        let variables = commitments
+            .iter()
+            .map(|V| verifier.commit(*V))
+            .collect::<Vec<_>>();
+        if *randomized {
+            shuffle_gadget(&mut verifier, &variables, 21).unwrap();
+        } else {
+            example_gadget(&mut verifier, &variables, 49).unwrap();
+        }
+        verifier.verify(proof, &pc_gens, &bp_gens).unwrap();
+    }
+
+    assert_eq!(
+        prover_transcript.challenge_scalar(b"next"),
+        verifier_transcript.challenge_scalar(b"next")
+    );
+}
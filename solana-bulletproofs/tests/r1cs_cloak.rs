@@ -0,0 +1,241 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use {
+    common::blinding,
+    solana_bulletproofs::{
+        generators::PedersenGens,
+        r1cs::{
+            cloak::{self, AllocatedValue, Value},
+            Prover, RandomizableConstraintSystem, Variable, Verifier,
+        },
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::scalar::Scalar,
+};
+
+fn flavor(asset: &'static [u8]) -> Scalar {
+    Transcript::new(asset).challenge_scalar(b"flavor")
+}
+
+fn value(q: u64, asset: &'static [u8]) -> Value {
+    Value {
+        q,
+        f: flavor(asset),
+    }
+}
+
+/// The quantities and flavors of `values`, in the order in which they are committed.
+fn scalars(values: &[Value]) -> Vec<Scalar> {
+    values
+        .iter()
+        .flat_map(|value| [Scalar::from(value.q), value.f])
+        .collect()
+}
+
+/// The values of the committed quantities and flavors `v`, with the assignments of the prover.
+fn allocated(v: &[Variable], assignments: Option<&[Value]>) -> Vec<AllocatedValue> {
+    v.chunks(2)
+        .enumerate()
+        .map(|(i, v)| AllocatedValue {
+            q: v[0],
+            f: v[1],
+            assignment: assignments.map(|assignments| assignments[i]),
+        })
+        .collect()
+}
+
+/// Constrain the first `inputs_len` of the committed values `v` to be cloaked into the others,
+/// where every value is committed as its quantity and its flavor.
+fn cloak_gadget<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    v: &[Variable],
+    inputs_len: usize,
+    assignments: Option<&[Value]>,
+) -> Result<(), String> {
+    let values = allocated(v, assignments);
+    let (inputs, outputs) = values.split_at(inputs_len);
+    cloak::cloak(cs, inputs, outputs)
+}
+
+fn prove_and_verify_cloak(inputs: &[Value], outputs: &[Value]) -> Result<(usize, usize), String> {
+    let values = [inputs, outputs].concat();
+    common::prove_and_verify(
+        &scalars(&values),
+        |cs, v| cloak_gadget(cs, v, inputs.len(), Some(&values)),
+        |cs, v| cloak_gadget(cs, v, inputs.len(), None),
+    )
+}
+
+#[test]
+fn test_cloak() {
+    // merge, split and move two assets at once
+    prove_and_verify_cloak(
+        &[value(3, b"usd"), value(5, b"eur"), value(4, b"usd")],
+        &[value(2, b"eur"), value(7, b"usd"), value(3, b"eur")],
+    )
+    .unwrap();
+
+    // split one input
+    prove_and_verify_cloak(&[value(10, b"usd")], &[value(4, b"usd"), value(6, b"usd")]).unwrap();
+
+    // merge two inputs
+    prove_and_verify_cloak(&[value(4, b"usd"), value(6, b"usd")], &[value(10, b"usd")]).unwrap();
+
+    // pass values through unchanged
+    prove_and_verify_cloak(
+        &[value(1, b"usd"), value(2, b"eur"), value(3, b"jpy")],
+        &[value(3, b"jpy"), value(1, b"usd"), value(2, b"eur")],
+    )
+    .unwrap();
+
+    // zero quantities
+    prove_and_verify_cloak(
+        &[value(5, b"usd"), value(0, b"usd")],
+        &[value(0, b"usd"), value(5, b"usd"), value(0, b"usd")],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_cloak_requires_conservation_per_flavor() {
+    assert_eq!(
+        prove_and_verify_cloak(&[value(10, b"usd")], &[value(4, b"usd"), value(7, b"usd")])
+            .unwrap_err(),
+        "R1CS constraints are not satisfied"
+    );
+    assert_eq!(
+        prove_and_verify_cloak(&[value(10, b"usd")], &[value(10, b"eur")]).unwrap_err(),
+        "R1CS constraints are not satisfied"
+    );
+    // the total of all flavors is preserved, but not the totals of each flavor
+    assert_eq!(
+        prove_and_verify_cloak(
+            &[value(3, b"usd"), value(5, b"eur")],
+            &[value(4, b"usd"), value(4, b"eur")],
+        )
+        .unwrap_err(),
+        "R1CS constraints are not satisfied"
+    );
+}
+
+#[test]
+fn test_cloak_rejects_other_commitments() {
+    let values = [
+        value(3, b"usd"),
+        value(5, b"eur"),
+        value(5, b"eur"),
+        value(3, b"usd"),
+    ];
+    let (proof, mut commitments) = common::prove(&scalars(&values), |cs, v| {
+        cloak_gadget(cs, v, 2, Some(&values))
+    })
+    .unwrap();
+    common::verify(&proof, &commitments, |cs, v| cloak_gadget(cs, v, 2, None)).unwrap();
+
+    // swap the flavors of the outputs, which keeps the quantities but exchanges the assets
+    commitments.swap(5, 7);
+    assert_eq!(
+        common::verify(&proof, &commitments, |cs, v| cloak_gadget(cs, v, 2, None)).unwrap_err(),
+        "R1CS proof verification failed"
+    );
+}
+
+#[test]
+fn test_value_commitments() {
+    // committing a value commits to its quantity and its flavor, as `scalars` lists them
+    let pc_gens = PedersenGens::default();
+    let values = [value(3, b"usd"), value(5, b"eur")];
+    let mut transcript = Transcript::new(b"r1cstest");
+    let mut prover = Prover::new(&pc_gens, &mut transcript);
+    let committed = values
+        .iter()
+        .zip(0..)
+        .map(|(value, i)| {
+            let (committed, allocated) = value
+                .prover_commit(&mut prover, blinding(2 * i), blinding(2 * i + 1))
+                .unwrap();
+            assert_eq!(allocated.assignment, Some(*value));
+            committed
+        })
+        .collect::<Vec<_>>();
+    let expected = scalars(&values)
+        .into_iter()
+        .zip(0..)
+        .map(|(v, i)| pc_gens.commit(v, blinding(i)).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        committed
+            .iter()
+            .flat_map(|value| [value.q, value.f])
+            .collect::<Vec<_>>(),
+        expected
+    );
+
+    let mut transcript = Transcript::new(b"r1cstest");
+    let mut verifier = Verifier::new(&mut transcript);
+    let allocated = committed
+        .iter()
+        .map(|value| value.verifier_commit(&mut verifier))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        (allocated[1].q, allocated[1].f, allocated[1].assignment),
+        (Variable::Committed(2), Variable::Committed(3), None)
+    );
+}
+
+/// Constrain `mix` of the committed values `v`, where every value is committed as its quantity
+/// and its flavor.
+fn mix_gadget<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    v: &[Variable],
+    assignments: Option<&[Value]>,
+) -> Result<(), String> {
+    let values = allocated(v, assignments);
+    cloak::mix(cs, values[0], values[1], values[2], values[3])
+}
+
+#[test]
+fn test_mix() {
+    let mix = |values: [Value; 4]| {
+        common::prove_and_verify(
+            &scalars(&values),
+            |cs, v| mix_gadget(cs, v, Some(&values)),
+            |cs, v| mix_gadget(cs, v, None),
+        )
+    };
+
+    // pass through
+    mix([
+        value(3, b"usd"),
+        value(5, b"eur"),
+        value(3, b"usd"),
+        value(5, b"eur"),
+    ])
+    .unwrap();
+    // merge, where the flavor of the zero quantity is arbitrary
+    mix([
+        value(3, b"usd"),
+        value(5, b"usd"),
+        value(0, b"eur"),
+        value(8, b"usd"),
+    ])
+    .unwrap();
+    // merge of different flavors
+    assert!(mix([
+        value(3, b"usd"),
+        value(5, b"eur"),
+        value(0, b"usd"),
+        value(8, b"usd"),
+    ])
+    .is_err());
+    // swap
+    assert!(mix([
+        value(3, b"usd"),
+        value(5, b"eur"),
+        value(5, b"eur"),
+        value(3, b"usd"),
+    ])
+    .is_err());
+}
@@ -0,0 +1,394 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use {
+    solana_bulletproofs::{
+        encoding::HEADER_SIZE,
+        r1cs::{gadgets, ConstraintSystem, R1CSProof, RandomizableConstraintSystem, Variable},
+    },
+    solana_ristretto::scalar::Scalar,
+    std::{cell::Cell, rc::Rc},
+};
+
+#[test]
+fn test_allocate_bit() {
+    for bit in [false, true] {
+        let lens = common::prove_and_verify(
+            &[bit as u64],
+            |cs, v| {
+                let b = gadgets::allocate_bit(cs, Some(bit))?;
+                gadgets::equal(cs, b.into(), v[0].into());
+                Ok(())
+            },
+            |cs, v| {
+                let b = gadgets::allocate_bit(cs, None)?;
+                gadgets::equal(cs, b.into(), v[0].into());
+                Ok(())
+            },
+        )
+        .unwrap();
+        // `allocate_bit` and `equal`
+        assert_eq!(lens, (1, 2 + 1));
+    }
+}
+
+#[test]
+fn test_constrain_boolean() {
+    for value in [0, 1] {
+        let lens = common::prove_and_verify(
+            &[value],
+            |cs, v| gadgets::constrain_boolean(cs, v[0].into()),
+            |cs, v| gadgets::constrain_boolean(cs, v[0].into()),
+        )
+        .unwrap();
+        assert_eq!(lens, (1, 3));
+    }
+
+    assert_eq!(
+        common::prove_and_verify(
+            &[2],
+            |cs, v| gadgets::constrain_boolean(cs, v[0].into()),
+            |cs, v| gadgets::constrain_boolean(cs, v[0].into()),
+        )
+        .unwrap_err(),
+        "R1CS constraints are not satisfied"
+    );
+}
+
+/// Constrain the committed booleans `v[0], v[1]` and the results of the boolean gadgets on them
+/// to the committed values `v[2], v[3], v[4]`.
+fn boolean_gadget<CS: ConstraintSystem>(cs: &mut CS, v: &[Variable]) -> Result<(), String> {
+    gadgets::constrain_boolean(cs, v[0].into())?;
+    gadgets::constrain_boolean(cs, v[1].into())?;
+    let and = gadgets::and(cs, v[0].into(), v[1].into())?;
+    let or = gadgets::or(cs, v[0].into(), v[1].into())?;
+    let not = gadgets::not(v[0].into());
+    gadgets::equal(cs, and.into(), v[2].into());
+    gadgets::equal(cs, or, v[3].into());
+    gadgets::equal(cs, not, v[4].into());
+    Ok(())
+}
+
+#[test]
+fn test_boolean_operations() {
+    for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+        let values = [a, b, a && b, a || b, !a].map(|bit| bit as u64);
+        let lens = common::prove_and_verify(
+            &values,
+            |cs, v| boolean_gadget(cs, v),
+            |cs, v| boolean_gadget(cs, v),
+        )
+        .unwrap();
+        // two `constrain_boolean`, `and`, `or`, `not` and three `equal`
+        assert_eq!(lens, (2 + 1 + 1, 2 * 3 + 2 + 2 + 3));
+    }
+
+    assert!(common::prove_and_verify(
+        &[1, 1, 1, 0, 0],
+        |cs, v| boolean_gadget(cs, v),
+        |cs, v| boolean_gadget(cs, v)
+    )
+    .is_err());
+}
+
+#[test]
+fn test_bits() {
+    let value = 0b1100_1010;
+    let lens = common::prove_and_verify(
+        &[value],
+        |cs, v| {
+            let bits = gadgets::bits(cs, v[0].into(), Some(value), 8)?;
+            // the least significant bit is 0
+            gadgets::equal(cs, bits[0].into(), 0u64.into());
+            gadgets::equal(cs, bits[1].into(), 1u64.into());
+            Ok(())
+        },
+        |cs, v| {
+            let bits = gadgets::bits(cs, v[0].into(), None, 8)?;
+            gadgets::equal(cs, bits[0].into(), 0u64.into());
+            gadgets::equal(cs, bits[1].into(), 1u64.into());
+            Ok(())
+        },
+    )
+    .unwrap();
+    // `bits` and two `equal`
+    assert_eq!(lens, (8, 2 * 8 + 1 + 2));
+}
+
+#[test]
+fn test_range() {
+    for (value, n) in [(0, 8), (255, 8), (u64::MAX, 64)] {
+        let lens = common::prove_and_verify(
+            &[value],
+            |cs, v| gadgets::range(cs, v[0].into(), Some(value), n),
+            |cs, v| gadgets::range(cs, v[0].into(), None, n),
+        )
+        .unwrap();
+        assert_eq!(lens, (n, 2 * n + 1));
+    }
+
+    assert_eq!(
+        common::prove_and_verify(
+            &[256],
+            |cs, v| gadgets::range(cs, v[0].into(), Some(256), 8),
+            |cs, v| gadgets::range(cs, v[0].into(), None, 8),
+        )
+        .unwrap_err(),
+        "R1CS constraints are not satisfied"
+    );
+
+    assert_eq!(
+        common::prove_and_verify(
+            &[0],
+            |cs, v| gadgets::range(cs, v[0].into(), Some(0), 65),
+            |cs, v| gadgets::range(cs, v[0].into(), None, 65),
+        )
+        .unwrap_err(),
+        "Bit length must be at most 64"
+    );
+}
+
+fn prove_less_than(left: u64, right: u64) -> Result<(usize, usize), String> {
+    common::prove_and_verify(
+        &[left, right],
+        |cs, v| gadgets::less_than(cs, v[0].into(), v[1].into(), Some((left, right)), 16),
+        |cs, v| gadgets::less_than(cs, v[0].into(), v[1].into(), None, 16),
+    )
+}
+
+fn prove_greater_than(left: u64, right: u64) -> Result<(usize, usize), String> {
+    common::prove_and_verify(
+        &[left, right],
+        |cs, v| gadgets::greater_than(cs, v[0].into(), v[1].into(), Some((left, right)), 16),
+        |cs, v| gadgets::greater_than(cs, v[0].into(), v[1].into(), None, 16),
+    )
+}
+
+#[test]
+fn test_comparisons() {
+    assert_eq!(prove_less_than(3, 5).unwrap(), (16, 2 * 16 + 1));
+    assert_eq!(prove_less_than(0, 65535).unwrap(), (16, 2 * 16 + 1));
+    assert!(prove_less_than(5, 5).is_err());
+    assert!(prove_less_than(5, 3).is_err());
+
+    assert_eq!(prove_greater_than(5, 3).unwrap(), (16, 2 * 16 + 1));
+    assert!(prove_greater_than(5, 5).is_err());
+    assert!(prove_greater_than(3, 5).is_err());
+}
+
+fn prove_equal(left: u64, right: u64) -> Result<(usize, usize), String> {
+    common::prove_and_verify(
+        &[left, right],
+        |cs, v| {
+            gadgets::equal(cs, v[0].into(), v[1].into());
+            Ok(())
+        },
+        |cs, v| {
+            gadgets::equal(cs, v[0].into(), v[1].into());
+            Ok(())
+        },
+    )
+}
+
+fn prove_not_equal(left: u64, right: u64) -> Result<(usize, usize), String> {
+    let difference = Scalar::from(left)
+        .subtract_scalar(Scalar::from(right))
+        .unwrap();
+    common::prove_and_verify(
+        &[left, right],
+        |cs, v| gadgets::not_equal(cs, v[0].into(), v[1].into(), Some(difference)),
+        |cs, v| gadgets::not_equal(cs, v[0].into(), v[1].into(), None),
+    )
+}
+
+#[test]
+fn test_equality() {
+    assert_eq!(prove_equal(7, 7).unwrap(), (0, 1));
+    assert!(prove_equal(7, 8).is_err());
+
+    assert_eq!(prove_not_equal(7, 8).unwrap(), (1, 2));
+    assert_eq!(prove_not_equal(8, 7).unwrap(), (1, 2));
+    assert_eq!(
+        prove_not_equal(7, 7).unwrap_err(),
+        "Zero scalar has no inverse"
+    );
+}
+
+/// Constrain `select(v[0], v[1], v[2]) = v[3]`.
+fn select_gadget<CS: ConstraintSystem>(cs: &mut CS, v: &[Variable]) -> Result<(), String> {
+    gadgets::constrain_boolean(cs, v[0].into())?;
+    let selected = gadgets::select(cs, v[0].into(), v[1].into(), v[2].into())?;
+    gadgets::equal(cs, selected, v[3].into());
+    Ok(())
+}
+
+#[test]
+fn test_select() {
+    // `constrain_boolean`, `select` and `equal`
+    let lens = (1 + 1, 3 + 2 + 1);
+    assert_eq!(
+        common::prove_and_verify(
+            &[1, 10, 20, 10],
+            |cs, v| select_gadget(cs, v),
+            |cs, v| select_gadget(cs, v)
+        )
+        .unwrap(),
+        lens
+    );
+    assert_eq!(
+        common::prove_and_verify(
+            &[0, 10, 20, 20],
+            |cs, v| select_gadget(cs, v),
+            |cs, v| select_gadget(cs, v)
+        )
+        .unwrap(),
+        lens
+    );
+    assert!(common::prove_and_verify(
+        &[1, 10, 20, 20],
+        |cs, v| select_gadget(cs, v),
+        |cs, v| select_gadget(cs, v)
+    )
+    .is_err());
+}
+
+/// Constrain `v[0] + v[1] + v[2] = v[3]`.
+fn sum_gadget<CS: ConstraintSystem>(cs: &mut CS, v: &[Variable]) -> Result<(), String> {
+    let sum = gadgets::sum(v[..3].iter().map(|v| (*v).into()));
+    gadgets::equal(cs, sum, v[3].into());
+    Ok(())
+}
+
+#[test]
+fn test_sum() {
+    assert_eq!(
+        common::prove_and_verify(
+            &[1, 2, 3, 6],
+            |cs, v| sum_gadget(cs, v),
+            |cs, v| sum_gadget(cs, v)
+        )
+        .unwrap(),
+        // `sum` and `equal`
+        (0, 1)
+    );
+    assert!(common::prove_and_verify(
+        &[1, 2, 3, 7],
+        |cs, v| sum_gadget(cs, v),
+        |cs, v| sum_gadget(cs, v)
+    )
+    .is_err());
+}
+
+/// Shuffle the committed tuples `v[..k]` to `v[k..]`, or the committed values if the tuples have
+/// width 1.
+fn shuffle_gadget<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    v: &[Variable],
+    width: usize,
+) -> Result<(), String> {
+    let (inputs, outputs) = v.split_at(v.len() / 2);
+    if width == 1 {
+        gadgets::shuffle(cs, inputs, outputs)
+    } else {
+        let inputs = inputs.chunks(width).map(<[_]>::to_vec).collect::<Vec<_>>();
+        let outputs = outputs.chunks(width).map(<[_]>::to_vec).collect::<Vec<_>>();
+        gadgets::tuple_shuffle(cs, &inputs, &outputs)
+    }
+}
+
+/// Run `shuffle_gadget`, and record the numbers of multipliers and of linear constraints in
+/// `lens` once its randomized constraints are built.
+fn counted_shuffle_gadget<CS: RandomizableConstraintSystem>(
+    cs: &mut CS,
+    v: &[Variable],
+    width: usize,
+    lens: Rc<Cell<(usize, usize)>>,
+) -> Result<(), String> {
+    shuffle_gadget(cs, v, width)?;
+    // the randomized constraints are built in the order they are specified, so this callback
+    // runs after those of the shuffle
+    cs.specify_randomized_constraints(move |cs| {
+        lens.set((cs.multipliers_len(), cs.constraints_len()));
+        Ok(())
+    })
+}
+
+/// Prove and verify a shuffle of `inputs` to `outputs`.
+///
+/// Returns the proof, and the numbers of multipliers and of linear constraints after checking that
+/// both sides built the same numbers.
+fn prove_shuffle(
+    inputs: &[Vec<u64>],
+    outputs: &[Vec<u64>],
+) -> Result<(R1CSProof, (usize, usize)), String> {
+    let width = inputs.first().map_or(1, Vec::len);
+    let values = inputs
+        .iter()
+        .chain(outputs)
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+
+    let prover_lens = Rc::new(Cell::new((0, 0)));
+    let (proof, commitments) = common::prove(&values, |cs, v| {
+        counted_shuffle_gadget(cs, v, width, prover_lens.clone())
+    })?;
+    let verifier_lens = Rc::new(Cell::new((0, 0)));
+    common::verify(&proof, &commitments, |cs, v| {
+        counted_shuffle_gadget(cs, v, width, verifier_lens.clone())
+    })?;
+    assert_eq!(verifier_lens.get(), prover_lens.get());
+
+    Ok((proof, prover_lens.get()))
+}
+
+/// The size of a randomized R1CS proof with `n` multipliers, a power of two.
+fn randomized_proof_size(n: usize) -> usize {
+    HEADER_SIZE + (2 * n.trailing_zeros() as usize + 16) * 32
+}
+
+#[test]
+fn test_shuffle() {
+    let inputs = [3, 1, 4, 1, 5, 9, 2, 6].map(|v| vec![v]);
+    let outputs = [1, 2, 3, 4, 5, 6, 9, 1].map(|v| vec![v]);
+    let (proof, lens) = prove_shuffle(&inputs, &outputs).unwrap();
+    assert_eq!(lens, (2 * (8 - 1), 4 * (8 - 1) + 1));
+    // the multipliers are rounded up to 16
+    assert_eq!(proof.serialized_size(), randomized_proof_size(16));
+
+    let outputs = [1, 2, 3, 4, 5, 6, 9, 9].map(|v| vec![v]);
+    assert_eq!(
+        prove_shuffle(&inputs, &outputs).unwrap_err(),
+        "R1CS constraints are not satisfied"
+    );
+    assert_eq!(
+        prove_shuffle(&inputs, &outputs[1..]).unwrap_err(),
+        "Shuffle inputs and outputs have different lengths"
+    );
+
+    // shuffles of a single value need no challenges
+    assert_eq!(prove_shuffle(&[vec![7]], &[vec![7]]).unwrap().1, (0, 1));
+    assert!(prove_shuffle(&[vec![7]], &[vec![8]]).is_err());
+}
+
+#[test]
+fn test_tuple_shuffle() {
+    let inputs = [vec![10, 1], vec![20, 2], vec![30, 1]];
+    let outputs = [vec![30, 1], vec![10, 1], vec![20, 2]];
+    let (proof, lens) = prove_shuffle(&inputs, &outputs).unwrap();
+    assert_eq!(lens, (2 * (3 - 1), 4 * (3 - 1) + 1));
+    assert_eq!(proof.serialized_size(), randomized_proof_size(4));
+
+    // the entries of the tuples must move together
+    let outputs = [vec![30, 2], vec![10, 1], vec![20, 1]];
+    assert_eq!(
+        prove_shuffle(&inputs, &outputs).unwrap_err(),
+        "R1CS constraints are not satisfied"
+    );
+    let outputs = [vec![30, 1], vec![10, 1], vec![20]];
+    assert_eq!(
+        prove_shuffle(&inputs, &outputs).unwrap_err(),
+        "Shuffle tuples have different widths"
+    );
+}
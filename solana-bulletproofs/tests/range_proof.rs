@@ -0,0 +1,581 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use {
+    common::blinding,
+    rand_chacha::{rand_core::SeedableRng, ChaChaRng},
+    solana_bulletproofs::{
+        encoding::HEADER_SIZE,
+        generators::{BulletproofGens, PedersenGens},
+        range_proof::{interval_bit_size, RangeProof},
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+fn prove(n: usize, v: u64) -> (RangeProof, RistrettoPoint) {
+    let bp_gens = BulletproofGens::new(64, 1);
+    let pc_gens = PedersenGens::default();
+    RangeProof::prove_single_with_rng(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"rangeprooftest"),
+        v,
+        &blinding(v),
+        n,
+        &mut ChaChaRng::seed_from_u64(n as u64),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_prove_and_verify_single() {
+    let bp_gens = BulletproofGens::new(64, 1);
+    let pc_gens = PedersenGens::default();
+
+    for n in [8, 16, 32, 64] {
+        let max = if n == 64 { u64::MAX } else { (1 << n) - 1 };
+        for v in [0, 1, max / 3, max] {
+            let (proof, V) = prove(n, v);
+            assert_eq!(V, pc_gens.commit(Scalar::from(v), blinding(v)).unwrap());
+            assert_eq!(
+                proof.serialized_size(),
+                HEADER_SIZE + (2 * n.ilog2() as usize + 9) * 32
+            );
+            proof
+                .verify_single(
+                    &bp_gens,
+                    &pc_gens,
+                    &mut Transcript::new(b"rangeprooftest"),
+                    &V,
+                    n,
+                )
+                .unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_prove_single_with_os_rng() {
+    let bp_gens = BulletproofGens::new(32, 1);
+    let pc_gens = PedersenGens::default();
+
+    let (proof, V) = RangeProof::prove_single(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"rangeprooftest"),
+        1 << 31,
+        &blinding(7),
+        32,
+    )
+    .unwrap();
+    proof
+        .verify_single(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"rangeprooftest"),
+            &V,
+            32,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_prove_single_rejects_invalid_parameters() {
+    let bp_gens = BulletproofGens::new(16, 1);
+    let pc_gens = PedersenGens::default();
+    let prove = |v: u64, n: usize| {
+        RangeProof::prove_single(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"rangeprooftest"),
+            v,
+            &blinding(v),
+            n,
+        )
+    };
+
+    // the value does not fit into n bits
+    assert!(prove(256, 8).is_err());
+    assert!(prove(1 << 16, 16).is_err());
+    // unsupported bit size
+    assert!(prove(1, 4).is_err());
+    // the generators are too small
+    assert!(prove(1, 32).is_err());
+}
+
+#[test]
+fn test_verify_single_rejects_wrong_statement() {
+    let bp_gens = BulletproofGens::new(64, 1);
+    let pc_gens = PedersenGens::default();
+    let (proof, V) = prove(32, 1000);
+    let verify = |label: &[u8], V, n| {
+        proof.verify_single(&bp_gens, &pc_gens, &mut Transcript::new(label), V, n)
+    };
+
+    assert!(verify(b"rangeprooftest", &V, 32).is_ok());
+    assert!(verify(b"other", &V, 32).is_err());
+    assert!(verify(b"rangeprooftest", &V, 16).is_err());
+    assert!(verify(b"rangeprooftest", &V, 64).is_err());
+
+    let other_V = pc_gens.commit(Scalar::from(1001), blinding(1000)).unwrap();
+    assert!(verify(b"rangeprooftest", &other_V, 32).is_err());
+}
+
+#[test]
+fn test_verify_single_rejects_modified_proof() {
+    let bp_gens = BulletproofGens::new(64, 1);
+    let pc_gens = PedersenGens::default();
+    let (proof, V) = prove(64, 12345);
+    let bytes = proof.to_bytes();
+
+    // modify t_x, the blinding factors, and the inner product scalars
+    for offset in [
+        HEADER_SIZE + 4 * 32,
+        HEADER_SIZE + 5 * 32,
+        HEADER_SIZE + 6 * 32,
+        bytes.len() - 64,
+        bytes.len() - 32,
+    ] {
+        let mut modified = bytes.clone();
+        let scalar = Scalar::from_bytes(&bytes[offset..offset + 32]).unwrap();
+        let scalar = scalar.add_scalar(Scalar::one()).unwrap();
+        modified[offset..offset + 32].copy_from_slice(&scalar.to_bytes());
+
+        let proof = RangeProof::from_bytes(&modified).unwrap();
+        assert!(proof
+            .verify_single(
+                &bp_gens,
+                &pc_gens,
+                &mut Transcript::new(b"rangeprooftest"),
+                &V,
+                64,
+            )
+            .is_err());
+    }
+
+    // swap A and S
+    let (A, S) = (HEADER_SIZE, HEADER_SIZE + 32);
+    let mut modified = bytes.clone();
+    modified[A..A + 32].copy_from_slice(&bytes[S..S + 32]);
+    modified[S..S + 32].copy_from_slice(&bytes[A..A + 32]);
+    let proof = RangeProof::from_bytes(&modified).unwrap();
+    assert!(proof
+        .verify_single(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"rangeprooftest"),
+            &V,
+            64,
+        )
+        .is_err());
+}
+
+#[test]
+fn test_bytes_round_trip() {
+    let (proof, _) = prove(16, 300);
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), proof.serialized_size());
+    assert_eq!(RangeProof::from_bytes(&bytes).unwrap(), proof);
+
+    assert!(RangeProof::from_bytes(&bytes[..bytes.len() - 32]).is_err());
+    assert!(RangeProof::from_bytes(&bytes[..HEADER_SIZE + 6 * 32]).is_err());
+
+    let mut modified = bytes.clone();
+    modified[HEADER_SIZE..HEADER_SIZE + 32].copy_from_slice(&[0xff; 32]);
+    assert!(RangeProof::from_bytes(&modified).is_err());
+
+    let mut modified = bytes;
+    modified[HEADER_SIZE + 4 * 32..HEADER_SIZE + 5 * 32].copy_from_slice(&[0xff; 32]);
+    assert!(RangeProof::from_bytes(&modified).is_err());
+}
+
+fn prove_multiple(n: usize, values: &[u64]) -> (RangeProof, Vec<RistrettoPoint>) {
+    let bp_gens = BulletproofGens::new(64, 8);
+    let pc_gens = PedersenGens::default();
+    let blindings = values.iter().map(|v| blinding(*v)).collect::<Vec<_>>();
+    RangeProof::prove_multiple_with_rng(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"rangeprooftest"),
+        values,
+        &blindings,
+        n,
+        &mut ChaChaRng::seed_from_u64(values.len() as u64),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_prove_and_verify_multiple() {
+    let bp_gens = BulletproofGens::new(64, 8);
+    let pc_gens = PedersenGens::default();
+
+    for n in [8, 32, 64] {
+        for m in 1..=8 {
+            let values = (0..m as u64).map(|j| (j * 37) % 256).collect::<Vec<_>>();
+            let (proof, value_commitments) = prove_multiple(n, &values);
+            assert_eq!(value_commitments.len(), m);
+            for (V, v) in value_commitments.iter().zip(&values) {
+                assert_eq!(*V, pc_gens.commit(Scalar::from(*v), blinding(*v)).unwrap());
+            }
+
+            // the proof size only depends on n times m rounded up to a power of two
+            let nm = n * m.next_power_of_two();
+            assert_eq!(
+                proof.serialized_size(),
+                HEADER_SIZE + (2 * nm.ilog2() as usize + 9) * 32
+            );
+
+            proof
+                .verify_multiple(
+                    &bp_gens,
+                    &pc_gens,
+                    &mut Transcript::new(b"rangeprooftest"),
+                    &value_commitments,
+                    n,
+                )
+                .unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_single_and_multiple_agree() {
+    let bp_gens = BulletproofGens::new(32, 1);
+    let pc_gens = PedersenGens::default();
+
+    let (proof, value_commitments) = prove_multiple(32, &[99]);
+    proof
+        .verify_single(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"rangeprooftest"),
+            &value_commitments[0],
+            32,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_verify_multiple_rejects_wrong_commitments() {
+    let bp_gens = BulletproofGens::new(64, 8);
+    let pc_gens = PedersenGens::default();
+    let (proof, value_commitments) = prove_multiple(16, &[1, 2, 3]);
+    let verify = |value_commitments: &[_]| {
+        proof.verify_multiple(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"rangeprooftest"),
+            value_commitments,
+            16,
+        )
+    };
+
+    assert!(verify(&value_commitments).is_ok());
+
+    // reordered commitments
+    let reordered = [
+        value_commitments[1],
+        value_commitments[0],
+        value_commitments[2],
+    ];
+    assert!(verify(&reordered).is_err());
+
+    // a missing commitment, which is padded with a different commitment
+    assert!(verify(&value_commitments[..2]).is_err());
+
+    // an explicit padding commitment is the same as implicit padding
+    let identity = RistrettoPoint::from_bytes(&[0; 32]).unwrap();
+    let padded = [&value_commitments[..], &[identity]].concat();
+    assert!(verify(&padded).is_ok());
+
+    // no commitments
+    assert!(verify(&[]).is_err());
+}
+
+#[test]
+fn test_prove_multiple_rejects_invalid_parameters() {
+    let bp_gens = BulletproofGens::new(64, 2);
+    let pc_gens = PedersenGens::default();
+    let prove = |values: &[u64], num_blindings: usize, n: usize| {
+        RangeProof::prove_multiple(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"rangeprooftest"),
+            values,
+            &vec![Scalar::one(); num_blindings],
+            n,
+        )
+    };
+
+    assert!(prove(&[1, 2], 2, 8).is_ok());
+    // mismatched blinding factors
+    assert!(prove(&[1, 2], 1, 8).is_err());
+    // no values
+    assert!(prove(&[], 0, 8).is_err());
+    // a value that does not fit into n bits
+    assert!(prove(&[1, 256], 2, 8).is_err());
+    // more parties than the generators support
+    assert!(prove(&[1, 2, 3], 3, 8).is_err());
+}
+
+#[test]
+fn test_batch_verify() {
+    let bp_gens = BulletproofGens::new(64, 8);
+    let pc_gens = PedersenGens::default();
+    let batch = [&[7][..], &[1, 2], &[3, 4, 5], &[6; 8], &[255]]
+        .iter()
+        .map(|values| prove_multiple(16, values))
+        .collect::<Vec<_>>();
+    let verify = |batch: &[(RangeProof, Vec<RistrettoPoint>)]| {
+        let mut transcripts = vec![Transcript::new(b"rangeprooftest"); batch.len()];
+        let mut items = batch
+            .iter()
+            .zip(&mut transcripts)
+            .map(|((proof, value_commitments), transcript)| {
+                (proof, &value_commitments[..], transcript)
+            })
+            .collect::<Vec<_>>();
+        RangeProof::batch_verify(&bp_gens, &pc_gens, &mut items, 16)
+    };
+
+    assert!(verify(&batch).is_ok());
+    assert!(verify(&batch[..1]).is_ok());
+    assert!(verify(&[]).is_ok());
+
+    // the error names the first proof that fails
+    let mut invalid = batch.clone();
+    invalid[2].1.swap(0, 1);
+    assert_eq!(
+        verify(&invalid).unwrap_err(),
+        "Range proof 2 failed verification"
+    );
+
+    let mut invalid = batch.clone();
+    invalid[0].0 = batch[4].0.clone();
+    invalid[4].0 = batch[0].0.clone();
+    assert_eq!(
+        verify(&invalid).unwrap_err(),
+        "Range proof 0 failed verification"
+    );
+
+    // a proof for different parameters
+    let mut invalid = batch;
+    invalid[1] = prove_multiple(8, &[1, 2]);
+    assert!(verify(&invalid).is_err());
+}
+
+#[test]
+fn test_batch_verify_transcript_state() {
+    let bp_gens = BulletproofGens::new(64, 8);
+    let pc_gens = PedersenGens::default();
+    let batch = [&[7][..], &[1, 2, 3]]
+        .iter()
+        .map(|values| prove_multiple(16, values))
+        .collect::<Vec<_>>();
+
+    let mut transcripts = vec![Transcript::new(b"rangeprooftest"); batch.len()];
+    let mut items = batch
+        .iter()
+        .zip(&mut transcripts)
+        .map(|((proof, value_commitments), transcript)| (proof, &value_commitments[..], transcript))
+        .collect::<Vec<_>>();
+    RangeProof::batch_verify(&bp_gens, &pc_gens, &mut items, 16).unwrap();
+
+    // every transcript ends in the same state as after verifying its proof on its own
+    for ((proof, value_commitments), transcript) in batch.iter().zip(&mut transcripts) {
+        let mut expected = Transcript::new(b"rangeprooftest");
+        proof
+            .verify_multiple(&bp_gens, &pc_gens, &mut expected, value_commitments, 16)
+            .unwrap();
+        assert_eq!(
+            transcript.challenge_scalar(b"next"),
+            expected.challenge_scalar(b"next")
+        );
+    }
+}
+
+#[test]
+fn test_transcripts_agree_after_proof() {
+    let bp_gens = BulletproofGens::new(64, 2);
+    let pc_gens = PedersenGens::default();
+    let mut rng = ChaChaRng::seed_from_u64(0);
+
+    // a single, an aggregated, a batched and an interval proof on the same transcript
+    let mut prover_transcript = Transcript::new(b"rangeprooftest");
+    let (single, V) = RangeProof::prove_single_with_rng(
+        &bp_gens,
+        &pc_gens,
+        &mut prover_transcript,
+        7,
+        &blinding(7),
+        8,
+        &mut rng,
+    )
+    .unwrap();
+    let (multiple, multiple_commitments) = RangeProof::prove_multiple_with_rng(
+        &bp_gens,
+        &pc_gens,
+        &mut prover_transcript,
+        &[1, 2],
+        &[blinding(1), blinding(2)],
+        16,
+        &mut rng,
+    )
+    .unwrap();
+    let (batched, batched_commitments) = RangeProof::prove_batched_with_rng(
+        &bp_gens,
+        &pc_gens,
+        &mut prover_transcript,
+        &[3, 4],
+        &[blinding(3), blinding(4)],
+        &[8, 8],
+        &mut rng,
+    )
+    .unwrap();
+    let (interval, interval_commitment) = RangeProof::prove_interval_with_rng(
+        &bp_gens,
+        &pc_gens,
+        &mut prover_transcript,
+        150,
+        &blinding(150),
+        100,
+        200,
+        &mut rng,
+    )
+    .unwrap();
+
+    let mut verifier_transcript = Transcript::new(b"rangeprooftest");
+    single
+        .verify_single(&bp_gens, &pc_gens, &mut verifier_transcript, &V, 8)
+        .unwrap();
+    multiple
+        .verify_multiple(
+            &bp_gens,
+            &pc_gens,
+            &mut verifier_transcript,
+            &multiple_commitments,
+            16,
+        )
+        .unwrap();
+    batched
+        .verify_batched(
+            &bp_gens,
+            &pc_gens,
+            &mut verifier_transcript,
+            &[(batched_commitments[0], 8), (batched_commitments[1], 8)],
+        )
+        .unwrap();
+    interval
+        .verify_interval(
+            &bp_gens,
+            &pc_gens,
+            &mut verifier_transcript,
+            &interval_commitment,
+            100,
+            200,
+        )
+        .unwrap();
+
+    assert_eq!(
+        prover_transcript.challenge_scalar(b"next"),
+        verifier_transcript.challenge_scalar(b"next")
+    );
+}
+
+fn prove_interval(v: u64, a: u64, b: u64) -> Result<(RangeProof, RistrettoPoint), String> {
+    let bp_gens = BulletproofGens::new(64, 2);
+    let pc_gens = PedersenGens::default();
+    RangeProof::prove_interval_with_rng(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"rangeprooftest"),
+        v,
+        &blinding(v),
+        a,
+        b,
+        &mut ChaChaRng::seed_from_u64(a ^ b),
+    )
+}
+
+fn verify_interval(proof: &RangeProof, V: &RistrettoPoint, a: u64, b: u64) -> Result<(), String> {
+    let bp_gens = BulletproofGens::new(64, 2);
+    let pc_gens = PedersenGens::default();
+    proof.verify_interval(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"rangeprooftest"),
+        V,
+        a,
+        b,
+    )
+}
+
+#[test]
+fn test_interval_bit_size() {
+    assert_eq!(interval_bit_size(0, 1), Ok(8));
+    assert_eq!(interval_bit_size(1000, 1256), Ok(8));
+    assert_eq!(interval_bit_size(1000, 1257), Ok(16));
+    assert_eq!(interval_bit_size(0, 1 << 32), Ok(32));
+    assert_eq!(interval_bit_size(0, u64::MAX), Ok(64));
+    assert!(interval_bit_size(5, 5).is_err());
+    assert!(interval_bit_size(6, 5).is_err());
+}
+
+#[test]
+fn test_prove_and_verify_interval() {
+    let pc_gens = PedersenGens::default();
+    let intervals = [
+        (0, 1),
+        (100, 1000),
+        (1 << 20, (1 << 20) + 65536),
+        (7, 1 << 40),
+        (1, u64::MAX),
+    ];
+
+    for (a, b) in intervals {
+        for v in [a, a + (b - a) / 2, b - 1] {
+            let (proof, V) = prove_interval(v, a, b).unwrap();
+            assert_eq!(V, pc_gens.commit(Scalar::from(v), blinding(v)).unwrap());
+            verify_interval(&proof, &V, a, b).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_prove_interval_rejects_values_outside() {
+    assert!(prove_interval(99, 100, 1000).is_err());
+    assert!(prove_interval(1000, 100, 1000).is_err());
+    assert!(prove_interval(u64::MAX, 0, u64::MAX).is_err());
+    assert!(prove_interval(5, 5, 5).is_err());
+}
+
+#[test]
+fn test_verify_interval_rejects_wrong_interval() {
+    let pc_gens = PedersenGens::default();
+    let (proof, V) = prove_interval(500, 100, 1000).unwrap();
+
+    assert!(verify_interval(&proof, &V, 100, 1000).is_ok());
+    // a subinterval, a superinterval, and a shifted interval that contain the value
+    assert!(verify_interval(&proof, &V, 200, 1000).is_err());
+    assert!(verify_interval(&proof, &V, 0, 1000).is_err());
+    assert!(verify_interval(&proof, &V, 101, 1001).is_err());
+    assert!(verify_interval(&proof, &V, 1000, 100).is_err());
+
+    let other_V = pc_gens.commit(Scalar::from(501), blinding(500)).unwrap();
+    assert!(verify_interval(&proof, &other_V, 100, 1000).is_err());
+
+    // the interval proof is not a plain range proof of the commitment
+    let bp_gens = BulletproofGens::new(64, 2);
+    assert!(proof
+        .verify_single(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"rangeprooftest"),
+            &V,
+            16,
+        )
+        .is_err());
+}
@@ -0,0 +1,277 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use {
+    common::blinding,
+    rand_chacha::{rand_core::SeedableRng, ChaChaRng},
+    solana_bulletproofs::{
+        errors::MPCError,
+        generators::{BulletproofGens, PedersenGens},
+        range_proof::{
+            dealer::{Dealer, DealerAwaitingProofShares},
+            messages::{BitChallenge, BitCommitment, PolyChallenge, PolyCommitment, ProofShare},
+            party::{Party, PartyAwaitingPolyChallenge},
+            RangeProof,
+        },
+    },
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+/// Run the protocol up to the proof shares, sending every message as bytes as through an
+/// in-memory channel.
+fn run_until_shares<'a, 'b>(
+    bp_gens: &'b BulletproofGens,
+    pc_gens: &'b PedersenGens,
+    transcript: &'a mut Transcript,
+    values: &[u64],
+    n: usize,
+) -> (
+    DealerAwaitingProofShares<'a, 'b>,
+    Vec<ProofShare>,
+    Vec<RistrettoPoint>,
+) {
+    let mut rng = ChaChaRng::seed_from_u64(values.len() as u64);
+    let dealer = Dealer::new(bp_gens, pc_gens, transcript, n, values.len()).unwrap();
+
+    let (parties, bit_commitments): (Vec<_>, Vec<_>) = values
+        .iter()
+        .enumerate()
+        .map(|(j, v)| {
+            let party = Party::new(bp_gens, pc_gens, *v, blinding(*v), n).unwrap();
+            let (party, bit_commitment) = party.assign_position_with_rng(j, &mut rng).unwrap();
+            (party, bit_commitment.to_bytes())
+        })
+        .unzip();
+    let bit_commitments = bit_commitments
+        .iter()
+        .map(|bytes| BitCommitment::from_bytes(bytes).unwrap())
+        .collect::<Vec<_>>();
+    let value_commitments = bit_commitments
+        .iter()
+        .map(BitCommitment::value_commitment)
+        .collect();
+
+    let (dealer, bit_challenge) = dealer.receive_bit_commitments(bit_commitments).unwrap();
+    let bit_challenge = BitChallenge::from_bytes(&bit_challenge.to_bytes()).unwrap();
+
+    let (parties, poly_commitments): (Vec<_>, Vec<_>) = parties
+        .into_iter()
+        .map(|party| {
+            let (party, poly_commitment) = party
+                .apply_challenge_with_rng(&bit_challenge, &mut rng)
+                .unwrap();
+            (party, poly_commitment.to_bytes())
+        })
+        .unzip();
+    let poly_commitments = poly_commitments
+        .iter()
+        .map(|bytes| PolyCommitment::from_bytes(bytes).unwrap())
+        .collect();
+
+    let (dealer, poly_challenge) = dealer.receive_poly_commitments(poly_commitments).unwrap();
+    let poly_challenge = PolyChallenge::from_bytes(&poly_challenge.to_bytes()).unwrap();
+
+    let proof_shares = parties
+        .into_iter()
+        .map(|party: PartyAwaitingPolyChallenge| {
+            let bytes = party.apply_challenge(&poly_challenge).unwrap().to_bytes();
+            ProofShare::from_bytes(&bytes).unwrap()
+        })
+        .collect();
+
+    (dealer, proof_shares, value_commitments)
+}
+
+#[test]
+fn test_mpc_proof_verifies() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(64, 8);
+
+    for n in [8, 64] {
+        for m in [1, 2, 4, 8] {
+            let values = (0..m as u64).map(|j| j * 31 % 256).collect::<Vec<_>>();
+            let mut transcript = Transcript::new(b"mpctest");
+            let (dealer, proof_shares, value_commitments) =
+                run_until_shares(&bp_gens, &pc_gens, &mut transcript, &values, n);
+            let proof = dealer.receive_shares(&proof_shares).unwrap();
+
+            for (V, v) in value_commitments.iter().zip(&values) {
+                assert_eq!(*V, pc_gens.commit(Scalar::from(*v), blinding(*v)).unwrap());
+            }
+            proof
+                .verify_multiple(
+                    &bp_gens,
+                    &pc_gens,
+                    &mut Transcript::new(b"mpctest"),
+                    &value_commitments,
+                    n,
+                )
+                .unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_mpc_trusted_shares() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(32, 2);
+    let mut transcript = Transcript::new(b"mpctest");
+    let (dealer, proof_shares, value_commitments) =
+        run_until_shares(&bp_gens, &pc_gens, &mut transcript, &[5, 6], 32);
+    let proof = dealer.receive_trusted_shares(&proof_shares).unwrap();
+
+    let bytes = proof.to_bytes();
+    RangeProof::from_bytes(&bytes)
+        .unwrap()
+        .verify_multiple(
+            &bp_gens,
+            &pc_gens,
+            &mut Transcript::new(b"mpctest"),
+            &value_commitments,
+            32,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_mpc_blames_malicious_parties() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(16, 4);
+
+    // parties 1 and 3 modify t_x and an entry of l(x) of their shares
+    let mut transcript = Transcript::new(b"mpctest");
+    let (dealer, mut proof_shares, _) =
+        run_until_shares(&bp_gens, &pc_gens, &mut transcript, &[1, 2, 3, 4], 16);
+    for (j, offset) in [(1, 0), (3, 5 * 32)] {
+        let mut bytes = proof_shares[j].to_bytes();
+        let scalar = Scalar::from_bytes(&bytes[offset..offset + 32]).unwrap();
+        let scalar = scalar.add_scalar(Scalar::one()).unwrap();
+        bytes[offset..offset + 32].copy_from_slice(&scalar.to_bytes());
+        proof_shares[j] = ProofShare::from_bytes(&bytes).unwrap();
+    }
+    assert_eq!(
+        dealer.receive_shares(&proof_shares),
+        Err(MPCError::MalformedProofShares {
+            bad_shares: vec![1, 3]
+        })
+    );
+
+    // party 2 sends a share of the wrong length
+    let mut transcript = Transcript::new(b"mpctest");
+    let (dealer, mut proof_shares, _) =
+        run_until_shares(&bp_gens, &pc_gens, &mut transcript, &[1, 2, 3, 4], 16);
+    let bytes = proof_shares[2].to_bytes();
+    proof_shares[2] = ProofShare::from_bytes(&bytes[..bytes.len() - 64]).unwrap();
+    assert_eq!(
+        dealer.receive_shares(&proof_shares),
+        Err(MPCError::MalformedProofShares {
+            bad_shares: vec![2]
+        })
+    );
+}
+
+#[test]
+fn test_mpc_rejects_wrong_number_of_messages() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(8, 2);
+    let party = |j: usize| {
+        Party::new(&bp_gens, &pc_gens, 1, blinding(1), 8)
+            .unwrap()
+            .assign_position(j)
+            .unwrap()
+    };
+
+    let mut transcript = Transcript::new(b"mpctest");
+    let dealer = Dealer::new(&bp_gens, &pc_gens, &mut transcript, 8, 2).unwrap();
+    let (_, bit_commitment) = party(0);
+    assert_eq!(
+        dealer.receive_bit_commitments(vec![bit_commitment]).err(),
+        Some(MPCError::WrongNumBitCommitments)
+    );
+
+    let mut transcript = Transcript::new(b"mpctest");
+    let (dealer, proof_shares, _) =
+        run_until_shares(&bp_gens, &pc_gens, &mut transcript, &[1, 2], 8);
+    assert_eq!(
+        dealer.receive_shares(&proof_shares[..1]),
+        Err(MPCError::WrongNumProofShares)
+    );
+}
+
+#[test]
+fn test_mpc_rejects_invalid_parameters() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(16, 4);
+    let mut transcript = Transcript::new(b"mpctest");
+
+    assert!(matches!(
+        Dealer::new(&bp_gens, &pc_gens, &mut transcript, 16, 3),
+        Err(MPCError::InvalidAggregation)
+    ));
+    assert!(matches!(
+        Dealer::new(&bp_gens, &pc_gens, &mut transcript, 16, 8),
+        Err(MPCError::InvalidAggregation)
+    ));
+    assert!(matches!(
+        Dealer::new(&bp_gens, &pc_gens, &mut transcript, 32, 2),
+        Err(MPCError::InvalidBitsize)
+    ));
+
+    assert!(matches!(
+        Party::new(&bp_gens, &pc_gens, 1, blinding(1), 12),
+        Err(MPCError::InvalidBitsize)
+    ));
+    assert!(matches!(
+        Party::new(&bp_gens, &pc_gens, 256, blinding(1), 8),
+        Err(MPCError::ValueOutOfRange)
+    ));
+    assert!(matches!(
+        Party::new(&bp_gens, &pc_gens, 1, blinding(1), 8)
+            .unwrap()
+            .assign_position(4),
+        Err(MPCError::InvalidAggregation)
+    ));
+}
+
+#[test]
+fn test_party_rejects_zero_challenges() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(8, 1);
+    let new_party = || {
+        Party::new(&bp_gens, &pc_gens, 1, blinding(1), 8)
+            .unwrap()
+            .assign_position(0)
+            .unwrap()
+            .0
+    };
+
+    let zero_challenge = BitChallenge::from_bytes(&[0; 64]).unwrap();
+    assert!(matches!(
+        new_party().apply_challenge(&zero_challenge),
+        Err(MPCError::MaliciousDealer)
+    ));
+
+    let challenge_bytes = [&Scalar::one().to_bytes()[..], &Scalar::one().to_bytes()].concat();
+    let bit_challenge = BitChallenge::from_bytes(&challenge_bytes).unwrap();
+    let (party, _) = new_party().apply_challenge(&bit_challenge).unwrap();
+    let zero_challenge = PolyChallenge::from_bytes(&[0; 32]).unwrap();
+    assert_eq!(
+        party.apply_challenge(&zero_challenge),
+        Err(MPCError::MaliciousDealer)
+    );
+}
+
+#[test]
+fn test_messages_reject_malformed_bytes() {
+    assert!(BitCommitment::from_bytes(&[0; 95]).is_err());
+    assert!(BitCommitment::from_bytes(&[0xff; 96]).is_err());
+    assert!(BitChallenge::from_bytes(&[0; 32]).is_err());
+    assert!(BitChallenge::from_bytes(&[0xff; 64]).is_err());
+    assert!(PolyCommitment::from_bytes(&[0; 65]).is_err());
+    assert!(PolyChallenge::from_bytes(&[0xff; 32]).is_err());
+    assert!(ProofShare::from_bytes(&[0; 64]).is_err());
+    assert!(ProofShare::from_bytes(&[0; 4 * 32]).is_err());
+    assert!(ProofShare::from_bytes(&[0; 5 * 32]).is_ok());
+}
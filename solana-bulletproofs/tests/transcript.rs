@@ -0,0 +1,183 @@
+use {
+    solana_bulletproofs::transcript::*,
+    solana_merlin::Transcript,
+    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
+};
+
+fn point(byte: u8) -> RistrettoPoint {
+    RistrettoPoint::from_bytes(&[byte; 32]).unwrap()
+}
+
+fn scalar(byte: u8) -> Scalar {
+    Scalar::from_bytes(&[byte; 32]).unwrap()
+}
+
+#[test]
+fn test_range_proof_schedule_matches_transcript() {
+    let mut expected = Transcript::new(b"test");
+    expected.append_message(b"dom-sep", b"rangeproof v1");
+    expected.append_u64(b"n", 8);
+    expected.append_u64(b"m", 2);
+    expected.append_point(b"V", &point(1));
+    expected.append_point(b"V", &point(2));
+    expected.append_point(b"A", &point(3));
+    expected.append_point(b"S", &point(4));
+    let mut expected_y = [0; 32];
+    expected.challenge_bytes(b"y", &mut expected_y);
+    let mut expected_z = [0; 32];
+    expected.challenge_bytes(b"z", &mut expected_z);
+    expected.append_point(b"T_1", &point(5));
+    expected.append_point(b"T_2", &point(6));
+    let mut expected_x = [0; 32];
+    expected.challenge_bytes(b"x", &mut expected_x);
+    expected.append_scalar(b"t_x", &scalar(7));
+    expected.append_scalar(b"t_x_blinding", &scalar(8));
+    expected.append_scalar(b"e_blinding", &scalar(9));
+    let mut expected_w = [0; 32];
+    expected.challenge_bytes(b"w", &mut expected_w);
+    expected.append_message(b"dom-sep", b"ipp v1");
+    expected.append_u64(b"n", 16);
+    expected.append_point(b"L", &point(10));
+    expected.append_point(b"R", &point(11));
+    let mut expected_u = [0; 32];
+    expected.challenge_bytes(b"u", &mut expected_u);
+
+    let transcript = range_proof_transcript(Transcript::new(b"test"), 8, 2)
+        .append::<V>(&point(1))
+        .append::<V>(&point(2))
+        .append::<A>(&point(3))
+        .append::<S>(&point(4));
+    let (mut y, mut z) = ([0; 32], [0; 32]);
+    let transcript = transcript
+        .challenge_bytes::<Y>(&mut y)
+        .challenge_bytes::<Z>(&mut z)
+        .append::<T1>(&point(5))
+        .append::<T2>(&point(6));
+    let mut x = [0; 32];
+    let transcript = transcript
+        .challenge_bytes::<X>(&mut x)
+        .append::<Tx>(&scalar(7))
+        .append::<TxBlinding>(&scalar(8))
+        .append::<EBlinding>(&scalar(9));
+    let mut w = [0; 32];
+    let transcript = range_proof_inner_product(transcript.challenge_bytes::<W>(&mut w), 16)
+        .append::<L>(&point(10))
+        .append::<R>(&point(11));
+    let mut u = [0; 32];
+    let transcript = transcript.challenge_bytes::<U>(&mut u);
+
+    assert_eq!(
+        (y, z, x, w, u),
+        (expected_y, expected_z, expected_x, expected_w, expected_u)
+    );
+    assert_eq!(transcript.into_transcript().finalize(), expected.finalize());
+}
+
+#[test]
+fn test_r1cs_schedule_matches_transcript() {
+    let mut expected = Transcript::new(b"test");
+    expected.append_message(b"dom-sep", b"r1cs v1");
+    expected.append_u64(b"m", 1);
+    expected.append_point(b"V", &point(1));
+    expected.append_point(b"A_I1", &point(2));
+    expected.append_point(b"A_O1", &point(3));
+    expected.append_point(b"S1", &point(4));
+    let mut expected_y = [0; 32];
+    expected.challenge_bytes(b"y", &mut expected_y);
+    let mut expected_z = [0; 32];
+    expected.challenge_bytes(b"z", &mut expected_z);
+    for (label, byte) in [
+        (b"T_1", 5),
+        (b"T_3", 6),
+        (b"T_4", 7),
+        (b"T_5", 8),
+        (b"T_6", 9),
+    ] {
+        expected.append_point(label, &point(byte));
+    }
+    let mut expected_x = [0; 32];
+    expected.challenge_bytes(b"x", &mut expected_x);
+    expected.append_scalar(b"t_x", &scalar(10));
+    expected.append_scalar(b"t_x_blinding", &scalar(11));
+    expected.append_scalar(b"e_blinding", &scalar(12));
+    let mut expected_w = [0; 32];
+    expected.challenge_bytes(b"w", &mut expected_w);
+    expected.append_message(b"dom-sep", b"ipp v1");
+    expected.append_u64(b"n", 2);
+    expected.append_point(b"L", &point(13));
+    expected.append_point(b"R", &point(14));
+    let mut expected_u = [0; 32];
+    expected.challenge_bytes(b"u", &mut expected_u);
+
+    let transcript = r1cs_transcript(Transcript::new(b"test"), 1)
+        .append::<V>(&point(1))
+        .append::<AI1>(&point(2))
+        .append::<AO1>(&point(3))
+        .append::<S1>(&point(4));
+    let (mut y, mut z) = ([0; 32], [0; 32]);
+    let transcript = transcript
+        .challenge_bytes::<Y>(&mut y)
+        .challenge_bytes::<Z>(&mut z)
+        .append::<T1>(&point(5))
+        .append::<T3>(&point(6))
+        .append::<T4>(&point(7))
+        .append::<T5>(&point(8))
+        .append::<T6>(&point(9));
+    let mut x = [0; 32];
+    let transcript = transcript
+        .challenge_bytes::<X>(&mut x)
+        .append::<Tx>(&scalar(10))
+        .append::<TxBlinding>(&scalar(11))
+        .append::<EBlinding>(&scalar(12));
+    let mut w = [0; 32];
+    let transcript = range_proof_inner_product(transcript.challenge_bytes::<W>(&mut w), 2)
+        .append::<L>(&point(13))
+        .append::<R>(&point(14));
+    let mut u = [0; 32];
+    let transcript = transcript.challenge_bytes::<U>(&mut u);
+
+    assert_eq!(
+        (y, z, x, w, u),
+        (expected_y, expected_z, expected_x, expected_w, expected_u)
+    );
+    assert_eq!(transcript.into_transcript().finalize(), expected.finalize());
+}
+
+#[test]
+fn test_r1cs_second_phase_schedule_matches_transcript() {
+    let mut expected = Transcript::new(b"test");
+    expected.append_message(b"dom-sep", b"r1cs v1");
+    expected.append_u64(b"m", 0);
+    expected.append_point(b"A_I1", &point(1));
+    expected.append_point(b"A_O1", &point(2));
+    expected.append_point(b"S1", &point(3));
+    expected.append_message(b"dom-sep", b"r1cs randomized v1");
+    let mut expected_c = [0; 32];
+    expected.challenge_bytes(b"gadget", &mut expected_c);
+    expected.append_point(b"A_I2", &point(4));
+    expected.append_point(b"A_O2", &point(5));
+    expected.append_point(b"S2", &point(6));
+    let mut expected_u = [0; 32];
+    expected.challenge_bytes(b"u", &mut expected_u);
+    let mut expected_y = [0; 32];
+    expected.challenge_bytes(b"y", &mut expected_y);
+
+    let transcript = r1cs_transcript(Transcript::new(b"test"), 0)
+        .append::<AI1>(&point(1))
+        .append::<AO1>(&point(2))
+        .append::<S1>(&point(3));
+    let mut transcript = r1cs_randomized_constraints(transcript);
+    let mut c = [0; 32];
+    transcript.challenge_bytes(b"gadget", &mut c);
+    let transcript = r1cs_second_phase(transcript)
+        .append::<AI2>(&point(4))
+        .append::<AO2>(&point(5))
+        .append::<S2>(&point(6));
+    let (mut u, mut y) = ([0; 32], [0; 32]);
+    let transcript = transcript
+        .challenge_bytes::<U>(&mut u)
+        .challenge_bytes::<Y>(&mut y);
+
+    assert_eq!((c, u, y), (expected_c, expected_u, expected_y));
+    assert_eq!(transcript.into_transcript().finalize(), expected.finalize());
+}
@@ -0,0 +1,137 @@
+use {
+    proc_macro::TokenStream,
+    proc_macro2::{Span, TokenStream as TokenStream2},
+    quote::quote,
+    syn::{
+        parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Error, LitByteStr,
+        LitStr, Result, WherePredicate,
+    },
+};
+
+/// Derive `solana_merlin::AppendToTranscript` for a struct.
+///
+/// Every field is appended to the transcript in declaration order through its own
+/// `AppendToTranscript` implementation. The label of a field defaults to its name and can be
+/// overridden with `#[transcript(label = "...")]`. Fields marked with `#[transcript(skip)]` are not
+/// appended.
+///
+/// The label that the implementation is called with is appended first under `struct`, which is
+/// distinct from the `dom-sep` label of `Transcript::new`, so a struct cannot be mistaken for a
+/// new transcript.
+#[proc_macro_derive(AppendToTranscript, attributes(transcript))]
+pub fn derive_append_to_transcript(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The parsed `#[transcript(...)]` attributes of a field.
+#[derive(Default)]
+struct FieldAttributes {
+    label: Option<LitByteStr>,
+    skip: bool,
+}
+
+fn expand(mut input: DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "AppendToTranscript can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut appends = Vec::new();
+    let mut bounds: Vec<WherePredicate> = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let attributes = parse_field_attributes(field)?;
+        if attributes.skip {
+            continue;
+        }
+
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(index);
+                quote!(#index)
+            }
+        };
+        let label = attributes.label.unwrap_or_else(|| {
+            let name = match &field.ident {
+                Some(ident) => ident.to_string(),
+                None => index.to_string(),
+            };
+            LitByteStr::new(name.as_bytes(), field.span())
+        });
+
+        appends.push(quote! {
+            ::solana_merlin::AppendToTranscript::append_to_transcript(
+                &self.#member,
+                #label,
+                transcript,
+            );
+        });
+
+        let ty = &field.ty;
+        bounds.push(parse_quote!(#ty: ::solana_merlin::AppendToTranscript));
+    }
+
+    // require every appended field type to implement the trait so that generic structs work
+    if !bounds.is_empty() {
+        let where_clause = input.generics.make_where_clause();
+        where_clause.predicates.extend(bounds);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::solana_merlin::AppendToTranscript for #name #ty_generics #where_clause {
+            fn append_to_transcript(
+                &self,
+                label: &[u8],
+                transcript: &mut ::solana_merlin::Transcript,
+            ) {
+                transcript.append_message(b"struct", label);
+                #(#appends)*
+            }
+        }
+    })
+}
+
+fn parse_field_attributes(field: &syn::Field) -> Result<FieldAttributes> {
+    let mut attributes = FieldAttributes::default();
+    for attr in &field.attrs {
+        if !attr.path().is_ident("transcript") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                attributes.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("label") {
+                let value = meta.value()?;
+                let label = if value.peek(LitByteStr) {
+                    value.parse::<LitByteStr>()?
+                } else {
+                    let label = value.parse::<LitStr>()?;
+                    LitByteStr::new(label.value().as_bytes(), label.span())
+                };
+                attributes.label = Some(label);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported transcript attribute"))
+            }
+        })?;
+    }
+
+    if attributes.skip && attributes.label.is_some() {
+        return Err(Error::new(
+            field.span(),
+            "a skipped field cannot have a transcript label",
+        ));
+    }
+    Ok(attributes)
+}
@@ -0,0 +1,107 @@
+use {
+    solana_merlin::{AppendToTranscript, Transcript},
+    solana_ristretto::ristretto::RistrettoPoint,
+};
+
+#[derive(AppendToTranscript)]
+struct Inner {
+    #[transcript(label = "n")]
+    bit_length: u64,
+    tag: [u8; 4],
+}
+
+#[derive(AppendToTranscript)]
+struct Outer {
+    #[transcript(label = b"V")]
+    commitment: RistrettoPoint,
+    inner: Inner,
+    #[transcript(skip)]
+    #[allow(dead_code)]
+    cached: Option<String>,
+    data: Vec<u8>,
+}
+
+#[derive(AppendToTranscript)]
+struct Tuple(#[transcript(label = "x")] u64, u64);
+
+#[derive(AppendToTranscript)]
+struct Generic<T> {
+    value: T,
+}
+
+fn challenge(transcript: &mut Transcript) -> [u8; 32] {
+    let mut challenge = [0; 32];
+    transcript.challenge_bytes(b"challenge", &mut challenge);
+    challenge
+}
+
+fn sample_point() -> RistrettoPoint {
+    RistrettoPoint::from_bytes(&[
+        208, 165, 125, 204, 2, 100, 218, 17, 170, 194, 23, 9, 102, 156, 134, 136, 217, 190, 98, 34,
+        183, 194, 228, 153, 92, 11, 108, 103, 28, 57, 88, 15,
+    ])
+    .unwrap()
+}
+
+#[test]
+fn test_derived_appends_match_manual_appends() {
+    let outer = Outer {
+        commitment: sample_point(),
+        inner: Inner {
+            bit_length: 64,
+            tag: *b"test",
+        },
+        cached: Some("not appended".to_string()),
+        data: b"data".to_vec(),
+    };
+
+    let mut derived = Transcript::new(b"derive test");
+    outer.append_to_transcript(b"statement", &mut derived);
+
+    let mut manual = Transcript::new(b"derive test");
+    manual.append_message(b"struct", b"statement");
+    manual.append_point(b"V", &outer.commitment);
+    manual.append_message(b"struct", b"inner");
+    manual.append_u64(b"n", 64);
+    manual.append_message(b"tag", b"test");
+    manual.append_message(b"data", b"data");
+
+    assert_eq!(challenge(&mut derived), challenge(&mut manual));
+}
+
+#[test]
+fn test_skipped_fields_are_not_appended() {
+    let outer = |cached: Option<String>| Outer {
+        commitment: sample_point(),
+        inner: Inner {
+            bit_length: 64,
+            tag: *b"test",
+        },
+        cached,
+        data: vec![],
+    };
+
+    let mut first = Transcript::new(b"derive test");
+    outer(None).append_to_transcript(b"statement", &mut first);
+
+    let mut second = Transcript::new(b"derive test");
+    outer(Some("cached".to_string())).append_to_transcript(b"statement", &mut second);
+
+    assert_eq!(challenge(&mut first), challenge(&mut second));
+}
+
+#[test]
+fn test_tuple_and_generic_structs() {
+    let mut derived = Transcript::new(b"derive test");
+    Tuple(1, 2).append_to_transcript(b"tuple", &mut derived);
+    Generic { value: 3_u64 }.append_to_transcript(b"generic", &mut derived);
+
+    let mut manual = Transcript::new(b"derive test");
+    manual.append_message(b"struct", b"tuple");
+    manual.append_u64(b"x", 1);
+    manual.append_u64(b"1", 2);
+    manual.append_message(b"struct", b"generic");
+    manual.append_u64(b"value", 3);
+
+    assert_eq!(challenge(&mut derived), challenge(&mut manual));
+}
@@ -0,0 +1,108 @@
+//! Print the known-answer vector file for the current transcript format.
+//!
+//! Run `cargo run --example generate_kat > tests/vectors/transcript-v<version>.kat` when the
+//! transcript format changes and `KAT_VERSION` is incremented.
+
+use solana_merlin::kat::{TranscriptOp, TranscriptVector};
+
+fn append(label: &[u8], message: &[u8]) -> TranscriptOp {
+    TranscriptOp::AppendMessage {
+        label: label.to_vec(),
+        message: message.to_vec(),
+    }
+}
+
+fn append_u64(label: &[u8], value: u64) -> TranscriptOp {
+    TranscriptOp::AppendU64 {
+        label: label.to_vec(),
+        value,
+    }
+}
+
+fn challenge(label: &[u8], len: usize) -> TranscriptOp {
+    TranscriptOp::ChallengeBytes {
+        label: label.to_vec(),
+        expected: vec![0; len],
+    }
+}
+
+fn finalize() -> TranscriptOp {
+    TranscriptOp::Finalize { expected: [0; 32] }
+}
+
+fn state() -> TranscriptOp {
+    TranscriptOp::State { expected: [0; 32] }
+}
+
+fn vector(name: &str, label: &[u8], ops: Vec<TranscriptOp>) -> TranscriptVector {
+    TranscriptVector {
+        name: name.to_string(),
+        label: label.to_vec(),
+        ops,
+    }
+}
+
+fn main() {
+    let long_message = (0..600).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    let mut vectors = vec![
+        vector(
+            "empty-transcript",
+            b"kat",
+            vec![state(), finalize(), challenge(b"challenge", 32)],
+        ),
+        vector(
+            "empty-label",
+            b"",
+            vec![append(b"", b""), challenge(b"", 32)],
+        ),
+        vector(
+            "append-message",
+            b"kat",
+            vec![
+                append(b"message", b"hello world"),
+                append(b"message", b""),
+                finalize(),
+                challenge(b"challenge", 32),
+            ],
+        ),
+        vector(
+            "append-u64",
+            b"kat",
+            vec![
+                append_u64(b"zero", 0),
+                append_u64(b"max", u64::MAX),
+                challenge(b"challenge", 64),
+            ],
+        ),
+        vector(
+            "buffer-fold",
+            b"kat",
+            vec![
+                append(b"long message", &long_message),
+                state(),
+                challenge(b"challenge", 32),
+            ],
+        ),
+        vector(
+            "interleaved-challenges",
+            b"kat",
+            vec![
+                append(b"first", b"first message"),
+                challenge(b"first challenge", 32),
+                append(b"second", b"second message"),
+                state(),
+                challenge(b"second challenge", 96),
+                finalize(),
+                state(),
+            ],
+        ),
+    ];
+    for vector in &mut vectors {
+        vector.fill_expected();
+    }
+
+    println!("# Known-answer vectors for the solana-merlin transcript format.");
+    println!("# Generated by `cargo run --example generate_kat`. Do not edit.");
+    print!("{}", TranscriptVector::format_file(&vectors));
+}
@@ -0,0 +1,81 @@
+//! Appending structured values to a transcript.
+
+use crate::Transcript;
+#[cfg(feature = "ristretto")]
+use solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar};
+
+/// A value that can be appended to a transcript under a label.
+///
+/// With the `derive` feature, this trait can be derived for structs whose fields implement it.
+/// See the `solana-merlin-derive` crate for details.
+pub trait AppendToTranscript {
+    /// Append the value to the transcript under the given label.
+    fn append_to_transcript(&self, label: &[u8], transcript: &mut Transcript);
+}
+
+impl<T: AppendToTranscript + ?Sized> AppendToTranscript for &T {
+    fn append_to_transcript(&self, label: &[u8], transcript: &mut Transcript) {
+        (**self).append_to_transcript(label, transcript);
+    }
+}
+
+impl AppendToTranscript for u64 {
+    fn append_to_transcript(&self, label: &[u8], transcript: &mut Transcript) {
+        transcript.append_u64(label, *self);
+    }
+}
+
+impl AppendToTranscript for [u8] {
+    fn append_to_transcript(&self, label: &[u8], transcript: &mut Transcript) {
+        transcript.append_message(label, self);
+    }
+}
+
+impl<const N: usize> AppendToTranscript for [u8; N] {
+    fn append_to_transcript(&self, label: &[u8], transcript: &mut Transcript) {
+        transcript.append_message(label, self);
+    }
+}
+
+impl AppendToTranscript for Vec<u8> {
+    fn append_to_transcript(&self, label: &[u8], transcript: &mut Transcript) {
+        transcript.append_message(label, self);
+    }
+}
+
+#[cfg(feature = "ristretto")]
+impl Transcript {
+    /// Append a `RistrettoPoint` into the transcript.
+    pub fn append_point(&mut self, label: &[u8], point: &RistrettoPoint) {
+        self.append_message(label, &point.to_bytes());
+    }
+
+    /// Append a `Scalar` into the transcript.
+    pub fn append_scalar(&mut self, label: &[u8], scalar: &Scalar) {
+        self.append_message(label, &scalar.to_bytes());
+    }
+
+    /// Squeeze a uniformly distributed `Scalar` from the transcript.
+    ///
+    /// 64 challenge bytes are reduced modulo the group order, so the bias of the result is
+    /// negligible.
+    pub fn challenge_scalar(&mut self, label: &[u8]) -> Scalar {
+        let mut bytes = [0; 64];
+        self.challenge_bytes(label, &mut bytes);
+        Scalar::from_bytes_mod_order_wide(&bytes)
+    }
+}
+
+#[cfg(feature = "ristretto")]
+impl AppendToTranscript for RistrettoPoint {
+    fn append_to_transcript(&self, label: &[u8], transcript: &mut Transcript) {
+        transcript.append_point(label, self);
+    }
+}
+
+#[cfg(feature = "ristretto")]
+impl AppendToTranscript for Scalar {
+    fn append_to_transcript(&self, label: &[u8], transcript: &mut Transcript) {
+        transcript.append_scalar(label, self);
+    }
+}
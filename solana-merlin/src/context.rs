@@ -0,0 +1,140 @@
+//! Binding transcripts to the Solana execution context.
+//!
+//! A proof that is verified by a program is not tied to anything on-chain on its own, so anyone
+//! who observes it can resubmit it in a different instruction. Appending an `ExecutionContext` to
+//! the transcript before any proof messages binds the proof to the program that verifies it, the
+//! account that signs for it, the accounts that it acts on, and optionally a recent slot or nonce.
+//! The prover and the verifier build the context with the same routine, so a proof does not
+//! verify with a different program, signer or accounts.
+//!
+//! The binding alone does not prevent replays: the same proof verifies again in another
+//! instruction with the same program, signer and accounts, since the recent slot and the nonce are
+//! only absorbed as numbers. A program that relies on them must check them itself. It must check a
+//! recent slot against the `Clock` sysvar with a maximum age, e.g. with `check_recent_slot`, and it
+//! must record a nonce as consumed so that it is not accepted twice.
+
+use {
+    crate::Transcript,
+    solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey},
+};
+
+/// The on-chain context that a proof is bound to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionContext<'a> {
+    program_id: &'a Pubkey,
+    signer: &'a Pubkey,
+    accounts: Vec<&'a Pubkey>,
+    freshness: Freshness,
+}
+
+/// The optional value that limits the lifetime of a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Freshness {
+    None,
+    RecentSlot(u64),
+    Nonce(u64),
+}
+
+impl<'a> ExecutionContext<'a> {
+    /// Create a context for a proof that is verified by `program_id` and signed for by `signer`.
+    pub fn new(program_id: &'a Pubkey, signer: &'a Pubkey) -> Self {
+        Self {
+            program_id,
+            signer,
+            accounts: Vec::new(),
+            freshness: Freshness::None,
+        }
+    }
+
+    /// Create a context from the accounts of an instruction.
+    ///
+    /// Returns `ProgramError::MissingRequiredSignature` if `signer` did not sign the transaction.
+    pub fn from_account_infos(
+        program_id: &'a Pubkey,
+        signer: &'a AccountInfo,
+        accounts: &'a [AccountInfo],
+    ) -> Result<Self, ProgramError> {
+        if !signer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(accounts
+            .iter()
+            .fold(Self::new(program_id, signer.key), |context, account| {
+                context.with_account(account.key)
+            }))
+    }
+
+    /// Add an account that the proof acts on.
+    ///
+    /// Accounts are bound in the order in which they are added.
+    pub fn with_account(mut self, account: &'a Pubkey) -> Self {
+        self.accounts.push(account);
+        self
+    }
+
+    /// Bind the proof to a recent slot.
+    ///
+    /// The verifier must check the slot with `check_recent_slot`.
+    pub fn with_recent_slot(mut self, slot: u64) -> Self {
+        self.freshness = Freshness::RecentSlot(slot);
+        self
+    }
+
+    /// Bind the proof to a nonce.
+    ///
+    /// The verifier must reject a nonce that it has seen before and record it as consumed.
+    pub fn with_nonce(mut self, nonce: u64) -> Self {
+        self.freshness = Freshness::Nonce(nonce);
+        self
+    }
+
+    /// Check that the context is bound to a slot that is at most `max_age` slots older than
+    /// `current_slot`, which the verifier reads from the `Clock` sysvar.
+    ///
+    /// Returns `ProgramError::InvalidArgument` if the context is not bound to a recent slot, or if
+    /// the slot is stale or in the future.
+    pub fn check_recent_slot(&self, current_slot: u64, max_age: u64) -> Result<(), ProgramError> {
+        match self.freshness {
+            Freshness::RecentSlot(slot)
+                if slot <= current_slot && current_slot - slot <= max_age =>
+            {
+                Ok(())
+            }
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
+
+impl Transcript {
+    /// Append a `Pubkey` into the transcript.
+    pub fn append_pubkey(&mut self, label: &[u8], pubkey: &Pubkey) {
+        self.append_message(label, pubkey.as_ref());
+    }
+
+    /// Append an execution context to the transcript.
+    ///
+    /// The program ID, the signer, the number of accounts followed by each account, and the
+    /// freshness value are appended in this order.
+    pub fn append_execution_context(&mut self, context: &ExecutionContext) {
+        self.append_pubkey(b"context-program-id", context.program_id);
+        self.append_pubkey(b"context-signer", context.signer);
+        self.append_u64(b"context-num-accounts", context.accounts.len() as u64);
+        for account in &context.accounts {
+            self.append_pubkey(b"context-account", account);
+        }
+
+        let mut freshness = [0; 9];
+        match context.freshness {
+            Freshness::None => (),
+            Freshness::RecentSlot(slot) => {
+                freshness[0] = 1;
+                freshness[1..].copy_from_slice(&slot.to_le_bytes());
+            }
+            Freshness::Nonce(nonce) => {
+                freshness[0] = 2;
+                freshness[1..].copy_from_slice(&nonce.to_le_bytes());
+            }
+        }
+        self.append_message(b"context-freshness", &freshness);
+    }
+}
@@ -0,0 +1,80 @@
+//! Structured domain separation for transcripts.
+//!
+//! Transcript labels identify messages within a protocol, but they do not distinguish between
+//! instances of the same protocol. A `DomainSeparator` binds the protocol name, a version number,
+//! and any number of runtime context fields (e.g. a program ID or the index of a proof in a batch)
+//! to a transcript, so that transcripts of different protocol instances never collide.
+
+use crate::Transcript;
+
+/// The protocol name, version, and context fields that identify a protocol instance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DomainSeparator<'a> {
+    protocol: &'a [u8],
+    version: u64,
+    context: Vec<(&'a [u8], &'a [u8])>,
+}
+
+impl<'a> DomainSeparator<'a> {
+    /// Create a domain separator for a protocol with the given name and version.
+    pub fn new(protocol: &'a [u8], version: u64) -> Self {
+        Self {
+            protocol,
+            version,
+            context: Vec::new(),
+        }
+    }
+
+    /// Add a context field to the domain separator.
+    ///
+    /// Context fields are bound in the order in which they are added.
+    pub fn with_context(mut self, label: &'a [u8], value: &'a [u8]) -> Self {
+        self.context.push((label, value));
+        self
+    }
+
+    /// The protocol name.
+    pub fn protocol(&self) -> &'a [u8] {
+        self.protocol
+    }
+
+    /// The protocol version.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// The context fields in the order in which they were added.
+    pub fn context(&self) -> &[(&'a [u8], &'a [u8])] {
+        &self.context
+    }
+
+    /// Append the version and the context fields to a transcript.
+    ///
+    /// Every field is appended under a fixed label, so a context label can never be confused with
+    /// a message label of the protocol itself.
+    fn append_fields(&self, transcript: &mut Transcript) {
+        transcript.append_u64(b"dom-sep-version", self.version);
+        transcript.append_u64(b"dom-sep-context-len", self.context.len() as u64);
+        for (label, value) in &self.context {
+            transcript.append_message(b"dom-sep-context-label", label);
+            transcript.append_message(b"dom-sep-context-value", value);
+        }
+    }
+}
+
+impl Transcript {
+    /// Create a new transcript for the protocol instance identified by a domain separator.
+    pub fn new_with_domain(domain: &DomainSeparator) -> Transcript {
+        let mut transcript = Transcript::new(domain.protocol);
+        domain.append_fields(&mut transcript);
+        transcript
+    }
+
+    /// Append a domain separator to the transcript.
+    ///
+    /// This is used when a protocol instance is embedded in a transcript that already exists.
+    pub fn append_domain_separator(&mut self, domain: &DomainSeparator) {
+        self.append_message(b"dom-sep-protocol", domain.protocol);
+        domain.append_fields(self);
+    }
+}
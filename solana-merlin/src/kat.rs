@@ -0,0 +1,382 @@
+//! Known-answer test vectors for transcripts.
+//!
+//! A refactor of the transcript can silently change the challenges that it derives, which breaks
+//! every proof that was created with an earlier version. The known-answer vectors in
+//! `tests/vectors/transcript-v1.kat` pin down the exact outputs of the transcript format and are
+//! replayed both natively and on-chain by `test-program`.
+//!
+//! A vector file starts with the line `solana-merlin-kat <version>`, where the version is
+//! `KAT_VERSION`. Lines starting with `#` and empty lines are ignored. Every vector is a block of
+//! operations, where all labels, messages, and outputs are hex-encoded and the empty byte string
+//! is written as `-`:
+//!
+//! ```text
+//! vector <name>
+//! new <label>
+//! append <label> <message>
+//! append-u64 <label> <decimal value>
+//! challenge <label> <expected challenge>
+//! finalize <expected digest>
+//! state <expected sha256 of the serialized transcript>
+//! end
+//! ```
+//!
+//! The vectors of a version are frozen: a change to the transcript format must come with a new
+//! version and a new vector file, which is generated with
+//! `cargo run --example generate_kat > tests/vectors/transcript-v<version>.kat`.
+//!
+//! For on-chain replay, a vector is encoded into instruction data with `TranscriptVector::to_bytes`.
+
+use {crate::Transcript, solana_program::hash::hashv};
+
+/// The version of the transcript format that is pinned down by the current vector file.
+pub const KAT_VERSION: u8 = 1;
+
+/// The first token of a vector file.
+#[cfg(not(target_os = "solana"))]
+const KAT_HEADER: &str = "solana-merlin-kat";
+
+const APPEND_MESSAGE_TAG: u8 = 0;
+const APPEND_U64_TAG: u8 = 1;
+const CHALLENGE_BYTES_TAG: u8 = 2;
+const FINALIZE_TAG: u8 = 3;
+const STATE_TAG: u8 = 4;
+
+/// A single operation of a known-answer vector.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptOp {
+    /// Append a message.
+    AppendMessage { label: Vec<u8>, message: Vec<u8> },
+    /// Append a `u64` number.
+    AppendU64 { label: Vec<u8>, value: u64 },
+    /// Squeeze challenge bytes and compare them against the expected bytes.
+    ChallengeBytes { label: Vec<u8>, expected: Vec<u8> },
+    /// Compare the digest returned by `Transcript::finalize` against the expected digest.
+    Finalize { expected: [u8; 32] },
+    /// Compare the SHA-256 hash of the serialized transcript against the expected hash.
+    State { expected: [u8; 32] },
+}
+
+/// A transcript label followed by a sequence of operations with their expected outputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptVector {
+    pub name: String,
+    pub label: Vec<u8>,
+    pub ops: Vec<TranscriptOp>,
+}
+
+impl TranscriptVector {
+    /// Run the operations of the vector and check every output against its expected value.
+    pub fn replay(&self) -> Result<(), String> {
+        let outputs = self.outputs();
+        for (index, (op, output)) in self.ops.iter().zip(outputs).enumerate() {
+            let expected = match op {
+                TranscriptOp::ChallengeBytes { expected, .. } => Some(expected.as_slice()),
+                TranscriptOp::Finalize { expected } | TranscriptOp::State { expected } => {
+                    Some(expected.as_slice())
+                }
+                _ => None,
+            };
+            if expected.is_some() && expected != output.as_deref() {
+                return Err(format!(
+                    "Vector {} does not match at operation {}",
+                    self.name, index
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the expected outputs of the vector with the outputs of the current transcript.
+    ///
+    /// This is used to generate a vector file for a new version.
+    pub fn fill_expected(&mut self) {
+        let outputs = self.outputs();
+        for (op, output) in self.ops.iter_mut().zip(outputs) {
+            match (op, output) {
+                (TranscriptOp::ChallengeBytes { expected, .. }, Some(output)) => *expected = output,
+                (TranscriptOp::Finalize { expected }, Some(output))
+                | (TranscriptOp::State { expected }, Some(output)) => {
+                    expected.copy_from_slice(&output)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Run the operations of the vector and return the output of every operation.
+    fn outputs(&self) -> Vec<Option<Vec<u8>>> {
+        let mut transcript = Transcript::new(&self.label);
+        self.ops
+            .iter()
+            .map(|op| match op {
+                TranscriptOp::AppendMessage { label, message } => {
+                    transcript.append_message(label, message);
+                    None
+                }
+                TranscriptOp::AppendU64 { label, value } => {
+                    transcript.append_u64(label, *value);
+                    None
+                }
+                TranscriptOp::ChallengeBytes { label, expected } => {
+                    let mut challenge = vec![0; expected.len()];
+                    transcript.challenge_bytes(label, &mut challenge);
+                    Some(challenge)
+                }
+                TranscriptOp::Finalize { .. } => Some(transcript.finalize().to_vec()),
+                TranscriptOp::State { .. } => {
+                    Some(hashv(&[&transcript.to_bytes()]).to_bytes().to_vec())
+                }
+            })
+            .collect()
+    }
+
+    /// Encode the vector into bytes that can be passed as instruction data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![KAT_VERSION];
+        write_field(&mut buf, self.name.as_bytes());
+        write_field(&mut buf, &self.label);
+        buf.extend_from_slice(&(self.ops.len() as u32).to_le_bytes());
+        for op in &self.ops {
+            match op {
+                TranscriptOp::AppendMessage { label, message } => {
+                    buf.push(APPEND_MESSAGE_TAG);
+                    write_field(&mut buf, label);
+                    write_field(&mut buf, message);
+                }
+                TranscriptOp::AppendU64 { label, value } => {
+                    buf.push(APPEND_U64_TAG);
+                    write_field(&mut buf, label);
+                    write_field(&mut buf, &value.to_le_bytes());
+                }
+                TranscriptOp::ChallengeBytes { label, expected } => {
+                    buf.push(CHALLENGE_BYTES_TAG);
+                    write_field(&mut buf, label);
+                    write_field(&mut buf, expected);
+                }
+                TranscriptOp::Finalize { expected } => {
+                    buf.push(FINALIZE_TAG);
+                    write_field(&mut buf, expected);
+                }
+                TranscriptOp::State { expected } => {
+                    buf.push(STATE_TAG);
+                    write_field(&mut buf, expected);
+                }
+            }
+        }
+        buf
+    }
+
+    /// Decode a vector from bytes produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let (&version, mut rest) = bytes
+            .split_first()
+            .ok_or_else(|| "Vector bytes are empty".to_string())?;
+        if version != KAT_VERSION {
+            return Err("Vector bytes have unsupported version".to_string());
+        }
+
+        let name = String::from_utf8(read_field(&mut rest)?.to_vec())
+            .map_err(|_| "Vector name is not valid UTF-8".to_string())?;
+        let label = read_field(&mut rest)?.to_vec();
+        let num_ops = read_array::<4>(&mut rest).map(u32::from_le_bytes)?;
+
+        let mut ops = Vec::new();
+        for _ in 0..num_ops {
+            let (&tag, tail) = rest
+                .split_first()
+                .ok_or_else(|| "Vector bytes are truncated".to_string())?;
+            rest = tail;
+
+            ops.push(match tag {
+                APPEND_MESSAGE_TAG => TranscriptOp::AppendMessage {
+                    label: read_field(&mut rest)?.to_vec(),
+                    message: read_field(&mut rest)?.to_vec(),
+                },
+                APPEND_U64_TAG => TranscriptOp::AppendU64 {
+                    label: read_field(&mut rest)?.to_vec(),
+                    value: read_fixed_field::<8>(&mut rest).map(u64::from_le_bytes)?,
+                },
+                CHALLENGE_BYTES_TAG => TranscriptOp::ChallengeBytes {
+                    label: read_field(&mut rest)?.to_vec(),
+                    expected: read_field(&mut rest)?.to_vec(),
+                },
+                FINALIZE_TAG => TranscriptOp::Finalize {
+                    expected: read_fixed_field(&mut rest)?,
+                },
+                STATE_TAG => TranscriptOp::State {
+                    expected: read_fixed_field(&mut rest)?,
+                },
+                _ => return Err("Vector operation has invalid tag".to_string()),
+            });
+        }
+        if !rest.is_empty() {
+            return Err("Vector bytes have trailing data".to_string());
+        }
+
+        Ok(Self { name, label, ops })
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+impl TranscriptVector {
+    /// Parse the vectors of a vector file.
+    pub fn parse_file(text: &str) -> Result<Vec<Self>, String> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        match lines
+            .next()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        {
+            Some(header) if header == [KAT_HEADER, &KAT_VERSION.to_string()] => (),
+            _ => return Err("Vector file has invalid header".to_string()),
+        }
+
+        let mut vectors = Vec::new();
+        while let Some(line) = lines.next() {
+            let name = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["vector", name] => name.to_string(),
+                _ => return Err(format!("Expected a vector, found `{line}`")),
+            };
+            let label = match lines
+                .next()
+                .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            {
+                Some(fields) if fields.len() == 2 && fields[0] == "new" => decode_hex(fields[1])?,
+                _ => return Err(format!("Vector {name} does not start with `new`")),
+            };
+
+            let mut ops = Vec::new();
+            loop {
+                let line = lines
+                    .next()
+                    .ok_or_else(|| format!("Vector {name} is not terminated by `end`"))?;
+                ops.push(
+                    match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                        ["append", label, message] => TranscriptOp::AppendMessage {
+                            label: decode_hex(label)?,
+                            message: decode_hex(message)?,
+                        },
+                        ["append-u64", label, value] => TranscriptOp::AppendU64 {
+                            label: decode_hex(label)?,
+                            value: value
+                                .parse()
+                                .map_err(|_| format!("Invalid u64 value `{value}`"))?,
+                        },
+                        ["challenge", label, expected] => TranscriptOp::ChallengeBytes {
+                            label: decode_hex(label)?,
+                            expected: decode_hex(expected)?,
+                        },
+                        ["finalize", expected] => TranscriptOp::Finalize {
+                            expected: decode_hex_array(expected)?,
+                        },
+                        ["state", expected] => TranscriptOp::State {
+                            expected: decode_hex_array(expected)?,
+                        },
+                        ["end"] => break,
+                        _ => return Err(format!("Invalid operation `{line}` in vector {name}")),
+                    },
+                );
+            }
+
+            vectors.push(Self { name, label, ops });
+        }
+        Ok(vectors)
+    }
+
+    /// Format vectors as a vector file for the current version.
+    pub fn format_file(vectors: &[Self]) -> String {
+        let mut text = format!("{KAT_HEADER} {KAT_VERSION}\n");
+        for vector in vectors {
+            text.push_str(&format!("\nvector {}\n", vector.name));
+            text.push_str(&format!("new {}\n", encode_hex(&vector.label)));
+            for op in &vector.ops {
+                let line = match op {
+                    TranscriptOp::AppendMessage { label, message } => {
+                        format!("append {} {}", encode_hex(label), encode_hex(message))
+                    }
+                    TranscriptOp::AppendU64 { label, value } => {
+                        format!("append-u64 {} {}", encode_hex(label), value)
+                    }
+                    TranscriptOp::ChallengeBytes { label, expected } => {
+                        format!("challenge {} {}", encode_hex(label), encode_hex(expected))
+                    }
+                    TranscriptOp::Finalize { expected } => {
+                        format!("finalize {}", encode_hex(expected))
+                    }
+                    TranscriptOp::State { expected } => format!("state {}", encode_hex(expected)),
+                };
+                text.push_str(&line);
+                text.push('\n');
+            }
+            text.push_str("end\n");
+        }
+        text
+    }
+}
+
+fn write_field(buf: &mut Vec<u8>, field: &[u8]) {
+    buf.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    buf.extend_from_slice(field);
+}
+
+fn read_array<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], String> {
+    if bytes.len() < N {
+        return Err("Vector bytes are truncated".to_string());
+    }
+    let (array, rest) = bytes.split_at(N);
+    *bytes = rest;
+    Ok(array.try_into().unwrap())
+}
+
+fn read_field<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8], String> {
+    let len = read_array::<4>(bytes).map(u32::from_le_bytes)? as usize;
+    if bytes.len() < len {
+        return Err("Vector bytes are truncated".to_string());
+    }
+    let (field, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(field)
+}
+
+fn read_fixed_field<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], String> {
+    read_field(bytes)?
+        .try_into()
+        .map_err(|_| "Vector field has invalid length".to_string())
+}
+
+#[cfg(not(target_os = "solana"))]
+fn encode_hex(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "-".to_string();
+    }
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(not(target_os = "solana"))]
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    // the empty byte string is written as `-` so that every field is a non-empty token
+    if hex == "-" {
+        return Ok(Vec::new());
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .filter(|pair| pair.len() == 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("Invalid hex `{hex}`"))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "solana"))]
+fn decode_hex_array<const N: usize>(hex: &str) -> Result<[u8; N], String> {
+    decode_hex(hex)?
+        .try_into()
+        .map_err(|_| format!("Hex `{hex}` has invalid length"))
+}
@@ -1,40 +1,85 @@
-use crate::state::HashState;
+#[cfg(not(target_os = "solana"))]
+pub use crate::rng::{TranscriptRng, TranscriptRngBuilder};
+pub use crate::{
+    append::AppendToTranscript, context::ExecutionContext, domain::DomainSeparator,
+    stream::MessageWriter,
+};
+use crate::{
+    state::{HashState, HASH_STATE_LEN},
+    trace::Trace,
+};
+#[cfg(feature = "derive")]
+pub use solana_merlin_derive::AppendToTranscript;
 
+mod append;
+mod context;
+mod domain;
+pub mod kat;
+#[cfg(not(target_os = "solana"))]
+mod rng;
 mod state;
+pub mod stream;
+pub mod trace;
+pub mod typed;
+
+/// Byte length of a serialized transcript.
+pub const TRANSCRIPT_LEN: usize = HASH_STATE_LEN;
 
 /// The transcript type that keeps track of the internal hash state.
 #[derive(Clone)]
 pub struct Transcript {
     state: HashState,
+    trace: Option<Trace>,
 }
 
 impl Transcript {
     /// Create a new transcript.
-    pub fn new(label: &'static [u8]) -> Transcript {
+    pub fn new(label: &[u8]) -> Transcript {
+        let mut transcript = Transcript {
+            state: HashState::new(label),
+            trace: None,
+        };
+        transcript.append_message(b"dom-sep", label);
+        transcript
+    }
+
+    /// Create a new transcript that records every operation that is performed on it.
+    ///
+    /// Off-chain, the recorded operations can be retrieved with `trace`. On-chain, they are
+    /// emitted as `sol_log_data` entries. See the `trace` module for details.
+    pub fn new_with_trace(label: &[u8]) -> Transcript {
         let mut transcript = Transcript {
             state: HashState::new(label),
+            trace: Some(Trace::new()),
         };
         transcript.append_message(b"dom-sep", label);
         transcript
     }
 
     /// Append a message to the transcript.
-    pub fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+    ///
+    /// Panics if the message is longer than `u32::MAX` bytes. Messages that are not available in
+    /// one contiguous slice can be appended with `message_writer`.
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
         let data_len = encode_usize_as_u32(message.len());
         self.state.absorb(label);
         self.state.absorb(&data_len);
         self.state.absorb(message);
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.record_append_message(label, message);
+        }
     }
 
     /// Append a `u64` number into the transcript.
-    pub fn append_u64(&mut self, label: &'static [u8], x: u64) {
+    pub fn append_u64(&mut self, label: &[u8], x: u64) {
         self.append_message(label, &encode_u64(x));
     }
 
     /// Squeeze bytes out of the transcript into a destination buffer.
-    pub fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+    pub fn challenge_bytes(&mut self, label: &[u8], dest: &mut [u8]) {
         // enforce that the destination buffer must be a multiple of 32 for now
-        assert!(dest.len() % 32 == 0);
+        assert_eq!(dest.len() % 32, 0);
 
         let data_len = encode_usize_as_u32(dest.len());
         self.state.absorb(label);
@@ -44,6 +89,39 @@ impl Transcript {
             let hash = self.state.squeeze();
             chunk.copy_from_slice(hash.as_ref());
         }
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.record_challenge_bytes(label, dest);
+        }
+    }
+
+    /// The operations recorded by a transcript that was created with `new_with_trace`.
+    pub fn trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    /// Return a 32-byte digest that binds every operation performed on the transcript.
+    ///
+    /// The transcript itself is left unchanged.
+    pub fn finalize(&self) -> [u8; 32] {
+        self.state.digest().to_bytes()
+    }
+
+    /// Serialize the transcript into `TRANSCRIPT_LEN` bytes.
+    ///
+    /// A transcript restored with `from_bytes` continues exactly where this transcript left off,
+    /// which allows a protocol to be verified across multiple transactions.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.state.to_bytes()
+    }
+
+    /// Deserialize a transcript from bytes produced by `to_bytes`.
+    ///
+    /// The trace of a transcript is not serialized, so the restored transcript does not record
+    /// operations.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let state = HashState::from_bytes(bytes)?;
+        Ok(Self { state, trace: None })
     }
 }
 
@@ -58,7 +136,7 @@ fn encode_u64(x: u64) -> [u8; 8] {
 fn encode_usize_as_u32(x: usize) -> [u8; 4] {
     use byteorder::{ByteOrder, LittleEndian};
 
-    assert!(x <= (u32::max_value() as usize));
+    assert!(x <= (u32::MAX as usize));
 
     let mut buf = [0; 4];
     LittleEndian::write_u32(&mut buf, x as u32);
@@ -0,0 +1,94 @@
+//! A random number generator that is bound to the transcript and to the prover's secrets.
+//!
+//! Prover nonces that are drawn only from the system RNG are a single point of failure: if the
+//! system RNG is weak or compromised, the nonces can leak the prover's secrets. A `TranscriptRng`
+//! is instead derived from the current transcript state, the prover's witness data, and fresh
+//! randomness. The output is unpredictable as long as either the witness or the external
+//! randomness is, and it is bound to the statement being proven.
+//!
+//! ```ignore
+//! let mut rng = transcript
+//!     .build_rng()
+//!     .rekey_with_witness_bytes(b"blinding", &blinding.to_bytes())
+//!     .finalize(&mut rand::thread_rng());
+//! ```
+//!
+//! Passing a seeded RNG to `finalize` makes the prover deterministic, which is useful in tests.
+
+use {
+    crate::{state::HashState, Transcript},
+    rand_core::{impls, CryptoRng, Error, RngCore},
+};
+
+impl Transcript {
+    /// Fork the current transcript state to construct an RNG for the prover.
+    ///
+    /// The transcript itself is not modified.
+    pub fn build_rng(&self) -> TranscriptRngBuilder {
+        TranscriptRngBuilder {
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Constructs a `TranscriptRng` by rekeying a forked transcript state with witness data.
+pub struct TranscriptRngBuilder {
+    state: HashState,
+}
+
+impl TranscriptRngBuilder {
+    /// Rekey the RNG state with witness data.
+    ///
+    /// The witness is absorbed after an `rng-witness` label, so it is framed differently from a
+    /// message that is appended to the transcript under the same label.
+    pub fn rekey_with_witness_bytes(mut self, label: &[u8], witness: &[u8]) -> Self {
+        self.state.absorb(b"rng-witness");
+        self.state.absorb(label);
+        self.state.absorb(&(witness.len() as u32).to_le_bytes());
+        self.state.absorb(witness);
+        self
+    }
+
+    /// Rekey the RNG state with randomness from an external RNG and construct the
+    /// `TranscriptRng`.
+    pub fn finalize<R: RngCore + CryptoRng>(mut self, rng: &mut R) -> TranscriptRng {
+        let mut random_bytes = [0; 32];
+        rng.fill_bytes(&mut random_bytes);
+
+        self.state.absorb(b"rng");
+        self.state.absorb(&random_bytes);
+
+        TranscriptRng { state: self.state }
+    }
+}
+
+/// An RNG whose output is bound to a transcript, the prover's witness data, and external
+/// randomness.
+pub struct TranscriptRng {
+    state: HashState,
+}
+
+impl RngCore for TranscriptRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.state.absorb(&(dest.len() as u32).to_le_bytes());
+        for chunk in dest.chunks_mut(32) {
+            let hash = self.state.squeeze();
+            chunk.copy_from_slice(&hash.as_ref()[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for TranscriptRng {}
@@ -1,28 +1,114 @@
 use solana_program::hash::{hashv, Hash};
 
+/// The maximum number of bytes that are buffered before they are folded into the hash state.
+pub(crate) const BUFFER_CAPACITY: usize = 512;
+
+/// Byte length of a serialized hash state: the state, the buffer length, and the padded buffer.
+pub(crate) const HASH_STATE_LEN: usize = 32 + 4 + BUFFER_CAPACITY;
+
 /// The internal hash state used to keep track of internal state used in Fiat-Shamir transforms.
+///
+/// Absorbed bytes are framed with a 4-byte little-endian length prefix and appended to an internal
+/// buffer. The buffer is only hashed into the state when a challenge is squeezed or when it fills
+/// up to `BUFFER_CAPACITY` bytes, so that a sequence of absorbs costs a single hash syscall.
 #[derive(Clone)]
 pub(crate) struct HashState {
     state: Hash,
+    buffer: Vec<u8>,
 }
 
 impl HashState {
     /// Create a new hash state.
-    pub fn new(bytes: &'static [u8]) -> Self {
+    pub fn new(bytes: &[u8]) -> Self {
         let hash = hashv(&[b"INIT_STATE", bytes]);
-        Self { state: hash }
+        Self {
+            state: hash,
+            buffer: Vec::with_capacity(BUFFER_CAPACITY),
+        }
     }
 
     /// Absorb bytes into the hash state.
     pub fn absorb(&mut self, bytes: &[u8]) {
-        let hash = hashv(&[b"ABSORB", self.state.as_ref(), bytes]);
-        self.state = hash;
+        assert!(bytes.len() <= (u32::MAX as usize));
+
+        self.absorb_header(bytes.len() as u32);
+        self.absorb_partial(bytes);
+    }
+
+    /// Absorb the length prefix of a byte string whose bytes are absorbed with `absorb_partial`.
+    ///
+    /// A header followed by partial absorbs that add up to `len` bytes is equivalent to a single
+    /// call to `absorb`.
+    pub fn absorb_header(&mut self, len: u32) {
+        self.write(&len.to_le_bytes());
+    }
+
+    /// Absorb part of a byte string whose length prefix was absorbed with `absorb_header`.
+    pub fn absorb_partial(&mut self, bytes: &[u8]) {
+        self.write(bytes);
     }
 
     /// Squeeze bytes out from the hash state.
     pub fn squeeze(&mut self) -> Hash {
-        let hash = hashv(&[b"SQUEEZE", self.state.as_ref()]);
+        let hash = hashv(&[b"SQUEEZE", self.state.as_ref(), &self.buffer]);
         self.state = hash;
+        self.buffer.clear();
         hash
     }
+
+    /// Return a binding of the hash state without modifying it.
+    pub fn digest(&self) -> Hash {
+        hashv(&[b"FINALIZE", self.state.as_ref(), &self.buffer])
+    }
+
+    /// Serialize the hash state into a fixed-length byte vector.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(HASH_STATE_LEN);
+        buf.extend_from_slice(self.state.as_ref());
+        buf.extend_from_slice(&(self.buffer.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&self.buffer);
+        buf.resize(HASH_STATE_LEN, 0);
+        buf
+    }
+
+    /// Deserialize a hash state from bytes produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != HASH_STATE_LEN {
+            return Err("Hash state bytes have invalid length".to_string());
+        }
+        let (state, rest) = bytes.split_at(32);
+        let (buffer_len, buffer) = rest.split_at(4);
+
+        let buffer_len = u32::from_le_bytes(buffer_len.try_into().unwrap()) as usize;
+        // a full buffer is always folded into the state, so it must be strictly smaller
+        if buffer_len >= BUFFER_CAPACITY {
+            return Err("Hash state buffer length is invalid".to_string());
+        }
+        let (buffer, padding) = buffer.split_at(buffer_len);
+        if padding.iter().any(|byte| *byte != 0) {
+            return Err("Hash state padding is non-zero".to_string());
+        }
+
+        let mut state_buffer = Vec::with_capacity(BUFFER_CAPACITY);
+        state_buffer.extend_from_slice(buffer);
+        Ok(Self {
+            state: Hash::new(state),
+            buffer: state_buffer,
+        })
+    }
+
+    /// Append raw bytes to the buffer, folding full buffers into the hash state.
+    fn write(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            let available = BUFFER_CAPACITY - self.buffer.len();
+            let (head, tail) = bytes.split_at(available.min(bytes.len()));
+            self.buffer.extend_from_slice(head);
+            bytes = tail;
+
+            if self.buffer.len() == BUFFER_CAPACITY {
+                self.state = hashv(&[b"ABSORB", self.state.as_ref(), &self.buffer]);
+                self.buffer.clear();
+            }
+        }
+    }
 }
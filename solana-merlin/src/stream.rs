@@ -0,0 +1,205 @@
+//! Appending messages to a transcript in chunks.
+//!
+//! `Transcript::append_message` requires the whole message in one contiguous slice of at most
+//! `u32::MAX` bytes. A `MessageWriter` instead absorbs a message chunk by chunk, so that large
+//! serialized statements can be appended from a reader off-chain, and account data that spans
+//! several accounts can be appended on-chain without copying it into a single buffer.
+//!
+//! If the total length of the message is known up front and fits into a `u32`, the message is
+//! absorbed exactly as `append_message` would absorb it, so the result does not depend on whether
+//! the message was appended in one piece or in chunks.
+//!
+//! Otherwise, the message is absorbed in a separate unsized format: the length field that
+//! `append_message` absorbs after the label is replaced by an empty byte string, and the message
+//! is split into blocks of `STREAM_BLOCK_LEN` bytes that are each absorbed as a separate byte
+//! string. The last block is always shorter than `STREAM_BLOCK_LEN` (possibly empty) and marks
+//! the end of the message. The blocks only depend on the message itself, so the prover and the
+//! verifier can split the message into chunks differently.
+
+use {
+    crate::{encode_usize_as_u32, Transcript},
+    solana_program::hash::Hasher,
+};
+
+/// Byte length of the blocks in which a message of unknown length is absorbed.
+pub const STREAM_BLOCK_LEN: usize = 256;
+
+/// Appends a single message to a transcript in chunks.
+///
+/// A writer is created with `Transcript::message_writer` and must be completed with `finish`.
+/// A writer that is dropped without calling `finish` leaves the transcript in a state that does
+/// not correspond to any complete message.
+#[must_use = "a message writer must be completed with `finish`"]
+pub struct MessageWriter<'a> {
+    transcript: &'a mut Transcript,
+    label: &'a [u8],
+    framing: Framing,
+    written: u64,
+    hasher: Option<Hasher>,
+}
+
+/// How the chunks of a message are absorbed into the hash state.
+enum Framing {
+    /// The message has a known length that fits into a `u32` and is absorbed as a single byte
+    /// string.
+    Sized { len: u64 },
+    /// The message is absorbed in blocks of `STREAM_BLOCK_LEN` bytes. The expected length is
+    /// checked on `finish` if it was known up front.
+    Unsized { len: Option<u64>, block: Vec<u8> },
+}
+
+impl<'a> MessageWriter<'a> {
+    fn new(transcript: &'a mut Transcript, label: &'a [u8], len: Option<u64>) -> Self {
+        let framing = match len {
+            Some(len) if len <= u32::MAX as u64 => {
+                transcript.state.absorb(label);
+                transcript.state.absorb(&encode_usize_as_u32(len as usize));
+                transcript.state.absorb_header(len as u32);
+                Framing::Sized { len }
+            }
+            len => {
+                transcript.state.absorb(label);
+                transcript.state.absorb(&[]);
+                Framing::Unsized {
+                    len,
+                    block: Vec::with_capacity(STREAM_BLOCK_LEN),
+                }
+            }
+        };
+        let hasher = transcript.trace.is_some().then(Hasher::default);
+
+        Self {
+            transcript,
+            label,
+            framing,
+            written: 0,
+            hasher,
+        }
+    }
+
+    /// Absorb the next chunk of the message.
+    ///
+    /// Panics if the chunk would exceed the length that was given to `Transcript::message_writer`.
+    pub fn write(&mut self, chunk: &[u8]) {
+        self.written = self
+            .written
+            .checked_add(chunk.len() as u64)
+            .expect("message length overflows u64");
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.hash(chunk);
+        }
+
+        match &mut self.framing {
+            Framing::Sized { len } => {
+                assert!(self.written <= *len, "message exceeds its declared length");
+                self.transcript.state.absorb_partial(chunk);
+            }
+            Framing::Unsized { len, block } => {
+                if let Some(len) = len {
+                    assert!(self.written <= *len, "message exceeds its declared length");
+                }
+
+                let mut chunk = chunk;
+                while !chunk.is_empty() {
+                    let available = STREAM_BLOCK_LEN - block.len();
+                    let (head, tail) = chunk.split_at(available.min(chunk.len()));
+                    block.extend_from_slice(head);
+                    chunk = tail;
+
+                    if block.len() == STREAM_BLOCK_LEN {
+                        self.transcript.state.absorb(block);
+                        block.clear();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Complete the message.
+    ///
+    /// Panics if fewer bytes were written than the length that was given to
+    /// `Transcript::message_writer`. Returns the total number of bytes in the message.
+    pub fn finish(self) -> u64 {
+        match self.framing {
+            Framing::Sized { len } => {
+                assert_eq!(
+                    self.written, len,
+                    "message is shorter than its declared length"
+                );
+            }
+            Framing::Unsized { len, block } => {
+                if let Some(len) = len {
+                    assert_eq!(
+                        self.written, len,
+                        "message is shorter than its declared length"
+                    );
+                }
+                self.transcript.state.absorb(&block);
+            }
+        }
+
+        if let (Some(trace), Some(hasher)) = (self.transcript.trace.as_mut(), self.hasher) {
+            trace.record_append_message_digest(
+                self.label,
+                self.written,
+                hasher.result().to_bytes(),
+            );
+        }
+        self.written
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+impl std::io::Write for MessageWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        MessageWriter::write(self, buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Transcript {
+    /// Start appending a message to the transcript in chunks.
+    ///
+    /// If `len` is known and fits into a `u32`, the message is absorbed exactly as
+    /// `append_message` would absorb it. Otherwise, it is absorbed in the unsized format that is
+    /// described in the `stream` module.
+    pub fn message_writer<'a>(
+        &'a mut self,
+        label: &'a [u8],
+        len: Option<u64>,
+    ) -> MessageWriter<'a> {
+        MessageWriter::new(self, label, len)
+    }
+
+    /// Append a message that is split across several slices to the transcript.
+    ///
+    /// The total length is known up front, so the result is the same as appending the
+    /// concatenation of the slices with `append_message`.
+    pub fn append_message_chunks(&mut self, label: &[u8], chunks: &[&[u8]]) {
+        let len = chunks.iter().map(|chunk| chunk.len() as u64).sum();
+        let mut writer = self.message_writer(label, Some(len));
+        for chunk in chunks {
+            writer.write(chunk);
+        }
+        writer.finish();
+    }
+
+    /// Append a message of unknown length that is read from a reader to the transcript.
+    ///
+    /// Returns the number of bytes that were appended. If the reader fails, the transcript is
+    /// left in an incomplete state and must be discarded.
+    #[cfg(not(target_os = "solana"))]
+    pub fn append_message_from_reader<R: std::io::Read>(
+        &mut self,
+        label: &[u8],
+        mut reader: R,
+    ) -> std::io::Result<u64> {
+        let mut writer = self.message_writer(label, None);
+        std::io::copy(&mut reader, &mut writer)?;
+        Ok(writer.finish())
+    }
+}
@@ -0,0 +1,234 @@
+//! Opt-in recording of transcript operations for debugging.
+//!
+//! When the prover and the verifier derive different challenges, the cause is almost always an
+//! operation that was appended with a different label, length, or message on one side. A transcript
+//! created with `Transcript::new_with_trace` records every operation that is performed on it:
+//!
+//! - Off-chain, the operations are collected into a `Trace` that can be inspected with
+//!   `Transcript::trace`.
+//! - On-chain, the operations are emitted with `sol_log_data` so that they show up as
+//!   `Program data:` lines in the transaction logs. These lines can be parsed back into a `Trace`
+//!   off-chain with `Trace::from_log_messages`.
+//!
+//! Two traces can be compared with `Trace::first_divergence`, which returns the first operation
+//! at which the two transcripts differ.
+
+use solana_program::hash::hashv;
+#[cfg(target_os = "solana")]
+use solana_program::log::sol_log_data;
+#[cfg(not(target_os = "solana"))]
+use std::fmt;
+
+/// The first field of every on-chain trace log entry.
+pub const TRACE_LOG_TAG: &[u8] = b"transcript-trace";
+
+const APPEND_MESSAGE_TAG: u8 = 0;
+const CHALLENGE_BYTES_TAG: u8 = 1;
+
+/// A single operation performed on a transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// A message was appended to the transcript.
+    AppendMessage {
+        label: Vec<u8>,
+        length: u32,
+        digest: [u8; 32],
+    },
+    /// Challenge bytes were squeezed out of the transcript.
+    ChallengeBytes { label: Vec<u8>, challenge: Vec<u8> },
+}
+
+/// The sequence of operations performed on a transcript.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    /// Create an empty trace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The recorded operations in the order they were performed.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Record that a message was appended to the transcript.
+    pub(crate) fn record_append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.record_append_message_digest(
+            label,
+            message.len() as u64,
+            hashv(&[message]).to_bytes(),
+        );
+    }
+
+    /// Record that a message with the given length and digest was appended to the transcript.
+    ///
+    /// Lengths that do not fit into a `u32` are recorded as `u32::MAX`.
+    pub(crate) fn record_append_message_digest(
+        &mut self,
+        label: &[u8],
+        length: u64,
+        digest: [u8; 32],
+    ) {
+        let length = u32::try_from(length).unwrap_or(u32::MAX).to_le_bytes();
+
+        #[cfg(not(target_os = "solana"))]
+        self.events.push(TraceEvent::AppendMessage {
+            label: label.to_vec(),
+            length: u32::from_le_bytes(length),
+            digest,
+        });
+
+        #[cfg(target_os = "solana")]
+        sol_log_data(&[
+            TRACE_LOG_TAG,
+            &[APPEND_MESSAGE_TAG],
+            label,
+            &length,
+            &digest,
+        ]);
+    }
+
+    /// Record that challenge bytes were squeezed out of the transcript.
+    pub(crate) fn record_challenge_bytes(&mut self, label: &[u8], challenge: &[u8]) {
+        #[cfg(not(target_os = "solana"))]
+        self.events.push(TraceEvent::ChallengeBytes {
+            label: label.to_vec(),
+            challenge: challenge.to_vec(),
+        });
+
+        #[cfg(target_os = "solana")]
+        sol_log_data(&[TRACE_LOG_TAG, &[CHALLENGE_BYTES_TAG], label, challenge]);
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+impl Trace {
+    /// Reconstruct a trace from the log messages of a transaction.
+    ///
+    /// Log messages that are not trace entries are ignored.
+    pub fn from_log_messages<S: AsRef<str>>(log_messages: &[S]) -> Result<Self, String> {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        let mut events = Vec::new();
+        for log_message in log_messages {
+            let Some(data) = log_message.as_ref().strip_prefix("Program data: ") else {
+                continue;
+            };
+            let fields = data
+                .split(' ')
+                .map(|field| STANDARD.decode(field))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| "Trace log entry is not valid base64".to_string())?;
+
+            match fields.as_slice() {
+                [tag, kind, label, length, digest]
+                    if tag == TRACE_LOG_TAG && kind == &[APPEND_MESSAGE_TAG] =>
+                {
+                    let length = length
+                        .as_slice()
+                        .try_into()
+                        .map(u32::from_le_bytes)
+                        .map_err(|_| "Trace log entry has invalid length".to_string())?;
+                    let digest = digest
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| "Trace log entry has invalid digest".to_string())?;
+                    events.push(TraceEvent::AppendMessage {
+                        label: label.clone(),
+                        length,
+                        digest,
+                    });
+                }
+                [tag, kind, label, challenge]
+                    if tag == TRACE_LOG_TAG && kind == &[CHALLENGE_BYTES_TAG] =>
+                {
+                    events.push(TraceEvent::ChallengeBytes {
+                        label: label.clone(),
+                        challenge: challenge.clone(),
+                    });
+                }
+                [tag, ..] if tag == TRACE_LOG_TAG => {
+                    return Err("Trace log entry is malformed".to_string());
+                }
+                _ => continue,
+            }
+        }
+        Ok(Self { events })
+    }
+
+    /// Compare two traces and return the first operation at which they differ.
+    ///
+    /// Returns `None` if the two traces are identical.
+    pub fn first_divergence(&self, other: &Trace) -> Option<Divergence> {
+        let len = self.events.len().max(other.events.len());
+        (0..len).find_map(|index| {
+            let left = self.events.get(index);
+            let right = other.events.get(index);
+            (left != right).then(|| Divergence {
+                index,
+                left: left.cloned(),
+                right: right.cloned(),
+            })
+        })
+    }
+}
+
+/// The first operation at which two traces differ.
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// The position of the operation in the traces.
+    pub index: usize,
+    /// The operation in the first trace, or `None` if the first trace ended earlier.
+    pub left: Option<TraceEvent>,
+    /// The operation in the second trace, or `None` if the second trace ended earlier.
+    pub right: Option<TraceEvent>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AppendMessage {
+                label,
+                length,
+                digest,
+            } => write!(
+                f,
+                "append \"{}\" length={} digest={}",
+                String::from_utf8_lossy(label),
+                length,
+                hex(digest)
+            ),
+            Self::ChallengeBytes { label, challenge } => write!(
+                f,
+                "challenge \"{}\" length={} bytes={}",
+                String::from_utf8_lossy(label),
+                challenge.len(),
+                hex(challenge)
+            ),
+        }
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let describe = |event: &Option<TraceEvent>| match event {
+            Some(event) => event.to_string(),
+            None => "<end of trace>".to_string(),
+        };
+        writeln!(f, "transcripts diverge at operation {}", self.index)?;
+        writeln!(f, "  left:  {}", describe(&self.left))?;
+        write!(f, "  right: {}", describe(&self.right))
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
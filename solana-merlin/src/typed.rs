@@ -0,0 +1,167 @@
+//! Transcripts that enforce the message schedule of a protocol at compile time.
+//!
+//! Fiat-Shamir bugs usually come from an append that is skipped, reordered, or made under a
+//! different label on one side of a protocol. A `TypedTranscript<S>` wraps a `Transcript` together
+//! with a state type `S`. A protocol defines:
+//!
+//! - a marker type for every message, which implements `Message` and fixes its label and value
+//!   type;
+//! - a marker type for every challenge, which implements `Challenge` and fixes its label;
+//! - a state type for every point in the protocol, which implements `Transition<M>` for every
+//!   message or challenge `M` that may follow in that state.
+//!
+//! `TypedTranscript::append` and `TypedTranscript::challenge_bytes` consume the transcript and
+//! return it in the next state, so an operation that is not allowed in the current state does not
+//! compile. Since the prover and the verifier are written against the same states, they share one
+//! machine-checked message schedule.
+//!
+//! ```
+//! use solana_merlin::typed::{Challenge, Message, Transition, TypedTranscript};
+//!
+//! struct Commitment;
+//! impl Message for Commitment {
+//!     const LABEL: &'static [u8] = b"commitment";
+//!     type Value = [u8; 32];
+//! }
+//!
+//! struct ChallengeC;
+//! impl Challenge for ChallengeC {
+//!     const LABEL: &'static [u8] = b"c";
+//! }
+//!
+//! struct AwaitingCommitment;
+//! struct AwaitingChallenge;
+//! struct Done;
+//! impl Transition<Commitment> for AwaitingCommitment {
+//!     type Next = AwaitingChallenge;
+//! }
+//! impl Transition<ChallengeC> for AwaitingChallenge {
+//!     type Next = Done;
+//! }
+//!
+//! let transcript = TypedTranscript::<AwaitingCommitment>::new(b"example");
+//! let transcript = transcript.append::<Commitment>(&[0; 32]);
+//! let mut c = [0; 32];
+//! let _transcript: TypedTranscript<Done> = transcript.challenge_bytes::<ChallengeC>(&mut c);
+//! ```
+//!
+//! Squeezing the challenge before appending the commitment is rejected by the compiler:
+//!
+//! ```compile_fail
+//! # use solana_merlin::typed::{Challenge, Message, Transition, TypedTranscript};
+//! # struct Commitment;
+//! # impl Message for Commitment {
+//! #     const LABEL: &'static [u8] = b"commitment";
+//! #     type Value = [u8; 32];
+//! # }
+//! # struct ChallengeC;
+//! # impl Challenge for ChallengeC {
+//! #     const LABEL: &'static [u8] = b"c";
+//! # }
+//! # struct AwaitingCommitment;
+//! # struct AwaitingChallenge;
+//! # struct Done;
+//! # impl Transition<Commitment> for AwaitingCommitment {
+//! #     type Next = AwaitingChallenge;
+//! # }
+//! # impl Transition<ChallengeC> for AwaitingChallenge {
+//! #     type Next = Done;
+//! # }
+//! let transcript = TypedTranscript::<AwaitingCommitment>::new(b"example");
+//! let mut c = [0; 32];
+//! let transcript = transcript.challenge_bytes::<ChallengeC>(&mut c);
+//! ```
+
+#[cfg(feature = "ristretto")]
+use solana_ristretto::scalar::Scalar;
+use {
+    crate::{AppendToTranscript, Transcript},
+    std::{borrow::BorrowMut, marker::PhantomData},
+};
+
+/// A message of a protocol with a fixed label and value type.
+pub trait Message {
+    /// The label under which the message is appended.
+    const LABEL: &'static [u8];
+    /// The type of the appended value.
+    type Value: AppendToTranscript + ?Sized;
+}
+
+/// A challenge of a protocol with a fixed label.
+pub trait Challenge {
+    /// The label under which the challenge is squeezed.
+    const LABEL: &'static [u8];
+}
+
+/// A state of a protocol in which the message or challenge `M` may be performed.
+pub trait Transition<M> {
+    /// The state of the protocol after `M` was performed.
+    type Next;
+}
+
+/// A transcript in the protocol state `S`.
+///
+/// The transcript is either owned (`T = Transcript`) or borrowed (`T = &mut Transcript`), so a
+/// protocol can also be run on a transcript that is owned by its caller.
+pub struct TypedTranscript<S, T = Transcript> {
+    transcript: T,
+    state: PhantomData<S>,
+}
+
+impl<S> TypedTranscript<S> {
+    /// Create a new transcript in the state `S`.
+    pub fn new(label: &[u8]) -> Self {
+        Self::from_transcript(Transcript::new(label))
+    }
+}
+
+impl<S, T: BorrowMut<Transcript>> TypedTranscript<S, T> {
+    /// Continue an existing transcript in the state `S`.
+    ///
+    /// This is used when a protocol is embedded in a transcript that already exists, e.g. after a
+    /// domain separator was appended.
+    pub fn from_transcript(transcript: T) -> Self {
+        Self {
+            transcript,
+            state: PhantomData,
+        }
+    }
+
+    /// Append the message `M`, which must be allowed in the current state.
+    pub fn append<M: Message>(mut self, value: &M::Value) -> TypedTranscript<S::Next, T>
+    where
+        S: Transition<M>,
+    {
+        value.append_to_transcript(M::LABEL, self.transcript.borrow_mut());
+        TypedTranscript::from_transcript(self.transcript)
+    }
+
+    /// Squeeze the challenge `C`, which must be allowed in the current state.
+    pub fn challenge_bytes<C: Challenge>(mut self, dest: &mut [u8]) -> TypedTranscript<S::Next, T>
+    where
+        S: Transition<C>,
+    {
+        self.transcript.borrow_mut().challenge_bytes(C::LABEL, dest);
+        TypedTranscript::from_transcript(self.transcript)
+    }
+
+    /// Squeeze the challenge `C` as a `Scalar`, see `Transcript::challenge_scalar`.
+    #[cfg(feature = "ristretto")]
+    pub fn challenge_scalar<C: Challenge>(mut self) -> (TypedTranscript<S::Next, T>, Scalar)
+    where
+        S: Transition<C>,
+    {
+        let scalar = self.transcript.borrow_mut().challenge_scalar(C::LABEL);
+        (TypedTranscript::from_transcript(self.transcript), scalar)
+    }
+
+    /// The underlying transcript.
+    pub fn as_transcript(&self) -> &Transcript {
+        self.transcript.borrow()
+    }
+
+    /// Leave the typed layer and return the underlying transcript.
+    pub fn into_transcript(self) -> T {
+        self.transcript
+    }
+}
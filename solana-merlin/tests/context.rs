@@ -0,0 +1,43 @@
+use {
+    solana_merlin::ExecutionContext,
+    solana_program::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+#[test]
+fn test_check_recent_slot() {
+    let program_id = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    let context = ExecutionContext::new(&program_id, &signer).with_recent_slot(100);
+
+    assert_eq!(context.check_recent_slot(100, 150), Ok(()));
+    assert_eq!(context.check_recent_slot(250, 150), Ok(()));
+
+    // a stale slot
+    assert_eq!(
+        context.check_recent_slot(251, 150),
+        Err(ProgramError::InvalidArgument)
+    );
+    // a slot in the future
+    assert_eq!(
+        context.check_recent_slot(99, 150),
+        Err(ProgramError::InvalidArgument)
+    );
+}
+
+#[test]
+fn test_check_recent_slot_requires_slot() {
+    let program_id = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+
+    let context = ExecutionContext::new(&program_id, &signer);
+    assert_eq!(
+        context.check_recent_slot(100, 150),
+        Err(ProgramError::InvalidArgument)
+    );
+
+    let context = context.with_nonce(100);
+    assert_eq!(
+        context.check_recent_slot(100, 150),
+        Err(ProgramError::InvalidArgument)
+    );
+}
@@ -0,0 +1,46 @@
+use solana_merlin::kat::{TranscriptOp, TranscriptVector, KAT_VERSION};
+
+const VECTORS: &str = include_str!("vectors/transcript-v1.kat");
+
+fn vectors() -> Vec<TranscriptVector> {
+    TranscriptVector::parse_file(VECTORS).unwrap()
+}
+
+#[test]
+fn test_vector_file_matches_version() {
+    assert_eq!(KAT_VERSION, 1);
+    assert!(!vectors().is_empty());
+}
+
+#[test]
+fn test_replay_vectors() {
+    for vector in vectors() {
+        vector.replay().unwrap();
+    }
+}
+
+#[test]
+fn test_vector_bytes_round_trip() {
+    for vector in vectors() {
+        let decoded = TranscriptVector::from_bytes(&vector.to_bytes()).unwrap();
+        assert_eq!(decoded, vector);
+    }
+}
+
+#[test]
+fn test_vector_file_round_trip() {
+    let vectors = vectors();
+    let text = TranscriptVector::format_file(&vectors);
+    assert_eq!(TranscriptVector::parse_file(&text).unwrap(), vectors);
+}
+
+#[test]
+fn test_replay_detects_modified_vector() {
+    let mut vector = vectors().remove(0);
+    for op in vector.ops.iter_mut() {
+        if let TranscriptOp::ChallengeBytes { expected, .. } = op {
+            expected[0] ^= 1;
+        }
+    }
+    assert!(vector.replay().is_err());
+}
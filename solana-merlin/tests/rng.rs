@@ -0,0 +1,83 @@
+use {
+    rand_chacha::ChaChaRng,
+    rand_core::{RngCore, SeedableRng},
+    solana_merlin::Transcript,
+};
+
+fn transcript_rng_output(transcript_message: &[u8], witness: &[u8], seed: [u8; 32]) -> [u8; 64] {
+    let mut transcript = Transcript::new(b"rng test");
+    transcript.append_message(b"statement", transcript_message);
+
+    let mut rng = transcript
+        .build_rng()
+        .rekey_with_witness_bytes(b"witness", witness)
+        .finalize(&mut ChaChaRng::from_seed(seed));
+
+    let mut output = [0; 64];
+    rng.fill_bytes(&mut output);
+    output
+}
+
+#[test]
+fn test_transcript_rng_is_deterministic_for_seeded_rng() {
+    let output = transcript_rng_output(b"statement", b"witness", [0; 32]);
+    assert_eq!(
+        output,
+        transcript_rng_output(b"statement", b"witness", [0; 32])
+    );
+}
+
+#[test]
+fn test_transcript_rng_is_bound_to_all_inputs() {
+    let output = transcript_rng_output(b"statement", b"witness", [0; 32]);
+
+    // with a fixed external RNG, the output still depends on the transcript and the witness
+    assert_ne!(
+        output,
+        transcript_rng_output(b"other statement", b"witness", [0; 32])
+    );
+    assert_ne!(
+        output,
+        transcript_rng_output(b"statement", b"other witness", [0; 32])
+    );
+    assert_ne!(
+        output,
+        transcript_rng_output(b"statement", b"witness", [1; 32])
+    );
+}
+
+#[test]
+fn test_witness_is_not_framed_like_a_message() {
+    let mut appended = Transcript::new(b"rng test");
+    appended.append_message(b"witness", b"witness");
+    let mut appended_rng = appended
+        .build_rng()
+        .finalize(&mut ChaChaRng::from_seed([0; 32]));
+
+    let mut rekeyed_rng = Transcript::new(b"rng test")
+        .build_rng()
+        .rekey_with_witness_bytes(b"witness", b"witness")
+        .finalize(&mut ChaChaRng::from_seed([0; 32]));
+
+    assert_ne!(appended_rng.next_u64(), rekeyed_rng.next_u64());
+}
+
+#[test]
+fn test_transcript_rng_does_not_modify_transcript() {
+    let mut transcript = Transcript::new(b"rng test");
+    let digest = transcript.finalize();
+
+    let mut rng = transcript
+        .build_rng()
+        .rekey_with_witness_bytes(b"witness", b"witness")
+        .finalize(&mut ChaChaRng::from_seed([0; 32]));
+    rng.next_u64();
+    assert_eq!(transcript.finalize(), digest);
+
+    // the RNG output is independent of the challenges that are derived from the transcript
+    let mut challenge = [0; 32];
+    transcript.challenge_bytes(b"challenge", &mut challenge);
+    let mut output = [0; 32];
+    rng.fill_bytes(&mut output);
+    assert_ne!(challenge, output);
+}
@@ -0,0 +1,121 @@
+use solana_merlin::{stream::STREAM_BLOCK_LEN, Transcript};
+
+fn challenge(transcript: &mut Transcript) -> [u8; 32] {
+    let mut challenge = [0; 32];
+    transcript.challenge_bytes(b"challenge", &mut challenge);
+    challenge
+}
+
+fn message(len: usize) -> Vec<u8> {
+    (0..len).map(|i| i as u8).collect()
+}
+
+#[test]
+fn test_sized_message_writer_matches_append_message() {
+    for len in [0, 1, 31, 512, 1000] {
+        let message = message(len);
+
+        let mut expected = Transcript::new(b"stream test");
+        expected.append_message(b"message", &message);
+
+        for chunk_len in [1, 7, 512] {
+            let mut transcript = Transcript::new(b"stream test");
+            let mut writer = transcript.message_writer(b"message", Some(len as u64));
+            for chunk in message.chunks(chunk_len) {
+                writer.write(chunk);
+            }
+            assert_eq!(writer.finish(), len as u64);
+
+            assert_eq!(challenge(&mut transcript), challenge(&mut expected.clone()));
+        }
+    }
+}
+
+#[test]
+fn test_append_message_chunks_matches_append_message() {
+    let message = message(700);
+    let (left, right) = message.split_at(300);
+
+    let mut expected = Transcript::new(b"stream test");
+    expected.append_message(b"message", &message);
+
+    let mut transcript = Transcript::new(b"stream test");
+    transcript.append_message_chunks(b"message", &[left, &[], right]);
+
+    assert_eq!(challenge(&mut transcript), challenge(&mut expected));
+}
+
+#[test]
+fn test_unsized_message_does_not_depend_on_chunking() {
+    for len in [
+        0,
+        1,
+        STREAM_BLOCK_LEN - 1,
+        STREAM_BLOCK_LEN,
+        3 * STREAM_BLOCK_LEN + 5,
+    ] {
+        let message = message(len);
+
+        let mut expected = Transcript::new(b"stream test");
+        assert_eq!(
+            expected
+                .append_message_from_reader(b"message", message.as_slice())
+                .unwrap(),
+            len as u64
+        );
+
+        let mut transcript = Transcript::new(b"stream test");
+        let mut writer = transcript.message_writer(b"message", None);
+        for chunk in message.chunks(13) {
+            writer.write(chunk);
+        }
+        writer.finish();
+
+        assert_eq!(challenge(&mut transcript), challenge(&mut expected));
+    }
+}
+
+#[test]
+fn test_unsized_message_differs_from_sized_message() {
+    let message = message(STREAM_BLOCK_LEN);
+
+    let mut sized = Transcript::new(b"stream test");
+    sized.append_message(b"message", &message);
+
+    let mut unsized_transcript = Transcript::new(b"stream test");
+    unsized_transcript
+        .append_message_from_reader(b"message", message.as_slice())
+        .unwrap();
+
+    assert_ne!(challenge(&mut sized), challenge(&mut unsized_transcript));
+}
+
+#[test]
+fn test_traced_message_writer_matches_append_message() {
+    let message = message(100);
+
+    let mut expected = Transcript::new_with_trace(b"stream test");
+    expected.append_message(b"message", &message);
+
+    let mut transcript = Transcript::new_with_trace(b"stream test");
+    transcript.append_message_chunks(b"message", &[&message[..40], &message[40..]]);
+
+    assert_eq!(transcript.trace(), expected.trace());
+}
+
+#[test]
+#[should_panic(expected = "message exceeds its declared length")]
+fn test_sized_message_writer_rejects_long_message() {
+    let mut transcript = Transcript::new(b"stream test");
+    let mut writer = transcript.message_writer(b"message", Some(4));
+    writer.write(&[0; 5]);
+}
+
+#[test]
+#[should_panic(expected = "message is shorter than its declared length")]
+fn test_sized_message_writer_rejects_short_message() {
+    let mut transcript = Transcript::new(b"stream test");
+    let mut writer = transcript.message_writer(b"message", Some(4));
+    writer.write(&[0; 3]);
+    writer.finish();
+}
@@ -0,0 +1,99 @@
+//! Print the known-answer vector file for the current Ristretto arithmetic.
+//!
+//! Run `cargo run --example generate_kat > tests/vectors/ristretto-v<version>.kat` when
+//! `KAT_VERSION` is incremented.
+
+use {
+    curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT, scalar::Scalar as DalekScalar},
+    solana_ristretto::kat::{RistrettoOp, RistrettoVector},
+};
+
+fn point(k: u64) -> [u8; 32] {
+    (RISTRETTO_BASEPOINT_POINT * DalekScalar::from(k))
+        .compress()
+        .to_bytes()
+}
+
+fn scalar(k: u64) -> [u8; 32] {
+    DalekScalar::from(k).to_bytes()
+}
+
+fn main() {
+    let identity = [0; 32];
+    let large_scalar = (-DalekScalar::from(1_u64)).to_bytes();
+
+    let mut vectors = vec![
+        RistrettoVector {
+            name: "add".to_string(),
+            ops: vec![
+                RistrettoOp::Add {
+                    left: point(1),
+                    right: point(2),
+                    expected: [0; 32],
+                },
+                RistrettoOp::Add {
+                    left: point(7),
+                    right: identity,
+                    expected: [0; 32],
+                },
+            ],
+        },
+        RistrettoVector {
+            name: "subtract".to_string(),
+            ops: vec![
+                RistrettoOp::Subtract {
+                    left: point(5),
+                    right: point(3),
+                    expected: [0; 32],
+                },
+                RistrettoOp::Subtract {
+                    left: point(3),
+                    right: point(3),
+                    expected: [0; 32],
+                },
+            ],
+        },
+        RistrettoVector {
+            name: "multiply".to_string(),
+            ops: vec![
+                RistrettoOp::Multiply {
+                    scalar: scalar(0),
+                    point: point(1),
+                    expected: [0; 32],
+                },
+                RistrettoOp::Multiply {
+                    scalar: scalar(11),
+                    point: point(13),
+                    expected: [0; 32],
+                },
+                RistrettoOp::Multiply {
+                    scalar: large_scalar,
+                    point: point(2),
+                    expected: [0; 32],
+                },
+            ],
+        },
+        RistrettoVector {
+            name: "multiscalar-multiply".to_string(),
+            ops: vec![
+                RistrettoOp::MultiscalarMultiply {
+                    scalars: vec![scalar(3)],
+                    points: vec![point(4)],
+                    expected: [0; 32],
+                },
+                RistrettoOp::MultiscalarMultiply {
+                    scalars: vec![scalar(2), scalar(5), large_scalar],
+                    points: vec![point(1), point(9), point(17)],
+                    expected: [0; 32],
+                },
+            ],
+        },
+    ];
+    for vector in &mut vectors {
+        vector.fill_expected().unwrap();
+    }
+
+    println!("# Known-answer vectors for solana-ristretto point arithmetic.");
+    println!("# Generated by `cargo run --example generate_kat`. Do not edit.");
+    print!("{}", RistrettoVector::format_file(&vectors));
+}
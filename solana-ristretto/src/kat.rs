@@ -0,0 +1,434 @@
+//! Known-answer test vectors for Ristretto point arithmetic.
+//!
+//! The arithmetic is implemented with curve25519-dalek off-chain and with syscalls on-chain. The
+//! known-answer vectors in `tests/vectors/ristretto-v1.kat` pin down the outputs of both
+//! implementations and are replayed natively and on-chain by `test-program`.
+//!
+//! A vector file starts with the line `solana-ristretto-kat <version>`, where the version is
+//! `KAT_VERSION`. Lines starting with `#` and empty lines are ignored. Every vector is a block of
+//! operations, where all scalars and points are hex-encoded and lists are comma-separated:
+//!
+//! ```text
+//! vector <name>
+//! add <left point> <right point> <expected point>
+//! subtract <left point> <right point> <expected point>
+//! multiply <scalar> <point> <expected point>
+//! multiscalar-multiply <scalars> <points> <expected point>
+//! end
+//! ```
+//!
+//! The vectors of a version are frozen. A new vector file is generated with
+//! `cargo run --example generate_kat > tests/vectors/ristretto-v<version>.kat`.
+//!
+//! For on-chain replay, a vector is encoded into instruction data with `RistrettoVector::to_bytes`.
+
+use crate::{ristretto::RistrettoPoint, scalar::Scalar};
+
+/// The version of the vector format.
+pub const KAT_VERSION: u8 = 1;
+
+/// The first token of a vector file.
+#[cfg(not(target_os = "solana"))]
+const KAT_HEADER: &str = "solana-ristretto-kat";
+
+const ADD_TAG: u8 = 0;
+const SUBTRACT_TAG: u8 = 1;
+const MULTIPLY_TAG: u8 = 2;
+const MULTISCALAR_MULTIPLY_TAG: u8 = 3;
+
+/// A single operation of a known-answer vector.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RistrettoOp {
+    /// Add two points.
+    Add {
+        left: [u8; 32],
+        right: [u8; 32],
+        expected: [u8; 32],
+    },
+    /// Subtract two points.
+    Subtract {
+        left: [u8; 32],
+        right: [u8; 32],
+        expected: [u8; 32],
+    },
+    /// Multiply a point by a scalar.
+    Multiply {
+        scalar: [u8; 32],
+        point: [u8; 32],
+        expected: [u8; 32],
+    },
+    /// Compute the multiscalar multiplication of points and scalars.
+    MultiscalarMultiply {
+        scalars: Vec<[u8; 32]>,
+        points: Vec<[u8; 32]>,
+        expected: [u8; 32],
+    },
+}
+
+/// A sequence of point operations with their expected outputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RistrettoVector {
+    pub name: String,
+    pub ops: Vec<RistrettoOp>,
+}
+
+impl RistrettoOp {
+    /// Compute the output of the operation.
+    fn output(&self) -> Result<[u8; 32], String> {
+        let result = match self {
+            RistrettoOp::Add { left, right, .. } => {
+                RistrettoPoint::from_bytes(left)?.add(&RistrettoPoint::from_bytes(right)?)?
+            }
+            RistrettoOp::Subtract { left, right, .. } => {
+                RistrettoPoint::from_bytes(left)?.subtract(&RistrettoPoint::from_bytes(right)?)?
+            }
+            RistrettoOp::Multiply { scalar, point, .. } => {
+                RistrettoPoint::from_bytes(point)?.multiply(&Scalar::from_bytes(scalar)?)?
+            }
+            RistrettoOp::MultiscalarMultiply {
+                scalars, points, ..
+            } => {
+                let scalars = scalars
+                    .iter()
+                    .map(|scalar| Scalar::from_bytes(scalar))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let points = points
+                    .iter()
+                    .map(|point| RistrettoPoint::from_bytes(point))
+                    .collect::<Result<Vec<_>, _>>()?;
+                RistrettoPoint::multiscalar_multiply(&scalars, &points)?
+            }
+        };
+        Ok(result.to_bytes())
+    }
+
+    fn expected(&self) -> [u8; 32] {
+        match self {
+            RistrettoOp::Add { expected, .. }
+            | RistrettoOp::Subtract { expected, .. }
+            | RistrettoOp::Multiply { expected, .. }
+            | RistrettoOp::MultiscalarMultiply { expected, .. } => *expected,
+        }
+    }
+
+    fn expected_mut(&mut self) -> &mut [u8; 32] {
+        match self {
+            RistrettoOp::Add { expected, .. }
+            | RistrettoOp::Subtract { expected, .. }
+            | RistrettoOp::Multiply { expected, .. }
+            | RistrettoOp::MultiscalarMultiply { expected, .. } => expected,
+        }
+    }
+}
+
+impl RistrettoVector {
+    /// Run the operations of the vector and check every output against its expected value.
+    pub fn replay(&self) -> Result<(), String> {
+        for (index, op) in self.ops.iter().enumerate() {
+            if op.output()? != op.expected() {
+                return Err(format!(
+                    "Vector {} does not match at operation {}",
+                    self.name, index
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the expected outputs of the vector with the outputs of the current implementation.
+    ///
+    /// This is used to generate a vector file for a new version.
+    pub fn fill_expected(&mut self) -> Result<(), String> {
+        for op in self.ops.iter_mut() {
+            *op.expected_mut() = op.output()?;
+        }
+        Ok(())
+    }
+
+    /// Encode the vector into bytes that can be passed as instruction data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![KAT_VERSION];
+        buf.extend_from_slice(&(self.name.len() as u32).to_le_bytes());
+        buf.extend_from_slice(self.name.as_bytes());
+        buf.extend_from_slice(&(self.ops.len() as u32).to_le_bytes());
+        for op in &self.ops {
+            match op {
+                RistrettoOp::Add {
+                    left,
+                    right,
+                    expected,
+                } => {
+                    buf.push(ADD_TAG);
+                    buf.extend_from_slice(left);
+                    buf.extend_from_slice(right);
+                    buf.extend_from_slice(expected);
+                }
+                RistrettoOp::Subtract {
+                    left,
+                    right,
+                    expected,
+                } => {
+                    buf.push(SUBTRACT_TAG);
+                    buf.extend_from_slice(left);
+                    buf.extend_from_slice(right);
+                    buf.extend_from_slice(expected);
+                }
+                RistrettoOp::Multiply {
+                    scalar,
+                    point,
+                    expected,
+                } => {
+                    buf.push(MULTIPLY_TAG);
+                    buf.extend_from_slice(scalar);
+                    buf.extend_from_slice(point);
+                    buf.extend_from_slice(expected);
+                }
+                RistrettoOp::MultiscalarMultiply {
+                    scalars,
+                    points,
+                    expected,
+                } => {
+                    buf.push(MULTISCALAR_MULTIPLY_TAG);
+                    buf.extend_from_slice(&(scalars.len() as u32).to_le_bytes());
+                    scalars
+                        .iter()
+                        .for_each(|scalar| buf.extend_from_slice(scalar));
+                    buf.extend_from_slice(&(points.len() as u32).to_le_bytes());
+                    points.iter().for_each(|point| buf.extend_from_slice(point));
+                    buf.extend_from_slice(expected);
+                }
+            }
+        }
+        buf
+    }
+
+    /// Decode a vector from bytes produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let (&version, mut rest) = bytes
+            .split_first()
+            .ok_or_else(|| "Vector bytes are empty".to_string())?;
+        if version != KAT_VERSION {
+            return Err("Vector bytes have unsupported version".to_string());
+        }
+
+        let name_len = read_u32(&mut rest)? as usize;
+        let name = String::from_utf8(read_bytes(&mut rest, name_len)?.to_vec())
+            .map_err(|_| "Vector name is not valid UTF-8".to_string())?;
+        let num_ops = read_u32(&mut rest)?;
+
+        let mut ops = Vec::new();
+        for _ in 0..num_ops {
+            let tag = read_bytes(&mut rest, 1)?[0];
+            ops.push(match tag {
+                ADD_TAG => RistrettoOp::Add {
+                    left: read_array(&mut rest)?,
+                    right: read_array(&mut rest)?,
+                    expected: read_array(&mut rest)?,
+                },
+                SUBTRACT_TAG => RistrettoOp::Subtract {
+                    left: read_array(&mut rest)?,
+                    right: read_array(&mut rest)?,
+                    expected: read_array(&mut rest)?,
+                },
+                MULTIPLY_TAG => RistrettoOp::Multiply {
+                    scalar: read_array(&mut rest)?,
+                    point: read_array(&mut rest)?,
+                    expected: read_array(&mut rest)?,
+                },
+                MULTISCALAR_MULTIPLY_TAG => {
+                    let num_scalars = read_u32(&mut rest)?;
+                    let scalars = (0..num_scalars)
+                        .map(|_| read_array(&mut rest))
+                        .collect::<Result<_, _>>()?;
+                    let num_points = read_u32(&mut rest)?;
+                    let points = (0..num_points)
+                        .map(|_| read_array(&mut rest))
+                        .collect::<Result<_, _>>()?;
+                    RistrettoOp::MultiscalarMultiply {
+                        scalars,
+                        points,
+                        expected: read_array(&mut rest)?,
+                    }
+                }
+                _ => return Err("Vector operation has invalid tag".to_string()),
+            });
+        }
+        if !rest.is_empty() {
+            return Err("Vector bytes have trailing data".to_string());
+        }
+
+        Ok(Self { name, ops })
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+impl RistrettoVector {
+    /// Parse the vectors of a vector file.
+    pub fn parse_file(text: &str) -> Result<Vec<Self>, String> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        match lines
+            .next()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        {
+            Some(header) if header == [KAT_HEADER, &KAT_VERSION.to_string()] => (),
+            _ => return Err("Vector file has invalid header".to_string()),
+        }
+
+        let mut vectors = Vec::new();
+        while let Some(line) = lines.next() {
+            let name = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["vector", name] => name.to_string(),
+                _ => return Err(format!("Expected a vector, found `{line}`")),
+            };
+
+            let mut ops = Vec::new();
+            loop {
+                let line = lines
+                    .next()
+                    .ok_or_else(|| format!("Vector {name} is not terminated by `end`"))?;
+                ops.push(
+                    match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                        ["add", left, right, expected] => RistrettoOp::Add {
+                            left: decode_hex(left)?,
+                            right: decode_hex(right)?,
+                            expected: decode_hex(expected)?,
+                        },
+                        ["subtract", left, right, expected] => RistrettoOp::Subtract {
+                            left: decode_hex(left)?,
+                            right: decode_hex(right)?,
+                            expected: decode_hex(expected)?,
+                        },
+                        ["multiply", scalar, point, expected] => RistrettoOp::Multiply {
+                            scalar: decode_hex(scalar)?,
+                            point: decode_hex(point)?,
+                            expected: decode_hex(expected)?,
+                        },
+                        ["multiscalar-multiply", scalars, points, expected] => {
+                            RistrettoOp::MultiscalarMultiply {
+                                scalars: decode_hex_list(scalars)?,
+                                points: decode_hex_list(points)?,
+                                expected: decode_hex(expected)?,
+                            }
+                        }
+                        ["end"] => break,
+                        _ => return Err(format!("Invalid operation `{line}` in vector {name}")),
+                    },
+                );
+            }
+
+            vectors.push(Self { name, ops });
+        }
+        Ok(vectors)
+    }
+
+    /// Format vectors as a vector file for the current version.
+    pub fn format_file(vectors: &[Self]) -> String {
+        let mut text = format!("{KAT_HEADER} {KAT_VERSION}\n");
+        for vector in vectors {
+            text.push_str(&format!("\nvector {}\n", vector.name));
+            for op in &vector.ops {
+                let line = match op {
+                    RistrettoOp::Add {
+                        left,
+                        right,
+                        expected,
+                    } => format!(
+                        "add {} {} {}",
+                        encode_hex(left),
+                        encode_hex(right),
+                        encode_hex(expected)
+                    ),
+                    RistrettoOp::Subtract {
+                        left,
+                        right,
+                        expected,
+                    } => format!(
+                        "subtract {} {} {}",
+                        encode_hex(left),
+                        encode_hex(right),
+                        encode_hex(expected)
+                    ),
+                    RistrettoOp::Multiply {
+                        scalar,
+                        point,
+                        expected,
+                    } => format!(
+                        "multiply {} {} {}",
+                        encode_hex(scalar),
+                        encode_hex(point),
+                        encode_hex(expected)
+                    ),
+                    RistrettoOp::MultiscalarMultiply {
+                        scalars,
+                        points,
+                        expected,
+                    } => format!(
+                        "multiscalar-multiply {} {} {}",
+                        encode_hex_list(scalars),
+                        encode_hex_list(points),
+                        encode_hex(expected)
+                    ),
+                };
+                text.push_str(&line);
+                text.push('\n');
+            }
+            text.push_str("end\n");
+        }
+        text
+    }
+}
+
+fn read_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if bytes.len() < len {
+        return Err("Vector bytes are truncated".to_string());
+    }
+    let (head, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(head)
+}
+
+fn read_array<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], String> {
+    Ok(read_bytes(bytes, N)?.try_into().unwrap())
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Result<u32, String> {
+    read_array(bytes).map(u32::from_le_bytes)
+}
+
+#[cfg(not(target_os = "solana"))]
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(not(target_os = "solana"))]
+fn encode_hex_list(list: &[[u8; 32]]) -> String {
+    list.iter()
+        .map(|bytes| encode_hex(bytes))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(not(target_os = "solana"))]
+fn decode_hex(hex: &str) -> Result<[u8; 32], String> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .filter(|pair| pair.len() == 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("Invalid hex `{hex}`"))
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .map_err(|_| format!("Hex `{hex}` has invalid length"))
+}
+
+#[cfg(not(target_os = "solana"))]
+fn decode_hex_list(hex: &str) -> Result<Vec<[u8; 32]>, String> {
+    hex.split(',').map(decode_hex).collect()
+}
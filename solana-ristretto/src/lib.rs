@@ -1,2 +1,3 @@
+pub mod kat;
 pub mod ristretto;
 pub mod scalar;
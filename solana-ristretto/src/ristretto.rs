@@ -7,7 +7,10 @@ use curve25519_dalek::{
 use solana_zk_token_sdk::curve25519::ristretto::{
     add_ristretto, multiply_ristretto, multiscalar_multiply_ristretto, subtract_ristretto,
 };
-use {crate::scalar::Scalar, solana_zk_token_sdk::curve25519::ristretto::PodRistrettoPoint};
+use {
+    crate::scalar::Scalar,
+    solana_zk_token_sdk::curve25519::ristretto::{validate_ristretto, PodRistrettoPoint},
+};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct RistrettoPoint(pub(crate) PodRistrettoPoint);
@@ -23,6 +26,11 @@ impl RistrettoPoint {
             .map_err(|_| "Point bytes have invalid length".to_string())?;
         Ok(Self(PodRistrettoPoint(bytes)))
     }
+
+    /// Check that the bytes are a valid encoding of a Ristretto point.
+    pub fn is_valid(&self) -> bool {
+        validate_ristretto(&self.0)
+    }
 }
 
 impl RistrettoPoint {
@@ -1,6 +1,7 @@
-#[cfg(not(target_os = "solana"))]
-use curve25519_dalek::scalar::Scalar as DalekScalar;
-use solana_zk_token_sdk::curve25519::scalar::PodScalar;
+use {
+    curve25519_dalek::scalar::Scalar as DalekScalar,
+    solana_zk_token_sdk::curve25519::scalar::PodScalar,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Scalar(pub(crate) PodScalar);
@@ -16,32 +17,122 @@ impl Scalar {
             .map_err(|_| "Scalar bytes have invalid length".to_string())?;
         Ok(Self(PodScalar(bytes)))
     }
+
+    /// Check that the bytes are the canonical encoding of a scalar, i.e. reduced modulo the group
+    /// order.
+    pub fn is_canonical(&self) -> bool {
+        DalekScalar::try_from(*self).is_ok()
+    }
+
+    /// Reduce the scalar modulo the group order, which makes a non-canonical scalar canonical.
+    pub fn reduce(self) -> Self {
+        DalekScalar::from_bytes_mod_order(self.to_bytes()).into()
+    }
+
+    /// Reduce a 64-byte little-endian integer modulo the group order.
+    ///
+    /// This is used to derive uniformly distributed scalars from transcript challenges.
+    pub fn from_bytes_mod_order_wide(bytes: &[u8; 64]) -> Self {
+        DalekScalar::from_bytes_mod_order_wide(bytes).into()
+    }
+}
+
+impl From<u64> for Scalar {
+    fn from(x: u64) -> Self {
+        DalekScalar::from(x).into()
+    }
 }
 
-#[cfg(not(target_os = "solana"))]
+// There are no syscalls for scalar arithmetic, so the dalek implementation is used both on-chain
+// and off-chain.
 impl Scalar {
     pub fn add_scalar(self, other: Scalar) -> Result<Self, String> {
-        let left: DalekScalar = self.0.try_into().unwrap();
-        let right: DalekScalar = other.0.try_into().unwrap();
+        let left: DalekScalar = self.try_into()?;
+        let right: DalekScalar = other.try_into()?;
         let result = left + right;
-        Ok(Self(result.into()))
+        Ok(result.into())
     }
 
     pub fn subtract_scalar(self, other: Scalar) -> Result<Self, String> {
-        let left: DalekScalar = self.0.try_into().unwrap();
-        let right: DalekScalar = other.0.try_into().unwrap();
+        let left: DalekScalar = self.try_into()?;
+        let right: DalekScalar = other.try_into()?;
         let result = left - right;
-        Ok(Self(result.into()))
+        Ok(result.into())
     }
 
     pub fn multiply_scalar(self, other: Scalar) -> Result<Self, String> {
-        let left: DalekScalar = self.0.try_into().unwrap();
-        let right: DalekScalar = other.0.try_into().unwrap();
+        let left: DalekScalar = self.try_into()?;
+        let right: DalekScalar = other.try_into()?;
         let result = left * right;
-        Ok(Self(result.into()))
+        Ok(result.into())
+    }
+
+    pub fn negate(self) -> Result<Self, String> {
+        let scalar: DalekScalar = self.try_into()?;
+        Ok((-scalar).into())
+    }
+
+    /// Multiply two scalars after reducing them modulo the group order, which cannot fail.
+    pub fn multiply_reduced(self, other: Scalar) -> Self {
+        let left = DalekScalar::from_bytes_mod_order(self.to_bytes());
+        let right = DalekScalar::from_bytes_mod_order(other.to_bytes());
+        (left * right).into()
+    }
+
+    /// Negate a scalar after reducing it modulo the group order, which cannot fail.
+    pub fn negate_reduced(self) -> Self {
+        (-DalekScalar::from_bytes_mod_order(self.to_bytes())).into()
+    }
+
+    /// Compute the multiplicative inverse of a non-zero scalar.
+    pub fn invert(self) -> Result<Self, String> {
+        if self == Self::zero() {
+            return Err("Zero scalar has no inverse".to_string());
+        }
+        let scalar: DalekScalar = self.try_into()?;
+        Ok(scalar.invert().into())
+    }
+
+    /// Invert every scalar in place with a single inversion.
+    ///
+    /// Inversion is by far the most expensive scalar operation on-chain, so all inverses that a
+    /// verifier needs should be computed in one batch. Returns the inverse of the product of all
+    /// scalars.
+    pub fn batch_invert(scalars: &mut [Scalar]) -> Result<Self, String> {
+        if scalars.iter().any(|scalar| *scalar == Self::zero()) {
+            return Err("Zero scalar has no inverse".to_string());
+        }
+        let mut dalek_scalars = scalars
+            .iter()
+            .map(|scalar| DalekScalar::try_from(*scalar))
+            .collect::<Result<Vec<_>, _>>()?;
+        let product_inverse = DalekScalar::batch_invert(&mut dalek_scalars);
+        for (scalar, inverse) in scalars.iter_mut().zip(dalek_scalars) {
+            *scalar = inverse.into();
+        }
+        Ok(product_inverse.into())
     }
 
     pub fn zero() -> Self {
         Scalar(PodScalar([0u8; 32]))
     }
+
+    pub fn one() -> Self {
+        Self::from(1)
+    }
+}
+
+impl From<DalekScalar> for Scalar {
+    fn from(scalar: DalekScalar) -> Self {
+        Self(PodScalar(scalar.to_bytes()))
+    }
+}
+
+impl TryFrom<Scalar> for DalekScalar {
+    type Error = String;
+
+    fn try_from(scalar: Scalar) -> Result<Self, Self::Error> {
+        DalekScalar::from_canonical_bytes(scalar.to_bytes())
+            .ok_or_else(|| "Scalar bytes are not canonical".to_string())
+    }
 }
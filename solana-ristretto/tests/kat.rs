@@ -0,0 +1,44 @@
+use solana_ristretto::kat::{RistrettoOp, RistrettoVector, KAT_VERSION};
+
+const VECTORS: &str = include_str!("vectors/ristretto-v1.kat");
+
+fn vectors() -> Vec<RistrettoVector> {
+    RistrettoVector::parse_file(VECTORS).unwrap()
+}
+
+#[test]
+fn test_vector_file_matches_version() {
+    assert_eq!(KAT_VERSION, 1);
+    assert!(!vectors().is_empty());
+}
+
+#[test]
+fn test_replay_vectors() {
+    for vector in vectors() {
+        vector.replay().unwrap();
+    }
+}
+
+#[test]
+fn test_vector_bytes_round_trip() {
+    for vector in vectors() {
+        let decoded = RistrettoVector::from_bytes(&vector.to_bytes()).unwrap();
+        assert_eq!(decoded, vector);
+    }
+}
+
+#[test]
+fn test_vector_file_round_trip() {
+    let vectors = vectors();
+    let text = RistrettoVector::format_file(&vectors);
+    assert_eq!(RistrettoVector::parse_file(&text).unwrap(), vectors);
+}
+
+#[test]
+fn test_replay_detects_modified_vector() {
+    let mut vector = vectors().remove(0);
+    if let RistrettoOp::Add { expected, .. } = &mut vector.ops[0] {
+        *expected = [0; 32];
+    }
+    assert!(vector.replay().is_err());
+}
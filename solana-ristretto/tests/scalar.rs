@@ -0,0 +1,31 @@
+use solana_ristretto::scalar::Scalar;
+
+/// The group order plus one, which is the non-canonical encoding of one
+const ORDER_PLUS_ONE: [u8; 32] = [
+    0xee, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+#[test]
+fn test_reduce() {
+    let scalar = Scalar::from_bytes(&ORDER_PLUS_ONE).unwrap();
+    assert!(!scalar.is_canonical());
+    assert_eq!(scalar.reduce(), Scalar::one());
+    assert_eq!(Scalar::from(7u64).reduce(), Scalar::from(7u64));
+}
+
+#[test]
+fn test_reduced_arithmetic() {
+    let non_canonical_one = Scalar::from_bytes(&ORDER_PLUS_ONE).unwrap();
+    assert!(non_canonical_one
+        .multiply_scalar(Scalar::from(3u64))
+        .is_err());
+    assert_eq!(
+        non_canonical_one.multiply_reduced(Scalar::from(3u64)),
+        Scalar::from(3u64)
+    );
+    assert_eq!(
+        non_canonical_one.negate_reduced(),
+        Scalar::one().negate().unwrap()
+    );
+}
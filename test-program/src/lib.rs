@@ -1,13 +1,37 @@
-use solana_merlin::Transcript;
+use solana_bulletproofs::{
+    batched_range_proof::{
+        BatchedRangeProofU128, BatchedRangeProofU128View, BatchedRangeProofU256,
+        BatchedRangeProofU256View, BatchedRangeProofU64, BatchedRangeProofU64View,
+    },
+    encoding::{ProofBytes, ProofType},
+    generators::{BulletproofGens, PedersenGens},
+    inner_product_proof::{InnerProductProof, InnerProductProofView},
+    r1cs::{gadgets, ConstraintSystem, R1CSProof, R1CSProofView, Variable, Verifier},
+    range_proof::{interval_bit_size, RangeProof, RangeProofView},
+};
+use solana_merlin::{
+    kat::TranscriptVector, DomainSeparator, ExecutionContext, Transcript, TRANSCRIPT_LEN,
+};
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, instruction::Instruction, msg,
-    program_error::ProgramError, pubkey::Pubkey,
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    log::sol_log_compute_units,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
 };
-use solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar};
+use solana_ristretto::{kat::RistrettoVector, ristretto::RistrettoPoint, scalar::Scalar};
 use std::mem::size_of;
 
 solana_program::declare_id!("Mer1in1111111111111111111111111111111111111");
 
+/// The maximum number of slots by which the recent slot of a `TranscriptExecutionContext`
+/// instruction may lag behind the current slot
+pub const MAX_SLOT_AGE: u64 = 150;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum BoomerangTestInstructions {
     /// Test transcript
@@ -36,11 +60,154 @@ pub enum BoomerangTestInstructions {
         scalars: Vec<Scalar>,
         points: Vec<RistrettoPoint>,
     },
+
+    /// Measure the compute units consumed by a transcript
+    TranscriptComputeUnits { num_appends: u32 },
+
+    /// Test storing a transcript in an account
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The transcript account
+    TranscriptStore { message: Vec<u8> },
+
+    /// Test resuming a transcript that is stored in an account
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The transcript account
+    TranscriptResume {
+        message: Vec<u8>,
+        expected_challenge: [u8; 32],
+    },
+
+    /// Test transcript with tracing enabled
+    TranscriptTrace { message: Vec<u8>, append_u64: u64 },
+
+    /// Test transcript domain separation with runtime context fields
+    TranscriptDomainSeparator {
+        proof_index: u64,
+        expected_challenge: [u8; 32],
+    },
+
+    /// Test binding a transcript to the execution context of the instruction, including a recent
+    /// slot that is at most `MAX_SLOT_AGE` slots older than the slot of the `Clock` sysvar
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[signer]` The signer that the proof is bound to
+    ///   1. ..`1+N` `[]` The accounts that the proof acts on
+    TranscriptExecutionContext {
+        recent_slot: u64,
+        expected_challenge: [u8; 32],
+    },
+
+    /// Test appending a message that spans the data of several accounts
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. ..`N` `[]` The accounts whose data is appended as a single message
+    TranscriptAccountData { expected_challenge: [u8; 32] },
+
+    /// Test replaying a transcript known-answer vector
+    KnownAnswerTranscript { vector: Vec<u8> },
+
+    /// Test replaying a Ristretto known-answer vector
+    KnownAnswerRistretto { vector: Vec<u8> },
+
+    /// Test a Pedersen commitment with the default generators
+    PedersenCommit {
+        value: Scalar,
+        blinding: Scalar,
+        expected_commitment: RistrettoPoint,
+    },
+
+    /// Test deriving Pedersen generators from a label
+    PedersenGensFromLabel {
+        label: Vec<u8>,
+        expected_gens: PedersenGens,
+    },
+
+    /// Test deriving Bulletproof generators
+    BulletproofGens {
+        gens_capacity: u32,
+        party_capacity: u32,
+        expected_digest: [u8; 32],
+    },
+
+    /// Test verifying an inner product proof with the generators `BulletproofGens::new(n, 1)`
+    /// and uniform generator factors
+    InnerProductProof {
+        n: u32,
+        commitment: RistrettoPoint,
+        product_base: RistrettoPoint,
+        proof: Vec<u8>,
+    },
+
+    /// Test verifying a range proof with the default Pedersen generators and
+    /// `BulletproofGens::new(n, 1)`
+    RangeProofSingle {
+        n: u32,
+        commitment: RistrettoPoint,
+        proof: Vec<u8>,
+    },
+
+    /// Test verifying an aggregated range proof with the default Pedersen generators and
+    /// `BulletproofGens::new(n, m)`, where `m` is the number of commitments rounded up to a power
+    /// of two
+    RangeProofMultiple {
+        n: u32,
+        commitments: Vec<RistrettoPoint>,
+        proof: Vec<u8>,
+    },
+
+    /// Test verifying a batched range proof with the default Pedersen generators and
+    /// `BulletproofGens::new(total, 1)`, where the total of 64, 128, or 256 bits is read from the
+    /// type tag of the serialized proof
+    BatchedRangeProof {
+        commitments: Vec<(RistrettoPoint, u32)>,
+        proof: Vec<u8>,
+    },
+
+    /// Test batch verification of aggregated range proofs with the default Pedersen generators
+    /// and `BulletproofGens::new(n, m)`, where `m` is the largest number of commitments of a
+    /// proof rounded up to a power of two
+    RangeProofBatch {
+        n: u32,
+        proofs: Vec<(Vec<RistrettoPoint>, Vec<u8>)>,
+    },
+
+    /// Test verifying a range proof for the interval `[a, b)` with the default Pedersen
+    /// generators and `BulletproofGens::new(n, 2)`, where `n` is the bit size of the interval
+    RangeProofInterval {
+        a: u64,
+        b: u64,
+        commitment: RistrettoPoint,
+        proof: Vec<u8>,
+    },
+
+    /// Test verifying an R1CS proof that the product of all committed values but the last equals
+    /// the last, with the default Pedersen generators and `BulletproofGens::new(n, 1)`, where `n`
+    /// is the number of multipliers rounded up to a power of two
+    R1CSProof {
+        commitments: Vec<RistrettoPoint>,
+        proof: Vec<u8>,
+    },
+
+    /// Test verifying an R1CS proof that the committed `outputs` are a shuffle of the committed
+    /// `inputs`, with the default Pedersen generators and `BulletproofGens::new(n, 1)`, where `n`
+    /// is the number of multipliers rounded up to a power of two
+    R1CSShuffleProof {
+        inputs: Vec<RistrettoPoint>,
+        outputs: Vec<RistrettoPoint>,
+        proof: Vec<u8>,
+    },
 }
 
 impl BoomerangTestInstructions {
     pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
         const U32_BYTES: usize = 4;
+        const U64_BYTES: usize = 8;
         const POINT_BYTES: usize = 32;
         const SCALAR_BYTES: usize = 32;
 
@@ -150,6 +317,395 @@ impl BoomerangTestInstructions {
 
                 Self::MultiscalarMultiplyRistretto { scalars, points }
             }
+            5 => {
+                let num_appends = rest
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+
+                Self::TranscriptComputeUnits { num_appends }
+            }
+            6 => {
+                let (length, message) = rest.split_at(U32_BYTES);
+                let length = u32::from_le_bytes(
+                    length
+                        .try_into()
+                        .map_err(|_| ProgramError::InvalidInstructionData)?,
+                ) as usize;
+
+                if message.len() != length {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+
+                Self::TranscriptStore {
+                    message: message.to_vec(),
+                }
+            }
+            7 => {
+                let (length, rest) = rest.split_at(U32_BYTES);
+                let length = u32::from_le_bytes(
+                    length
+                        .try_into()
+                        .map_err(|_| ProgramError::InvalidInstructionData)?,
+                ) as usize;
+                let (message, expected_challenge) = rest.split_at(length);
+                let expected_challenge = expected_challenge
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::TranscriptResume {
+                    message: message.to_vec(),
+                    expected_challenge,
+                }
+            }
+            8 => {
+                let (length, rest) = rest.split_at(U32_BYTES);
+                let length = u32::from_le_bytes(
+                    length
+                        .try_into()
+                        .map_err(|_| ProgramError::InvalidInstructionData)?,
+                ) as usize;
+                let (message, append_u64) = rest.split_at(length);
+                let append_u64 = append_u64
+                    .try_into()
+                    .ok()
+                    .map(u64::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+
+                Self::TranscriptTrace {
+                    message: message.to_vec(),
+                    append_u64,
+                }
+            }
+            9 => {
+                let (proof_index, expected_challenge) = rest.split_at(U64_BYTES);
+                let proof_index = proof_index
+                    .try_into()
+                    .ok()
+                    .map(u64::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let expected_challenge = expected_challenge
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::TranscriptDomainSeparator {
+                    proof_index,
+                    expected_challenge,
+                }
+            }
+            10 => {
+                let (recent_slot, expected_challenge) = rest.split_at(U64_BYTES);
+                let recent_slot = recent_slot
+                    .try_into()
+                    .ok()
+                    .map(u64::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let expected_challenge = expected_challenge
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::TranscriptExecutionContext {
+                    recent_slot,
+                    expected_challenge,
+                }
+            }
+            11 => {
+                let expected_challenge = rest
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::TranscriptAccountData { expected_challenge }
+            }
+            12 => Self::KnownAnswerTranscript {
+                vector: rest.to_vec(),
+            },
+            13 => Self::KnownAnswerRistretto {
+                vector: rest.to_vec(),
+            },
+            14 => {
+                let (value, rest) = rest.split_at(SCALAR_BYTES);
+                let (blinding, expected_commitment) = rest.split_at(SCALAR_BYTES);
+
+                let value =
+                    Scalar::from_bytes(value).map_err(|_| ProgramError::InvalidInstructionData)?;
+                let blinding = Scalar::from_bytes(blinding)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+                let expected_commitment = RistrettoPoint::from_bytes(expected_commitment)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::PedersenCommit {
+                    value,
+                    blinding,
+                    expected_commitment,
+                }
+            }
+            15 => {
+                let (expected_base, rest) = rest.split_at(POINT_BYTES);
+                let (expected_blinding_base, label) = rest.split_at(POINT_BYTES);
+
+                let expected_base = RistrettoPoint::from_bytes(expected_base)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+                let expected_blinding_base = RistrettoPoint::from_bytes(expected_blinding_base)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::PedersenGensFromLabel {
+                    label: label.to_vec(),
+                    expected_gens: PedersenGens::new(expected_base, expected_blinding_base)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?,
+                }
+            }
+            16 => {
+                let (gens_capacity, rest) = rest.split_at(U32_BYTES);
+                let (party_capacity, expected_digest) = rest.split_at(U32_BYTES);
+
+                let gens_capacity = gens_capacity
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let party_capacity = party_capacity
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let expected_digest = expected_digest
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::BulletproofGens {
+                    gens_capacity,
+                    party_capacity,
+                    expected_digest,
+                }
+            }
+            17 => {
+                let (n, rest) = rest.split_at(U32_BYTES);
+                let (commitment, rest) = rest.split_at(POINT_BYTES);
+                let (product_base, proof) = rest.split_at(POINT_BYTES);
+
+                let n = n
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let commitment = RistrettoPoint::from_bytes(commitment)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+                let product_base = RistrettoPoint::from_bytes(product_base)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::InnerProductProof {
+                    n,
+                    commitment,
+                    product_base,
+                    proof: proof.to_vec(),
+                }
+            }
+            18 => {
+                let (n, rest) = rest.split_at(U32_BYTES);
+                let (commitment, proof) = rest.split_at(POINT_BYTES);
+
+                let n = n
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let commitment = RistrettoPoint::from_bytes(commitment)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::RangeProofSingle {
+                    n,
+                    commitment,
+                    proof: proof.to_vec(),
+                }
+            }
+            19 => {
+                let (n, rest) = rest.split_at(U32_BYTES);
+                let (num_commitments, mut rest) = rest.split_at(U32_BYTES);
+
+                let n = n
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let num_commitments = num_commitments
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+
+                let mut commitments = Vec::with_capacity(num_commitments as usize);
+                for _ in 0..num_commitments {
+                    let (commitment, remaining) = rest.split_at(POINT_BYTES);
+                    let commitment = RistrettoPoint::from_bytes(commitment)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+                    commitments.push(commitment);
+                    rest = remaining;
+                }
+
+                Self::RangeProofMultiple {
+                    n,
+                    commitments,
+                    proof: rest.to_vec(),
+                }
+            }
+            20 => {
+                let (num_commitments, mut rest) = rest.split_at(U32_BYTES);
+
+                let num_commitments = num_commitments
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+
+                let mut commitments = Vec::with_capacity(num_commitments as usize);
+                for _ in 0..num_commitments {
+                    let (commitment, remaining) = rest.split_at(POINT_BYTES);
+                    let (bit_length, remaining) = remaining.split_at(U32_BYTES);
+                    let commitment = RistrettoPoint::from_bytes(commitment)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+                    let bit_length = bit_length
+                        .try_into()
+                        .ok()
+                        .map(u32::from_le_bytes)
+                        .ok_or(ProgramError::InvalidInstructionData)?;
+                    commitments.push((commitment, bit_length));
+                    rest = remaining;
+                }
+
+                Self::BatchedRangeProof {
+                    commitments,
+                    proof: rest.to_vec(),
+                }
+            }
+            21 => {
+                let (n, rest) = rest.split_at(U32_BYTES);
+                let (num_proofs, mut rest) = rest.split_at(U32_BYTES);
+
+                let n = n
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let num_proofs = num_proofs
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+
+                let mut proofs = Vec::with_capacity(num_proofs as usize);
+                for _ in 0..num_proofs {
+                    let (num_commitments, remaining) = rest.split_at(U32_BYTES);
+                    let num_commitments = num_commitments
+                        .try_into()
+                        .ok()
+                        .map(u32::from_le_bytes)
+                        .ok_or(ProgramError::InvalidInstructionData)?;
+                    rest = remaining;
+
+                    let mut commitments = Vec::with_capacity(num_commitments as usize);
+                    for _ in 0..num_commitments {
+                        let (commitment, remaining) = rest.split_at(POINT_BYTES);
+                        let commitment = RistrettoPoint::from_bytes(commitment)
+                            .map_err(|_| ProgramError::InvalidInstructionData)?;
+                        commitments.push(commitment);
+                        rest = remaining;
+                    }
+
+                    let (proof_len, remaining) = rest.split_at(U32_BYTES);
+                    let proof_len = proof_len
+                        .try_into()
+                        .ok()
+                        .map(u32::from_le_bytes)
+                        .ok_or(ProgramError::InvalidInstructionData)?;
+                    let (proof, remaining) = remaining.split_at(proof_len as usize);
+                    proofs.push((commitments, proof.to_vec()));
+                    rest = remaining;
+                }
+
+                Self::RangeProofBatch { n, proofs }
+            }
+            22 => {
+                let (a, rest) = rest.split_at(U64_BYTES);
+                let (b, rest) = rest.split_at(U64_BYTES);
+                let (commitment, proof) = rest.split_at(POINT_BYTES);
+
+                let a = a
+                    .try_into()
+                    .ok()
+                    .map(u64::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let b = b
+                    .try_into()
+                    .ok()
+                    .map(u64::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let commitment = RistrettoPoint::from_bytes(commitment)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Self::RangeProofInterval {
+                    a,
+                    b,
+                    commitment,
+                    proof: proof.to_vec(),
+                }
+            }
+            23 => {
+                let (num_commitments, mut rest) = rest.split_at(U32_BYTES);
+
+                let num_commitments = num_commitments
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+
+                let mut commitments = Vec::with_capacity(num_commitments as usize);
+                for _ in 0..num_commitments {
+                    let (commitment, remaining) = rest.split_at(POINT_BYTES);
+                    let commitment = RistrettoPoint::from_bytes(commitment)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+                    commitments.push(commitment);
+                    rest = remaining;
+                }
+
+                Self::R1CSProof {
+                    commitments,
+                    proof: rest.to_vec(),
+                }
+            }
+            24 => {
+                let (k, rest) = rest.split_at(U32_BYTES);
+
+                let k = k
+                    .try_into()
+                    .ok()
+                    .map(u32::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)? as usize;
+
+                // the inputs and the outputs must fit into the instruction data before any of them
+                // is allocated
+                let commitments_bytes_length = k
+                    .checked_mul(2 * POINT_BYTES)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                if rest.len() < commitments_bytes_length {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                let (commitments_bytes, rest) = rest.split_at(commitments_bytes_length);
+
+                let mut commitments = Vec::with_capacity(2 * k);
+                for commitment_bytes in commitments_bytes.chunks(POINT_BYTES) {
+                    let commitment = RistrettoPoint::from_bytes(commitment_bytes)
+                        .map_err(|_| ProgramError::InvalidInstructionData)?;
+                    commitments.push(commitment);
+                }
+                let outputs = commitments.split_off(k);
+
+                Self::R1CSShuffleProof {
+                    inputs: commitments,
+                    outputs,
+                    proof: rest.to_vec(),
+                }
+            }
 
             _ => return Err(ProgramError::InvalidInstructionData),
         })
@@ -199,39 +755,212 @@ impl BoomerangTestInstructions {
                     .iter()
                     .for_each(|point| buf.extend_from_slice(&point.to_bytes()));
             }
-        }
-        buf
-    }
-}
-
-/// Create a `BoomerangTestInstructions::Transcript` instruction
-pub fn transcript(message: Vec<u8>, append_u64: u64) -> Instruction {
-    Instruction {
-        program_id: id(),
-        accounts: vec![],
-        data: BoomerangTestInstructions::Transcript {
-            message,
-            append_u64,
-        }
-        .pack(),
-    }
-}
-
-/// Create a `BoomerangTestInstructions::AddRistretto` instruction
-pub fn add_ristretto(left_point: &RistrettoPoint, right_point: &RistrettoPoint) -> Instruction {
-    Instruction {
-        program_id: id(),
-        accounts: vec![],
-        data: BoomerangTestInstructions::AddRistretto {
-            left_point: *left_point,
-            right_point: *right_point,
-        }
-        .pack(),
-    }
-}
-
-/// Create a `BoomerangTestInstructions::SubtractRistretto` instruction
-pub fn subtract_ristretto(
+            Self::TranscriptComputeUnits { num_appends } => {
+                buf.push(5);
+                buf.extend_from_slice(&num_appends.to_le_bytes());
+            }
+            Self::TranscriptStore { message } => {
+                buf.push(6);
+                buf.extend_from_slice(&(message.len() as u32).to_le_bytes());
+                buf.extend_from_slice(message);
+            }
+            Self::TranscriptResume {
+                message,
+                expected_challenge,
+            } => {
+                buf.push(7);
+                buf.extend_from_slice(&(message.len() as u32).to_le_bytes());
+                buf.extend_from_slice(message);
+                buf.extend_from_slice(expected_challenge);
+            }
+            Self::TranscriptTrace {
+                message,
+                append_u64,
+            } => {
+                buf.push(8);
+                buf.extend_from_slice(&(message.len() as u32).to_le_bytes());
+                buf.extend_from_slice(message);
+                buf.extend_from_slice(&append_u64.to_le_bytes());
+            }
+            Self::TranscriptDomainSeparator {
+                proof_index,
+                expected_challenge,
+            } => {
+                buf.push(9);
+                buf.extend_from_slice(&proof_index.to_le_bytes());
+                buf.extend_from_slice(expected_challenge);
+            }
+            Self::TranscriptExecutionContext {
+                recent_slot,
+                expected_challenge,
+            } => {
+                buf.push(10);
+                buf.extend_from_slice(&recent_slot.to_le_bytes());
+                buf.extend_from_slice(expected_challenge);
+            }
+            Self::TranscriptAccountData { expected_challenge } => {
+                buf.push(11);
+                buf.extend_from_slice(expected_challenge);
+            }
+            Self::KnownAnswerTranscript { vector } => {
+                buf.push(12);
+                buf.extend_from_slice(vector);
+            }
+            Self::KnownAnswerRistretto { vector } => {
+                buf.push(13);
+                buf.extend_from_slice(vector);
+            }
+            Self::PedersenCommit {
+                value,
+                blinding,
+                expected_commitment,
+            } => {
+                buf.push(14);
+                buf.extend_from_slice(&value.to_bytes());
+                buf.extend_from_slice(&blinding.to_bytes());
+                buf.extend_from_slice(&expected_commitment.to_bytes());
+            }
+            Self::PedersenGensFromLabel {
+                label,
+                expected_gens,
+            } => {
+                buf.push(15);
+                buf.extend_from_slice(&expected_gens.B().to_bytes());
+                buf.extend_from_slice(&expected_gens.B_blinding().to_bytes());
+                buf.extend_from_slice(label);
+            }
+            Self::BulletproofGens {
+                gens_capacity,
+                party_capacity,
+                expected_digest,
+            } => {
+                buf.push(16);
+                buf.extend_from_slice(&gens_capacity.to_le_bytes());
+                buf.extend_from_slice(&party_capacity.to_le_bytes());
+                buf.extend_from_slice(expected_digest);
+            }
+            Self::InnerProductProof {
+                n,
+                commitment,
+                product_base,
+                proof,
+            } => {
+                buf.push(17);
+                buf.extend_from_slice(&n.to_le_bytes());
+                buf.extend_from_slice(&commitment.to_bytes());
+                buf.extend_from_slice(&product_base.to_bytes());
+                buf.extend_from_slice(proof);
+            }
+            Self::RangeProofSingle {
+                n,
+                commitment,
+                proof,
+            } => {
+                buf.push(18);
+                buf.extend_from_slice(&n.to_le_bytes());
+                buf.extend_from_slice(&commitment.to_bytes());
+                buf.extend_from_slice(proof);
+            }
+            Self::RangeProofMultiple {
+                n,
+                commitments,
+                proof,
+            } => {
+                buf.push(19);
+                buf.extend_from_slice(&n.to_le_bytes());
+                buf.extend_from_slice(&(commitments.len() as u32).to_le_bytes());
+                for commitment in commitments {
+                    buf.extend_from_slice(&commitment.to_bytes());
+                }
+                buf.extend_from_slice(proof);
+            }
+            Self::BatchedRangeProof { commitments, proof } => {
+                buf.push(20);
+                buf.extend_from_slice(&(commitments.len() as u32).to_le_bytes());
+                for (commitment, bit_length) in commitments {
+                    buf.extend_from_slice(&commitment.to_bytes());
+                    buf.extend_from_slice(&bit_length.to_le_bytes());
+                }
+                buf.extend_from_slice(proof);
+            }
+            Self::RangeProofBatch { n, proofs } => {
+                buf.push(21);
+                buf.extend_from_slice(&n.to_le_bytes());
+                buf.extend_from_slice(&(proofs.len() as u32).to_le_bytes());
+                for (commitments, proof) in proofs {
+                    buf.extend_from_slice(&(commitments.len() as u32).to_le_bytes());
+                    for commitment in commitments {
+                        buf.extend_from_slice(&commitment.to_bytes());
+                    }
+                    buf.extend_from_slice(&(proof.len() as u32).to_le_bytes());
+                    buf.extend_from_slice(proof);
+                }
+            }
+            Self::RangeProofInterval {
+                a,
+                b,
+                commitment,
+                proof,
+            } => {
+                buf.push(22);
+                buf.extend_from_slice(&a.to_le_bytes());
+                buf.extend_from_slice(&b.to_le_bytes());
+                buf.extend_from_slice(&commitment.to_bytes());
+                buf.extend_from_slice(proof);
+            }
+            Self::R1CSProof { commitments, proof } => {
+                buf.push(23);
+                buf.extend_from_slice(&(commitments.len() as u32).to_le_bytes());
+                for commitment in commitments {
+                    buf.extend_from_slice(&commitment.to_bytes());
+                }
+                buf.extend_from_slice(proof);
+            }
+            Self::R1CSShuffleProof {
+                inputs,
+                outputs,
+                proof,
+            } => {
+                buf.push(24);
+                buf.extend_from_slice(&(inputs.len() as u32).to_le_bytes());
+                for commitment in inputs.iter().chain(outputs) {
+                    buf.extend_from_slice(&commitment.to_bytes());
+                }
+                buf.extend_from_slice(proof);
+            }
+        }
+        buf
+    }
+}
+
+/// Create a `BoomerangTestInstructions::Transcript` instruction
+pub fn transcript(message: Vec<u8>, append_u64: u64) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::Transcript {
+            message,
+            append_u64,
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::AddRistretto` instruction
+pub fn add_ristretto(left_point: &RistrettoPoint, right_point: &RistrettoPoint) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::AddRistretto {
+            left_point: *left_point,
+            right_point: *right_point,
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::SubtractRistretto` instruction
+pub fn subtract_ristretto(
     left_point: &RistrettoPoint,
     right_point: &RistrettoPoint,
 ) -> Instruction {
@@ -271,10 +1000,376 @@ pub fn multiscalar_multiply_ristretto(
     }
 }
 
+/// Create a `BoomerangTestInstructions::TranscriptComputeUnits` instruction
+pub fn transcript_compute_units(num_appends: u32) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::TranscriptComputeUnits { num_appends }.pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::TranscriptStore` instruction
+pub fn transcript_store(transcript_account: &Pubkey, message: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![AccountMeta::new(*transcript_account, false)],
+        data: BoomerangTestInstructions::TranscriptStore { message }.pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::TranscriptResume` instruction
+pub fn transcript_resume(
+    transcript_account: &Pubkey,
+    message: Vec<u8>,
+    expected_challenge: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![AccountMeta::new(*transcript_account, false)],
+        data: BoomerangTestInstructions::TranscriptResume {
+            message,
+            expected_challenge,
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::TranscriptTrace` instruction
+pub fn transcript_trace(message: Vec<u8>, append_u64: u64) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::TranscriptTrace {
+            message,
+            append_u64,
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::TranscriptDomainSeparator` instruction
+pub fn transcript_domain_separator(proof_index: u64, expected_challenge: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::TranscriptDomainSeparator {
+            proof_index,
+            expected_challenge,
+        }
+        .pack(),
+    }
+}
+
+/// The transcript that is used by the `TranscriptDomainSeparator` instruction
+pub fn domain_separated_transcript(program_id: &Pubkey, proof_index: u64) -> Transcript {
+    let proof_index = proof_index.to_le_bytes();
+    let domain = DomainSeparator::new(b"boomerang-test", 1)
+        .with_context(b"program-id", program_id.as_ref())
+        .with_context(b"proof-index", &proof_index);
+    Transcript::new_with_domain(&domain)
+}
+
+/// Create a `BoomerangTestInstructions::TranscriptExecutionContext` instruction
+pub fn transcript_execution_context(
+    signer: &Pubkey,
+    target_accounts: &[Pubkey],
+    recent_slot: u64,
+    expected_challenge: [u8; 32],
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(*signer, true)];
+    accounts.extend(
+        target_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+
+    Instruction {
+        program_id: id(),
+        accounts,
+        data: BoomerangTestInstructions::TranscriptExecutionContext {
+            recent_slot,
+            expected_challenge,
+        }
+        .pack(),
+    }
+}
+
+/// The transcript that is used by the `TranscriptExecutionContext` instruction
+pub fn execution_context_transcript(context: &ExecutionContext) -> Transcript {
+    let mut transcript = Transcript::new(b"boomerang-test");
+    transcript.append_execution_context(context);
+    transcript
+}
+
+/// Create a `BoomerangTestInstructions::TranscriptAccountData` instruction
+pub fn transcript_account_data(
+    data_accounts: &[Pubkey],
+    expected_challenge: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: data_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false))
+            .collect(),
+        data: BoomerangTestInstructions::TranscriptAccountData { expected_challenge }.pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::KnownAnswerTranscript` instruction
+pub fn known_answer_transcript(vector: &TranscriptVector) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::KnownAnswerTranscript {
+            vector: vector.to_bytes(),
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::KnownAnswerRistretto` instruction
+pub fn known_answer_ristretto(vector: &RistrettoVector) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::KnownAnswerRistretto {
+            vector: vector.to_bytes(),
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::PedersenCommit` instruction
+pub fn pedersen_commit(
+    value: Scalar,
+    blinding: Scalar,
+    expected_commitment: RistrettoPoint,
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::PedersenCommit {
+            value,
+            blinding,
+            expected_commitment,
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::BulletproofGens` instruction
+pub fn bulletproof_gens(
+    gens_capacity: u32,
+    party_capacity: u32,
+    expected_digest: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::BulletproofGens {
+            gens_capacity,
+            party_capacity,
+            expected_digest,
+        }
+        .pack(),
+    }
+}
+
+/// The digest of all generators that is checked by the `BulletproofGens` instruction
+pub fn bulletproof_gens_digest(bp_gens: &BulletproofGens) -> [u8; 32] {
+    let points = bp_gens
+        .G(bp_gens.gens_capacity, bp_gens.party_capacity)
+        .chain(bp_gens.H(bp_gens.gens_capacity, bp_gens.party_capacity))
+        .map(|point| point.to_bytes())
+        .collect::<Vec<_>>();
+    let points = points
+        .iter()
+        .map(|point| point.as_slice())
+        .collect::<Vec<_>>();
+    hashv(&points).to_bytes()
+}
+
+/// Create a `BoomerangTestInstructions::PedersenGensFromLabel` instruction
+pub fn pedersen_gens_from_label(label: Vec<u8>, expected_gens: PedersenGens) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::PedersenGensFromLabel {
+            label,
+            expected_gens,
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::InnerProductProof` instruction
+pub fn inner_product_proof(
+    n: u32,
+    commitment: &RistrettoPoint,
+    product_base: &RistrettoPoint,
+    proof: &InnerProductProof,
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::InnerProductProof {
+            n,
+            commitment: *commitment,
+            product_base: *product_base,
+            proof: proof.to_bytes(),
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::RangeProofSingle` instruction
+pub fn range_proof_single(n: u32, commitment: &RistrettoPoint, proof: &RangeProof) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::RangeProofSingle {
+            n,
+            commitment: *commitment,
+            proof: proof.to_bytes(),
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::RangeProofMultiple` instruction
+pub fn range_proof_multiple(
+    n: u32,
+    commitments: &[RistrettoPoint],
+    proof: &RangeProof,
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::RangeProofMultiple {
+            n,
+            commitments: commitments.to_vec(),
+            proof: proof.to_bytes(),
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::BatchedRangeProof` instruction from a serialized
+/// `BatchedRangeProofU64`, `BatchedRangeProofU128`, or `BatchedRangeProofU256`
+pub fn batched_range_proof(commitments: &[(RistrettoPoint, u32)], proof: &[u8]) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::BatchedRangeProof {
+            commitments: commitments.to_vec(),
+            proof: proof.to_vec(),
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::RangeProofBatch` instruction
+pub fn range_proof_batch(n: u32, proofs: &[(&[RistrettoPoint], &RangeProof)]) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::RangeProofBatch {
+            n,
+            proofs: proofs
+                .iter()
+                .map(|(commitments, proof)| (commitments.to_vec(), proof.to_bytes()))
+                .collect(),
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::RangeProofInterval` instruction
+pub fn range_proof_interval(
+    a: u64,
+    b: u64,
+    commitment: &RistrettoPoint,
+    proof: &RangeProof,
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::RangeProofInterval {
+            a,
+            b,
+            commitment: *commitment,
+            proof: proof.to_bytes(),
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::R1CSProof` instruction
+pub fn r1cs_proof(commitments: &[RistrettoPoint], proof: &R1CSProof) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::R1CSProof {
+            commitments: commitments.to_vec(),
+            proof: proof.to_bytes(),
+        }
+        .pack(),
+    }
+}
+
+/// Create a `BoomerangTestInstructions::R1CSShuffleProof` instruction
+pub fn r1cs_shuffle_proof(
+    inputs: &[RistrettoPoint],
+    outputs: &[RistrettoPoint],
+    proof: &R1CSProof,
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: vec![],
+        data: BoomerangTestInstructions::R1CSShuffleProof {
+            inputs: inputs.to_vec(),
+            outputs: outputs.to_vec(),
+            proof: proof.to_bytes(),
+        }
+        .pack(),
+    }
+}
+
+/// The number of multipliers of the `R1CSShuffleProof` instruction for `k` inputs, rounded up to
+/// a power of two
+pub fn r1cs_shuffle_multipliers(k: usize) -> usize {
+    (2 * k.saturating_sub(1)).next_power_of_two()
+}
+
+/// The gadget of the `R1CSProof` instruction, which constrains the product of all variables but
+/// the last to equal the last with one multiplier per factor after the first
+pub fn r1cs_product_gadget<CS: ConstraintSystem>(
+    cs: &mut CS,
+    variables: &[Variable],
+) -> Result<(), String> {
+    let (last, factors) = variables
+        .split_last()
+        .ok_or_else(|| "Product gadget has no variables".to_string())?;
+    let (first, factors) = factors
+        .split_first()
+        .ok_or_else(|| "Product gadget has no factors".to_string())?;
+
+    let mut product = *first;
+    for factor in factors {
+        let (_, _, output) = cs.multiply(product.into(), (*factor).into())?;
+        product = output;
+    }
+    cs.constrain(product - *last);
+
+    Ok(())
+}
+
 solana_program::entrypoint!(process_instruction);
 pub fn process_instruction(
-    _program_id: &Pubkey,
-    _accounts: &[AccountInfo],
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
     let instruction = BoomerangTestInstructions::unpack(instruction_data)?;
@@ -339,6 +1434,457 @@ pub fn process_instruction(
                 .map_err(|_| ProgramError::InvalidInstructionData)?;
             msg!("Result: {:?}", result.to_bytes());
 
+            Ok(())
+        }
+        BoomerangTestInstructions::TranscriptComputeUnits { num_appends } => {
+            msg!("Instruction: TranscriptComputeUnits");
+
+            let message = [0; 32];
+
+            sol_log_compute_units();
+            let mut transcript = Transcript::new(b"example label");
+            for _ in 0..num_appends {
+                transcript.append_message(b"sample label", &message);
+            }
+
+            let mut challenge_bytes = [0; 32];
+            transcript.challenge_bytes(b"sample challenge", &mut challenge_bytes);
+            sol_log_compute_units();
+
+            msg!("Challenge bytes: {:?}", challenge_bytes);
+
+            Ok(())
+        }
+        BoomerangTestInstructions::TranscriptStore { message } => {
+            msg!("Instruction: TranscriptStore");
+
+            let account_info_iter = &mut accounts.iter();
+            let transcript_account_info = next_account_info(account_info_iter)?;
+
+            let mut transcript = Transcript::new(b"example label");
+            transcript.append_message(b"sample label", &message);
+
+            let mut transcript_account_data = transcript_account_info.try_borrow_mut_data()?;
+            if transcript_account_data.len() != TRANSCRIPT_LEN {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            transcript_account_data.copy_from_slice(&transcript.to_bytes());
+
+            Ok(())
+        }
+        BoomerangTestInstructions::TranscriptResume {
+            message,
+            expected_challenge,
+        } => {
+            msg!("Instruction: TranscriptResume");
+
+            let account_info_iter = &mut accounts.iter();
+            let transcript_account_info = next_account_info(account_info_iter)?;
+
+            let mut transcript_account_data = transcript_account_info.try_borrow_mut_data()?;
+            let mut transcript = Transcript::from_bytes(&transcript_account_data)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+            transcript.append_message(b"sample label", &message);
+
+            let mut challenge_bytes = [0; 32];
+            transcript.challenge_bytes(b"sample challenge", &mut challenge_bytes);
+            if challenge_bytes != expected_challenge {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            msg!("Transcript digest: {:?}", transcript.finalize());
+
+            transcript_account_data.copy_from_slice(&transcript.to_bytes());
+
+            Ok(())
+        }
+        BoomerangTestInstructions::TranscriptTrace {
+            message,
+            append_u64,
+        } => {
+            msg!("Instruction: TranscriptTrace");
+
+            let mut transcript = Transcript::new_with_trace(b"example label");
+            transcript.append_message(b"sample label", &message);
+            transcript.append_u64(b"sample label", append_u64);
+
+            let mut challenge_bytes = [0; 32];
+            transcript.challenge_bytes(b"sample challenge", &mut challenge_bytes);
+
+            Ok(())
+        }
+        BoomerangTestInstructions::TranscriptDomainSeparator {
+            proof_index,
+            expected_challenge,
+        } => {
+            msg!("Instruction: TranscriptDomainSeparator");
+
+            let mut transcript = domain_separated_transcript(program_id, proof_index);
+
+            let mut challenge_bytes = [0; 32];
+            transcript.challenge_bytes(b"sample challenge", &mut challenge_bytes);
+            if challenge_bytes != expected_challenge {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            Ok(())
+        }
+        BoomerangTestInstructions::TranscriptExecutionContext {
+            recent_slot,
+            expected_challenge,
+        } => {
+            msg!("Instruction: TranscriptExecutionContext");
+
+            let (signer_info, target_account_infos) = accounts
+                .split_first()
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let context = ExecutionContext::from_account_infos(
+                program_id,
+                signer_info,
+                target_account_infos,
+            )?
+            .with_recent_slot(recent_slot);
+            context.check_recent_slot(Clock::get()?.slot, MAX_SLOT_AGE)?;
+            let mut transcript = execution_context_transcript(&context);
+
+            let mut challenge_bytes = [0; 32];
+            transcript.challenge_bytes(b"sample challenge", &mut challenge_bytes);
+            if challenge_bytes != expected_challenge {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            Ok(())
+        }
+        BoomerangTestInstructions::TranscriptAccountData { expected_challenge } => {
+            msg!("Instruction: TranscriptAccountData");
+
+            let account_data = accounts
+                .iter()
+                .map(|account_info| account_info.try_borrow_data())
+                .collect::<Result<Vec<_>, _>>()?;
+            let chunks = account_data
+                .iter()
+                .map(|data| &data[..])
+                .collect::<Vec<_>>();
+
+            let mut transcript = Transcript::new(b"example label");
+            transcript.append_message_chunks(b"account data", &chunks);
+
+            let mut challenge_bytes = [0; 32];
+            transcript.challenge_bytes(b"sample challenge", &mut challenge_bytes);
+            if challenge_bytes != expected_challenge {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            Ok(())
+        }
+        BoomerangTestInstructions::KnownAnswerTranscript { vector } => {
+            msg!("Instruction: KnownAnswerTranscript");
+
+            let vector = TranscriptVector::from_bytes(&vector)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            vector.replay().map_err(|err| {
+                msg!("{}", err);
+                ProgramError::InvalidInstructionData
+            })
+        }
+        BoomerangTestInstructions::KnownAnswerRistretto { vector } => {
+            msg!("Instruction: KnownAnswerRistretto");
+
+            let vector = RistrettoVector::from_bytes(&vector)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            vector.replay().map_err(|err| {
+                msg!("{}", err);
+                ProgramError::InvalidInstructionData
+            })
+        }
+        BoomerangTestInstructions::PedersenCommit {
+            value,
+            blinding,
+            expected_commitment,
+        } => {
+            msg!("Instruction: PedersenCommit");
+
+            let commitment = PedersenGens::default()
+                .commit(value, blinding)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            if commitment != expected_commitment {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            Ok(())
+        }
+        BoomerangTestInstructions::PedersenGensFromLabel {
+            label,
+            expected_gens,
+        } => {
+            msg!("Instruction: PedersenGensFromLabel");
+
+            if PedersenGens::from_label(&label) != expected_gens {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            Ok(())
+        }
+        BoomerangTestInstructions::BulletproofGens {
+            gens_capacity,
+            party_capacity,
+            expected_digest,
+        } => {
+            msg!("Instruction: BulletproofGens");
+
+            let bp_gens = BulletproofGens::new(gens_capacity as usize, party_capacity as usize);
+            if bulletproof_gens_digest(&bp_gens) != expected_digest {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            Ok(())
+        }
+        BoomerangTestInstructions::InnerProductProof {
+            n,
+            commitment,
+            product_base,
+            proof,
+        } => {
+            msg!("Instruction: InnerProductProof");
+
+            let n = n as usize;
+            let proof = InnerProductProofView::from_bytes(&proof)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            let bp_gens = BulletproofGens::new(n, 1);
+            let g_vec = bp_gens.share(0).G(n).copied().collect::<Vec<_>>();
+            let h_vec = bp_gens.share(0).H(n).copied().collect::<Vec<_>>();
+            let factors = vec![Scalar::one(); n];
+
+            sol_log_compute_units();
+            let mut transcript = Transcript::new(b"innerproducttest");
+            proof
+                .verify(
+                    n,
+                    &mut transcript,
+                    &factors,
+                    &factors,
+                    &commitment,
+                    &product_base,
+                    &g_vec,
+                    &h_vec,
+                )
+                .map_err(|err| {
+                    msg!("{}", err);
+                    ProgramError::InvalidInstructionData
+                })?;
+            sol_log_compute_units();
+
+            Ok(())
+        }
+        BoomerangTestInstructions::RangeProofSingle {
+            n,
+            commitment,
+            proof,
+        } => {
+            msg!("Instruction: RangeProofSingle");
+
+            let n = n as usize;
+            let proof = RangeProofView::from_bytes(&proof)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            let bp_gens = BulletproofGens::new(n, 1);
+            let pc_gens = PedersenGens::default();
+
+            sol_log_compute_units();
+            let mut transcript = Transcript::new(b"rangeprooftest");
+            proof
+                .verify_single(&bp_gens, &pc_gens, &mut transcript, &commitment, n)
+                .map_err(|err| {
+                    msg!("{}", err);
+                    ProgramError::InvalidInstructionData
+                })?;
+            sol_log_compute_units();
+
+            Ok(())
+        }
+        BoomerangTestInstructions::RangeProofMultiple {
+            n,
+            commitments,
+            proof,
+        } => {
+            msg!("Instruction: RangeProofMultiple");
+
+            let n = n as usize;
+            let m = commitments.len().next_power_of_two();
+            let proof = RangeProofView::from_bytes(&proof)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            let bp_gens = BulletproofGens::new(n, m);
+            let pc_gens = PedersenGens::default();
+
+            sol_log_compute_units();
+            let mut transcript = Transcript::new(b"rangeprooftest");
+            proof
+                .verify_multiple(&bp_gens, &pc_gens, &mut transcript, &commitments, n)
+                .map_err(|err| {
+                    msg!("{}", err);
+                    ProgramError::InvalidInstructionData
+                })?;
+            sol_log_compute_units();
+
+            Ok(())
+        }
+        BoomerangTestInstructions::BatchedRangeProof { commitments, proof } => {
+            msg!("Instruction: BatchedRangeProof");
+
+            let commitments = commitments
+                .into_iter()
+                .map(|(commitment, bit_length)| (commitment, bit_length as usize))
+                .collect::<Vec<_>>();
+            let proof_type = ProofBytes::parse(&proof)
+                .map_err(|_| ProgramError::InvalidInstructionData)?
+                .proof_type;
+            let total = match proof_type {
+                ProofType::BatchedRangeU64 => BatchedRangeProofU64::BIT_LENGTH,
+                ProofType::BatchedRangeU128 => BatchedRangeProofU128::BIT_LENGTH,
+                ProofType::BatchedRangeU256 => BatchedRangeProofU256::BIT_LENGTH,
+                _ => return Err(ProgramError::InvalidInstructionData),
+            };
+            let bp_gens = BulletproofGens::new(total, 1);
+            let pc_gens = PedersenGens::default();
+
+            sol_log_compute_units();
+            let mut transcript = Transcript::new(b"batchedrangeprooftest");
+            let result = match proof_type {
+                ProofType::BatchedRangeU64 => BatchedRangeProofU64View::from_bytes(&proof)
+                    .and_then(|proof| {
+                        proof.verify(&bp_gens, &pc_gens, &mut transcript, &commitments)
+                    }),
+                ProofType::BatchedRangeU128 => BatchedRangeProofU128View::from_bytes(&proof)
+                    .and_then(|proof| {
+                        proof.verify(&bp_gens, &pc_gens, &mut transcript, &commitments)
+                    }),
+                _ => BatchedRangeProofU256View::from_bytes(&proof).and_then(|proof| {
+                    proof.verify(&bp_gens, &pc_gens, &mut transcript, &commitments)
+                }),
+            };
+            result.map_err(|err| {
+                msg!("{}", err);
+                ProgramError::InvalidInstructionData
+            })?;
+            sol_log_compute_units();
+
+            Ok(())
+        }
+        BoomerangTestInstructions::RangeProofBatch { n, proofs } => {
+            msg!("Instruction: RangeProofBatch");
+
+            let n = n as usize;
+            let m = proofs
+                .iter()
+                .map(|(commitments, _)| commitments.len().next_power_of_two())
+                .max()
+                .unwrap_or(1);
+            let proofs = proofs
+                .into_iter()
+                .map(|(commitments, proof)| Ok((commitments, RangeProof::from_bytes(&proof)?)))
+                .collect::<Result<Vec<_>, String>>()
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            let bp_gens = BulletproofGens::new(n, m);
+            let pc_gens = PedersenGens::default();
+
+            sol_log_compute_units();
+            let mut transcripts = vec![Transcript::new(b"rangeprooftest"); proofs.len()];
+            let mut items = proofs
+                .iter()
+                .zip(&mut transcripts)
+                .map(|((commitments, proof), transcript)| (proof, &commitments[..], transcript))
+                .collect::<Vec<_>>();
+            RangeProof::batch_verify(&bp_gens, &pc_gens, &mut items, n).map_err(|err| {
+                msg!("{}", err);
+                ProgramError::InvalidInstructionData
+            })?;
+            sol_log_compute_units();
+
+            Ok(())
+        }
+        BoomerangTestInstructions::RangeProofInterval {
+            a,
+            b,
+            commitment,
+            proof,
+        } => {
+            msg!("Instruction: RangeProofInterval");
+
+            let n = interval_bit_size(a, b).map_err(|_| ProgramError::InvalidInstructionData)?;
+            let proof = RangeProofView::from_bytes(&proof)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            let bp_gens = BulletproofGens::new(n, 2);
+            let pc_gens = PedersenGens::default();
+
+            sol_log_compute_units();
+            let mut transcript = Transcript::new(b"rangeprooftest");
+            proof
+                .verify_interval(&bp_gens, &pc_gens, &mut transcript, &commitment, a, b)
+                .map_err(|err| {
+                    msg!("{}", err);
+                    ProgramError::InvalidInstructionData
+                })?;
+            sol_log_compute_units();
+
+            Ok(())
+        }
+        BoomerangTestInstructions::R1CSProof { commitments, proof } => {
+            msg!("Instruction: R1CSProof");
+
+            let n = commitments.len().saturating_sub(2).next_power_of_two();
+            let proof = R1CSProofView::from_bytes(&proof)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            let bp_gens = BulletproofGens::new(n, 1);
+            let pc_gens = PedersenGens::default();
+
+            sol_log_compute_units();
+            let mut transcript = Transcript::new(b"r1csprooftest");
+            let mut verifier = Verifier::new(&mut transcript);
+            let variables = commitments
+                .into_iter()
+                .map(|commitment| verifier.commit(commitment))
+                .collect::<Vec<_>>();
+            r1cs_product_gadget(&mut verifier, &variables)
+                .and_then(|()| verifier.verify_view(&proof, &pc_gens, &bp_gens))
+                .map_err(|err| {
+                    msg!("{}", err);
+                    ProgramError::InvalidInstructionData
+                })?;
+            sol_log_compute_units();
+
+            Ok(())
+        }
+        BoomerangTestInstructions::R1CSShuffleProof {
+            inputs,
+            outputs,
+            proof,
+        } => {
+            msg!("Instruction: R1CSShuffleProof");
+
+            let n = r1cs_shuffle_multipliers(inputs.len());
+            let proof = R1CSProofView::from_bytes(&proof)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            let bp_gens = BulletproofGens::new(n, 1);
+            let pc_gens = PedersenGens::default();
+
+            sol_log_compute_units();
+            let mut transcript = Transcript::new(b"r1csshuffletest");
+            let mut verifier = Verifier::new(&mut transcript);
+            let inputs = inputs
+                .into_iter()
+                .map(|commitment| verifier.commit(commitment))
+                .collect::<Vec<_>>();
+            let outputs = outputs
+                .into_iter()
+                .map(|commitment| verifier.commit(commitment))
+                .collect::<Vec<_>>();
+            gadgets::shuffle(&mut verifier, &inputs, &outputs)
+                .and_then(|()| verifier.verify_view(&proof, &pc_gens, &bp_gens))
+                .map_err(|err| {
+                    msg!("{}", err);
+                    ProgramError::InvalidInstructionData
+                })?;
+            sol_log_compute_units();
+
             Ok(())
         }
     }
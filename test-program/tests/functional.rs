@@ -2,11 +2,35 @@
 
 use {
     boomerang_test_program::*,
+    solana_bulletproofs::{
+        batched_range_proof::BatchedRangeProofU128,
+        generators::{BulletproofGens, PedersenGens},
+        inner_product_proof::InnerProductProof,
+        r1cs::{gadgets, Prover, R1CSProof},
+        range_proof::{RangeProof, SUPPORTED_BIT_SIZES},
+        util,
+    },
+    solana_merlin::{
+        kat::TranscriptVector,
+        trace::{Trace, TraceEvent},
+        ExecutionContext, Transcript, TRANSCRIPT_LEN,
+    },
     solana_program_test::*,
-    solana_ristretto::{ristretto::RistrettoPoint, scalar::Scalar},
-    solana_sdk::{signature::Signer, transaction::Transaction},
+    solana_ristretto::{kat::RistrettoVector, ristretto::RistrettoPoint, scalar::Scalar},
+    solana_sdk::{
+        account::Account,
+        clock::Clock,
+        compute_budget::ComputeBudgetInstruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
 };
 
+/// The compute unit limit of the transactions that verify proofs, which is the maximum that a
+/// transaction can request.
+const COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
 fn program_test() -> ProgramTest {
     ProgramTest::new(
         "boomerang_test_program",
@@ -35,6 +59,882 @@ async fn test_transcript() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_transcript_store_and_resume() {
+    let transcript_account = Keypair::new();
+
+    let mut program_test = program_test();
+    program_test.add_account(
+        transcript_account.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![0; TRANSCRIPT_LEN],
+            owner: id(),
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let first_message = b"first_message".to_vec();
+    let second_message = b"second_message".to_vec();
+
+    // the challenge that results from running the transcript in a single instruction
+    let mut transcript = Transcript::new(b"example label");
+    transcript.append_message(b"sample label", &first_message);
+    transcript.append_message(b"sample label", &second_message);
+    let mut expected_challenge = [0; 32];
+    transcript.challenge_bytes(b"sample challenge", &mut expected_challenge);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            transcript_store(&transcript_account.pubkey(), first_message),
+            transcript_resume(
+                &transcript_account.pubkey(),
+                second_message,
+                expected_challenge,
+            ),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let transcript_account_data = context
+        .banks_client
+        .get_account(transcript_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let stored_transcript = Transcript::from_bytes(&transcript_account_data).unwrap();
+    assert_eq!(stored_transcript.finalize(), transcript.finalize());
+}
+
+#[tokio::test]
+async fn test_transcript_trace() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_message = b"test_message".to_vec();
+    let test_u64 = 77_u64;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[transcript_trace(test_message.clone(), test_u64)],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let log_messages = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap()
+        .metadata
+        .unwrap()
+        .log_messages;
+    let onchain_trace = Trace::from_log_messages(&log_messages).unwrap();
+
+    // an off-chain transcript with the same operations produces the same trace
+    let mut transcript = Transcript::new_with_trace(b"example label");
+    transcript.append_message(b"sample label", &test_message);
+    transcript.append_u64(b"sample label", test_u64);
+    let mut challenge_bytes = [0; 32];
+    transcript.challenge_bytes(b"sample challenge", &mut challenge_bytes);
+
+    let offchain_trace = transcript.trace().unwrap();
+    assert_eq!(onchain_trace.events().len(), 4);
+    assert_eq!(offchain_trace.first_divergence(&onchain_trace), None);
+
+    // an off-chain transcript with a different `u64` diverges at the third operation
+    let mut transcript = Transcript::new_with_trace(b"example label");
+    transcript.append_message(b"sample label", &test_message);
+    transcript.append_u64(b"sample label", test_u64 + 1);
+    let mut challenge_bytes = [0; 32];
+    transcript.challenge_bytes(b"sample challenge", &mut challenge_bytes);
+
+    let divergence = transcript
+        .trace()
+        .unwrap()
+        .first_divergence(&onchain_trace)
+        .unwrap();
+    assert_eq!(divergence.index, 2);
+    assert!(matches!(
+        divergence.right,
+        Some(TraceEvent::AppendMessage { length: 8, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_transcript_domain_separator() {
+    let mut context = program_test().start_with_context().await;
+
+    let proof_index = 3;
+
+    let mut transcript = domain_separated_transcript(&id(), proof_index);
+    let mut expected_challenge = [0; 32];
+    transcript.challenge_bytes(b"sample challenge", &mut expected_challenge);
+
+    // a transcript for a different proof index derives a different challenge
+    let mut other_transcript = domain_separated_transcript(&id(), proof_index + 1);
+    let mut other_challenge = [0; 32];
+    other_transcript.challenge_bytes(b"sample challenge", &mut other_challenge);
+    assert_ne!(expected_challenge, other_challenge);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[transcript_domain_separator(proof_index, expected_challenge)],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_transcript_execution_context() {
+    let mut context = program_test().start_with_context().await;
+
+    let program_id = id();
+    let payer = context.payer.pubkey();
+    let target_account = Pubkey::new_unique();
+    let recent_slot = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .slot;
+
+    let execution_context = ExecutionContext::new(&program_id, &payer)
+        .with_account(&target_account)
+        .with_recent_slot(recent_slot);
+    let mut transcript = execution_context_transcript(&execution_context);
+    let mut expected_challenge = [0; 32];
+    transcript.challenge_bytes(b"sample challenge", &mut expected_challenge);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[transcript_execution_context(
+            &payer,
+            &[target_account],
+            recent_slot,
+            expected_challenge,
+        )],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    // replaying the proof against a different account fails
+    let other_account = Pubkey::new_unique();
+    let transaction = Transaction::new_signed_with_payer(
+        &[transcript_execution_context(
+            &payer,
+            &[other_account],
+            recent_slot,
+            expected_challenge,
+        )],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+
+    // replaying the proof with a different signer fails
+    let other_signer = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[transcript_execution_context(
+            &other_signer.pubkey(),
+            &[target_account],
+            recent_slot,
+            expected_challenge,
+        )],
+        Some(&payer),
+        &[&context.payer, &other_signer],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+
+    // replaying the proof after the slot has become stale fails
+    context
+        .warp_to_slot(recent_slot + MAX_SLOT_AGE + 1)
+        .unwrap();
+    let last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[transcript_execution_context(
+            &payer,
+            &[target_account],
+            recent_slot,
+            expected_challenge,
+        )],
+        Some(&payer),
+        &[&context.payer],
+        last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_transcript_account_data() {
+    let first_data = (0..700).map(|i| i as u8).collect::<Vec<_>>();
+    let second_data = (0..300).map(|i| (i * 3) as u8).collect::<Vec<_>>();
+
+    let mut program_test = program_test();
+    let data_accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+    for (data_account, data) in data_accounts.iter().zip([&first_data, &second_data]) {
+        program_test.add_account(
+            *data_account,
+            Account {
+                lamports: 1_000_000_000,
+                data: data.clone(),
+                owner: id(),
+                ..Account::default()
+            },
+        );
+    }
+    let mut context = program_test.start_with_context().await;
+
+    // the data of both accounts is appended as if it were a single contiguous message
+    let mut transcript = Transcript::new(b"example label");
+    transcript.append_message(b"account data", &[first_data, second_data].concat());
+    let mut expected_challenge = [0; 32];
+    transcript.challenge_bytes(b"sample challenge", &mut expected_challenge);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[transcript_account_data(&data_accounts, expected_challenge)],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_known_answer_transcript() {
+    let mut context = program_test().start_with_context().await;
+
+    let vectors = TranscriptVector::parse_file(include_str!(
+        "../../solana-merlin/tests/vectors/transcript-v1.kat"
+    ))
+    .unwrap();
+    for vector in vectors {
+        vector.replay().unwrap();
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[known_answer_transcript(&vector)],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_known_answer_ristretto() {
+    let mut context = program_test().start_with_context().await;
+
+    let vectors = RistrettoVector::parse_file(include_str!(
+        "../../solana-ristretto/tests/vectors/ristretto-v1.kat"
+    ))
+    .unwrap();
+    for vector in vectors {
+        vector.replay().unwrap();
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[known_answer_ristretto(&vector)],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_pedersen_commit() {
+    let mut context = program_test().start_with_context().await;
+
+    let mut value = [0; 32];
+    value[..8].copy_from_slice(&55_u64.to_le_bytes());
+    let value = Scalar::from_bytes(&value).unwrap();
+    let blinding = Scalar::from_bytes(&[
+        29, 94, 215, 225, 101, 59, 14, 208, 160, 87, 210, 199, 211, 243, 64, 193, 6, 78, 44, 32,
+        37, 214, 56, 148, 43, 122, 232, 106, 110, 145, 103, 8,
+    ])
+    .unwrap();
+    let expected_commitment = PedersenGens::default().commit(value, blinding).unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[pedersen_commit(value, blinding, expected_commitment)],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_pedersen_gens_from_label() {
+    let mut context = program_test().start_with_context().await;
+
+    let label = b"boomerang-test".to_vec();
+    let expected_gens = PedersenGens::from_label(&label);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[pedersen_gens_from_label(label, expected_gens)],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_bulletproof_gens() {
+    let mut context = program_test().start_with_context().await;
+
+    let expected_digest = bulletproof_gens_digest(&BulletproofGens::new(8, 2));
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[bulletproof_gens(8, 2, expected_digest)],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+fn inner_product_instance(n: usize) -> (RistrettoPoint, RistrettoPoint, InnerProductProof) {
+    let bp_gens = BulletproofGens::new(n, 1);
+    let g_vec = bp_gens.share(0).G(n).copied().collect::<Vec<_>>();
+    let h_vec = bp_gens.share(0).H(n).copied().collect::<Vec<_>>();
+    let product_base = PedersenGens::default().B_blinding();
+    let a = (0..n as u64)
+        .map(|i| Scalar::from(i + 1))
+        .collect::<Vec<_>>();
+    let b = (0..n as u64)
+        .map(|i| Scalar::from(2 * i + 3))
+        .collect::<Vec<_>>();
+    let factors = vec![Scalar::one(); n];
+
+    let scalars = [&a[..], &b[..], &[util::inner_product(&a, &b).unwrap()]].concat();
+    let points = [&g_vec[..], &h_vec[..], &[product_base]].concat();
+    let commitment = RistrettoPoint::multiscalar_multiply(&scalars, &points).unwrap();
+
+    let proof = InnerProductProof::create(
+        &mut Transcript::new(b"innerproducttest"),
+        &product_base,
+        &factors,
+        &factors,
+        g_vec,
+        h_vec,
+        a,
+        b,
+    )
+    .unwrap();
+    (commitment, product_base, proof)
+}
+
+#[tokio::test]
+async fn test_inner_product_proof() {
+    let mut context = program_test().start_with_context().await;
+
+    let (commitment, product_base, proof) = inner_product_instance(16);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            inner_product_proof(16, &commitment, &product_base, &proof),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_inner_product_proof_rejects_wrong_commitment() {
+    let mut context = program_test().start_with_context().await;
+
+    let (commitment, product_base, proof) = inner_product_instance(16);
+    let commitment = commitment.add(&product_base).unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            inner_product_proof(16, &commitment, &product_base, &proof),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_range_proof_single() {
+    // the handler derives the generators on-chain, so the consumed units include their derivation
+    for n in SUPPORTED_BIT_SIZES {
+        let mut context = program_test().start_with_context().await;
+
+        let (proof, commitment) = RangeProof::prove_single(
+            &BulletproofGens::new(n, 1),
+            &PedersenGens::default(),
+            &mut Transcript::new(b"rangeprooftest"),
+            255,
+            &Scalar::from(77),
+            n,
+        )
+        .unwrap();
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+                range_proof_single(n as u32, &commitment, &proof),
+            ],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let result = context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        result.result.unwrap();
+        let compute_units = result.metadata.unwrap().compute_units_consumed;
+        assert!(compute_units <= u64::from(COMPUTE_UNIT_LIMIT));
+    }
+}
+
+#[tokio::test]
+async fn test_range_proof_single_rejects_wrong_commitment() {
+    let mut context = program_test().start_with_context().await;
+
+    let (proof, _) = RangeProof::prove_single(
+        &BulletproofGens::new(32, 1),
+        &PedersenGens::default(),
+        &mut Transcript::new(b"rangeprooftest"),
+        255,
+        &Scalar::from(77),
+        32,
+    )
+    .unwrap();
+    let commitment = PedersenGens::default()
+        .commit(Scalar::from(256), Scalar::from(77))
+        .unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            range_proof_single(32, &commitment, &proof),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_range_proof_multiple() {
+    let mut context = program_test().start_with_context().await;
+
+    let values = [1, 2, 255];
+    let blindings = [Scalar::from(11), Scalar::from(22), Scalar::from(33)];
+    let (proof, commitments) = RangeProof::prove_multiple(
+        &BulletproofGens::new(16, 4),
+        &PedersenGens::default(),
+        &mut Transcript::new(b"rangeprooftest"),
+        &values,
+        &blindings,
+        16,
+    )
+    .unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            range_proof_multiple(16, &commitments, &proof),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_range_proof_batch() {
+    let mut context = program_test().start_with_context().await;
+
+    let bp_gens = BulletproofGens::new(16, 2);
+    let pc_gens = PedersenGens::default();
+    let (first_proof, first_commitments) = RangeProof::prove_single(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"rangeprooftest"),
+        1000,
+        &Scalar::from(11),
+        16,
+    )
+    .map(|(proof, commitment)| (proof, vec![commitment]))
+    .unwrap();
+    let (second_proof, second_commitments) = RangeProof::prove_multiple(
+        &bp_gens,
+        &pc_gens,
+        &mut Transcript::new(b"rangeprooftest"),
+        &[1, 2],
+        &[Scalar::from(22), Scalar::from(33)],
+        16,
+    )
+    .unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            range_proof_batch(
+                16,
+                &[
+                    (&first_commitments[..], &first_proof),
+                    (&second_commitments[..], &second_proof),
+                ],
+            ),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+async fn process_range_proof_interval(
+    v: u64,
+    proof_interval: (u64, u64),
+    verify_interval: (u64, u64),
+) -> Result<(), BanksClientError> {
+    let mut context = program_test().start_with_context().await;
+
+    let (a, b) = proof_interval;
+    let (proof, commitment) = RangeProof::prove_interval(
+        &BulletproofGens::new(16, 2),
+        &PedersenGens::default(),
+        &mut Transcript::new(b"rangeprooftest"),
+        v,
+        &Scalar::from(11),
+        a,
+        b,
+    )
+    .unwrap();
+
+    let (a, b) = verify_interval;
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            range_proof_interval(a, b, &commitment, &proof),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+#[tokio::test]
+async fn test_range_proof_interval() {
+    process_range_proof_interval(1500, (1000, 5000), (1000, 5000))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_range_proof_interval_rejects_wrong_interval() {
+    assert!(
+        process_range_proof_interval(1500, (1000, 5000), (1000, 2000))
+            .await
+            .is_err()
+    );
+}
+
+fn prove_product(values: &[u64]) -> (R1CSProof, Vec<RistrettoPoint>) {
+    let pc_gens = PedersenGens::default();
+    let mut transcript = Transcript::new(b"r1csprooftest");
+    let mut prover = Prover::new(&pc_gens, &mut transcript);
+    let (commitments, variables): (Vec<_>, Vec<_>) = values
+        .iter()
+        .zip(1u64..)
+        .map(|(v, blinding)| {
+            prover
+                .commit(Scalar::from(*v), Scalar::from(blinding))
+                .unwrap()
+        })
+        .unzip();
+    r1cs_product_gadget(&mut prover, &variables).unwrap();
+    let n = values.len().saturating_sub(2).next_power_of_two();
+    let proof = prover.prove(&BulletproofGens::new(n, 1)).unwrap();
+    (proof, commitments)
+}
+
+#[tokio::test]
+async fn test_r1cs_proof() {
+    let mut context = program_test().start_with_context().await;
+
+    let (proof, commitments) = prove_product(&[2, 3, 5, 7, 210]);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            r1cs_proof(&commitments, &proof),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_r1cs_proof_rejects_wrong_product() {
+    let mut context = program_test().start_with_context().await;
+
+    let (proof, mut commitments) = prove_product(&[2, 3, 5, 7, 210]);
+    commitments.swap(0, 4);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            r1cs_proof(&commitments, &proof),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+}
+
+fn prove_shuffle(
+    inputs: &[u64],
+    outputs: &[u64],
+) -> (R1CSProof, Vec<RistrettoPoint>, Vec<RistrettoPoint>) {
+    let pc_gens = PedersenGens::default();
+    let mut transcript = Transcript::new(b"r1csshuffletest");
+    let mut prover = Prover::new(&pc_gens, &mut transcript);
+    let mut commit = |values: &[u64], first_blinding: u64| -> (Vec<_>, Vec<_>) {
+        values
+            .iter()
+            .zip(first_blinding..)
+            .map(|(v, blinding)| {
+                prover
+                    .commit(Scalar::from(*v), Scalar::from(blinding))
+                    .unwrap()
+            })
+            .unzip()
+    };
+    let (input_commitments, input_variables) = commit(inputs, 1);
+    let (output_commitments, output_variables) = commit(outputs, 101);
+    gadgets::shuffle(&mut prover, &input_variables, &output_variables).unwrap();
+    let n = r1cs_shuffle_multipliers(inputs.len());
+    let proof = prover.prove(&BulletproofGens::new(n, 1)).unwrap();
+    (proof, input_commitments, output_commitments)
+}
+
+#[tokio::test]
+async fn test_r1cs_shuffle_proof() {
+    let mut context = program_test().start_with_context().await;
+
+    let (proof, inputs, outputs) =
+        prove_shuffle(&[3, 1, 4, 1, 5, 9, 2, 6], &[6, 1, 9, 2, 4, 5, 1, 3]);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            r1cs_shuffle_proof(&inputs, &outputs, &proof),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result.unwrap();
+    let compute_units = result.metadata.unwrap().compute_units_consumed;
+    assert!(compute_units <= u64::from(COMPUTE_UNIT_LIMIT));
+}
+
+#[tokio::test]
+async fn test_r1cs_shuffle_proof_rejects_other_commitments() {
+    let mut context = program_test().start_with_context().await;
+
+    let (proof, mut inputs, outputs) =
+        prove_shuffle(&[3, 1, 4, 1, 5, 9, 2, 6], &[6, 1, 9, 2, 4, 5, 1, 3]);
+    inputs[0] = outputs[0];
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            r1cs_shuffle_proof(&inputs, &outputs, &proof),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_batched_range_proof() {
+    let mut context = program_test().start_with_context().await;
+
+    let values = [u64::MAX, 1 << 20, 255];
+    let blindings = [Scalar::from(11), Scalar::from(22), Scalar::from(33)];
+    let bit_lengths = [64, 32, 32];
+    let (proof, commitments) = BatchedRangeProofU128::new(
+        &BulletproofGens::new(128, 1),
+        &PedersenGens::default(),
+        &mut Transcript::new(b"batchedrangeprooftest"),
+        &values,
+        &blindings,
+        &bit_lengths,
+    )
+    .unwrap();
+    let commitments = commitments
+        .into_iter()
+        .zip(bit_lengths.map(|n| n as u32))
+        .collect::<Vec<_>>();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            batched_range_proof(&commitments, &proof.to_bytes()),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_batched_range_proof_rejects_wrong_bit_lengths() {
+    let mut context = program_test().start_with_context().await;
+
+    let (proof, commitments) = BatchedRangeProofU128::new(
+        &BulletproofGens::new(128, 1),
+        &PedersenGens::default(),
+        &mut Transcript::new(b"batchedrangeprooftest"),
+        &[1, 2, 3],
+        &[Scalar::from(11), Scalar::from(22), Scalar::from(33)],
+        &[64, 32, 32],
+    )
+    .unwrap();
+    let commitments = commitments
+        .into_iter()
+        .zip([32, 64, 32])
+        .collect::<Vec<_>>();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            batched_range_proof(&commitments, &proof.to_bytes()),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_err());
+}
+
 #[tokio::test]
 async fn test_add_ristretto() {
     let mut context = program_test().start_with_context().await;
@@ -159,3 +1059,40 @@ async fn test_multiscalar_multiply_ristretto() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn test_transcript_compute_units() {
+    let mut context = program_test().start_with_context().await;
+
+    // the hash syscalls of the original format, which absorbed every message with its own hash,
+    // already cost more than the whole sequence is allowed to cost
+    for (num_appends, max_compute_units) in [(1, 1_190), (16, 6_725), (64, 24_437)] {
+        let transaction = Transaction::new_signed_with_payer(
+            &[transcript_compute_units(num_appends)],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let log_messages = context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap()
+            .metadata
+            .unwrap()
+            .log_messages;
+
+        let remaining = log_messages
+            .iter()
+            .filter_map(|log| {
+                log.strip_prefix("Program consumption: ")?
+                    .strip_suffix(" units remaining")?
+                    .parse::<u64>()
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(remaining.len(), 2);
+        let compute_units = remaining[0] - remaining[1];
+        assert!(compute_units <= max_compute_units);
+    }
+}